@@ -5,6 +5,8 @@ sol!(
     pragma solidity ^0.8.25;
 
     interface IEndpointV2 {
+        event PacketSent(bytes encodedPayload, bytes options, address sendLibrary);
+
         function send(
             uint32 dstEid,
             bytes calldata receiver,
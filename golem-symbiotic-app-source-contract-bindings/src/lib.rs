@@ -1 +1 @@
-pub mod app_src;
\ No newline at end of file
+pub mod app_src;
@@ -0,0 +1,708 @@
+use alloy_primitives::{keccak256, B256};
+use golem_symbiotic_consensus_mpt_types::public_values::decode_chain_head;
+use golem_symbiotic_consensus_mpt_types::types::{
+    AggregatedPublicValues, AggregationMigration, AggregationProofInputs, AggregationProofOutputs, ProofOutputs,
+    RotationProofOutputs,
+};
+use std::fmt;
+
+/// Custom error type for aggregation failures
+#[derive(Debug)]
+pub enum AggregationError {
+    /// Error when no transition proofs are given
+    NoTransitions,
+    /// Error when the public values of a transition cannot be decoded
+    InvalidTransitionOutputs { index: usize, reason: String },
+    /// Error when a transition does not continue from the previous transition's store hash
+    BrokenHashChain {
+        index: usize,
+        expected: B256,
+        actual: B256,
+    },
+    /// Error when a transition does not start at the previous transition's output slot
+    BrokenSlotChain {
+        index: usize,
+        expected: u64,
+        actual: u64,
+    },
+    /// Error when a transition is bound to a different network than the first transition
+    NetworkMismatch {
+        index: usize,
+        expected: B256,
+        actual: B256,
+    },
+    /// Error when a transition enforced a different sync committee participation minimum than the first transition
+    ParticipationThresholdMismatch {
+        index: usize,
+        expected: u64,
+        actual: u64,
+    },
+    /// Error when a transition was proven by a different program version than the first transition
+    ProgramVersionMismatch {
+        index: usize,
+        expected: u32,
+        actual: u32,
+    },
+    /// Error when the previous version's aggregation cannot be migrated from
+    InvalidMigration { reason: String },
+}
+
+impl fmt::Display for AggregationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AggregationError::NoTransitions => write!(f, "No transition proofs to aggregate"),
+            AggregationError::InvalidTransitionOutputs { index, reason } => write!(
+                f,
+                "Public values of transition {} could not be decoded: {}",
+                index, reason
+            ),
+            AggregationError::BrokenHashChain {
+                index,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "Transition {} input store hash {:?} does not match the previous output store hash {:?}",
+                index, actual, expected
+            ),
+            AggregationError::BrokenSlotChain {
+                index,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "Transition {} input slot {} does not match the previous output slot {}",
+                index, actual, expected
+            ),
+            AggregationError::NetworkMismatch {
+                index,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "Transition {} network identity digest {:?} does not match the first transition's {:?}",
+                index, actual, expected
+            ),
+            AggregationError::ParticipationThresholdMismatch {
+                index,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "Transition {} minimum sync committee participation {} does not match the first transition's {}",
+                index, actual, expected
+            ),
+            AggregationError::ProgramVersionMismatch {
+                index,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "Transition {} program version {} does not match the first transition's {}",
+                index, actual, expected
+            ),
+            AggregationError::InvalidMigration { reason } => {
+                write!(f, "Invalid migration from the previous program version: {}", reason)
+            }
+        }
+    }
+}
+
+impl std::error::Error for AggregationError {}
+
+/// Big endian bytes of a verification key hash given as eight u32 words.
+pub fn vkey_to_bytes32(vkey: &[u32; 8]) -> B256 {
+    let mut bytes = [0u8; 32];
+    for (i, word) in vkey.iter().enumerate() {
+        bytes[i * 4..(i + 1) * 4].copy_from_slice(&word.to_be_bytes());
+    }
+    B256::from(bytes)
+}
+
+/// Chain fields shared by the public values of transitions and sync committee rotations.
+#[derive(Clone)]
+struct ChainLink {
+    network_identity_digest: B256,
+    input_slot: u64,
+    input_store_hash: B256,
+    output_slot: u64,
+    output_store_hash: B256,
+    program_version: u32,
+    transition: Option<ProofOutputs>, // `None` for a rotation
+}
+
+impl ChainLink {
+    fn decode(index: usize, public_values: &AggregatedPublicValues) -> Result<Self, AggregationError> {
+        match public_values {
+            AggregatedPublicValues::Transition(bytes) => {
+                let outputs = ProofOutputs::from_bytes(bytes).map_err(|e| {
+                    AggregationError::InvalidTransitionOutputs {
+                        index,
+                        reason: e.to_string(),
+                    }
+                })?;
+                Ok(Self {
+                    network_identity_digest: outputs.network_identity_digest,
+                    input_slot: outputs.input_slot,
+                    input_store_hash: outputs.input_store_hash,
+                    output_slot: outputs.output_slot,
+                    output_store_hash: outputs.output_store_hash,
+                    program_version: outputs.program_version,
+                    transition: Some(outputs),
+                })
+            }
+            AggregatedPublicValues::Rotation(bytes) => {
+                let outputs = RotationProofOutputs::from_bytes(bytes).map_err(|e| {
+                    AggregationError::InvalidTransitionOutputs {
+                        index,
+                        reason: e.to_string(),
+                    }
+                })?;
+                Ok(Self {
+                    network_identity_digest: outputs.network_identity_digest,
+                    input_slot: outputs.input_slot,
+                    input_store_hash: outputs.input_store_hash,
+                    output_slot: outputs.output_slot,
+                    output_store_hash: outputs.output_store_hash,
+                    program_version: outputs.program_version,
+                    transition: None,
+                })
+            }
+        }
+    }
+}
+
+/// Aggregates the public values of consecutive transition and sync committee rotation proofs into a single
+/// transition.
+///
+/// The recursive verification of each proof against `transition_vkey` or `rotation_vkey` (and of the previous
+/// version's aggregation proof when migrating) is performed by the zkVM entrypoint; this function checks that the
+/// verified proofs form an unbroken chain and commits to them.
+///
+/// # Inputs
+/// | Name                       | Type         | Description                                      |
+/// |----------------------------|--------------|--------------------------------------------------|
+/// | `transition_vkey`          | `[u32; 8]`   | Verification key hash of the transition program  |
+/// | `rotation_vkey`            | `[u32; 8]`   | Verification key hash of the rotation program    |
+/// | `transition_public_values` | `Vec<AggregatedPublicValues>` | Public values of each proof, in order |
+/// | `migration`                | `Option<AggregationMigration>` | Previous program version's aggregation to continue from |
+///
+/// # Operations
+/// 1. **Decode** each entry's public values as `ProofOutputs` (transition) or `RotationProofOutputs` (rotation)
+/// 2. **Chain Check**: for every entry after the first assert
+///    - `input_store_hash == previous.output_store_hash`
+///    - `input_slot == previous.output_slot`
+///    - `network_identity_digest == first.network_identity_digest`
+///    - `program_version == first.program_version`
+///    - For transitions, `min_sync_committee_participants` equals the first transition's
+///    - With `migration`, the first entry continues the previous aggregation: same network,
+///      `first.input_store_hash == previous.output_store_hash` and `first.input_slot == previous.output_slot`
+/// 3. **Commit**
+///    - `execution_state_roots_hash` = `keccak256(execution_state_root_0 ‖ … ‖ execution_state_root_n)` over the
+///      transitions only, as rotations carry no execution state
+///    - `transition_outputs_hash` = `keccak256(keccak256(public_values_0) ‖ … ‖ keccak256(public_values_n))` over
+///      every entry, so that any transition's commitments (storage, receipts, ...) can be opened against the aggregate
+///    - `sync_committee_participants` = the lowest `sync_committee_participants` of the transitions, zero without
+///      transitions
+///    - `forced_update` = whether any transition is a force update
+///    - `previous_aggregation_vkey` = `migration.previous_aggregation_vkey`, zero without migration
+///      (only committed: the previous vkey is not known to the program, verifiers must pin it next to the current one)
+///
+/// # Outputs
+/// | Field                        | Type   | Description                                   |
+/// |------------------------------|--------|-----------------------------------------------|
+/// | `network_identity_digest`    | `B256` | Network identity shared by every entry        |
+/// | `input_slot`                 | `u64`  | Input slot of the first entry                 |
+/// | `input_store_hash`           | `B256` | Input store hash of the first entry           |
+/// | `output_slot`                | `u64`  | Output slot of the last entry                 |
+/// | `output_store_hash`          | `B256` | Output store hash of the last entry           |
+/// | `transition_vkey`            | `B256` | Verification key the transitions were verified against |
+/// | `rotation_vkey`              | `B256` | Verification key the rotations were verified against |
+/// | `execution_state_roots_hash` | `B256` | Commitment to every transition's execution state root |
+/// | `transition_outputs_hash`    | `B256` | Commitment to every entry's public values     |
+/// | `n_transitions`              | `u64`  | Number of aggregated transitions and rotations |
+/// | `sync_committee_participants` | `u64` | Lowest finality update participation of the transitions |
+/// | `min_sync_committee_participants` | `u64` | Minimum participation enforced by every transition, zero without transitions |
+/// | `forced_update`              | `bool` | Any transition advanced the store without finality |
+/// | `program_version`            | `u32`  | Program version of every entry                |
+/// | `previous_aggregation_vkey`  | `B256` | Aggregation vkey migrated from, or zero       |
+///
+/// # Error Conditions
+/// - `NoTransitions` → Nothing to aggregate
+/// - `InvalidTransitionOutputs { index, reason }` → Public values do not decode as their tagged kind
+/// - `BrokenHashChain { index, expected, actual }` → Store hash chain is broken
+/// - `BrokenSlotChain { index, expected, actual }` → Slot chain is broken
+/// - `NetworkMismatch { index, expected, actual }` → Entries are bound to different networks
+/// - `ParticipationThresholdMismatch { index, expected, actual }` → Transitions enforced different participation minimums
+/// - `ProgramVersionMismatch { index, expected, actual }` → Entries were proven by different program versions
+/// - `InvalidMigration { reason }` → The previous aggregation is undecodable or bound to another network
+/// - `BrokenHashChain` / `BrokenSlotChain` with `index` 0 → The first entry does not continue the previous
+///   aggregation
+pub fn aggregation_program(
+    inputs: AggregationProofInputs,
+) -> Result<AggregationProofOutputs, AggregationError> {
+    let AggregationProofInputs {
+        transition_vkey,
+        rotation_vkey,
+        transition_public_values,
+        migration,
+    } = inputs;
+
+    if transition_public_values.is_empty() {
+        return Err(AggregationError::NoTransitions);
+    }
+
+    let mut execution_state_roots: Vec<u8> = Vec::with_capacity(transition_public_values.len() * 32);
+    let mut transition_outputs_hashes: Vec<u8> = Vec::with_capacity(transition_public_values.len() * 32);
+    let mut first: Option<ChainLink> = None;
+    let mut previous: Option<ChainLink> = None;
+    let mut min_sync_committee_participants: Option<u64> = None;
+    let mut sync_committee_participants: Option<u64> = None;
+    let mut forced_update = false;
+
+    for (index, public_values) in transition_public_values.iter().enumerate() {
+        // 1. Decode
+        let link = ChainLink::decode(index, public_values)?;
+
+        // 2. Chain check
+        if let Some(first) = &first {
+            if link.network_identity_digest != first.network_identity_digest {
+                return Err(AggregationError::NetworkMismatch {
+                    index,
+                    expected: first.network_identity_digest,
+                    actual: link.network_identity_digest,
+                });
+            }
+            if link.program_version != first.program_version {
+                return Err(AggregationError::ProgramVersionMismatch {
+                    index,
+                    expected: first.program_version,
+                    actual: link.program_version,
+                });
+            }
+        }
+        if let Some(previous) = &previous {
+            if link.input_store_hash != previous.output_store_hash {
+                return Err(AggregationError::BrokenHashChain {
+                    index,
+                    expected: previous.output_store_hash,
+                    actual: link.input_store_hash,
+                });
+            }
+            if link.input_slot != previous.output_slot {
+                return Err(AggregationError::BrokenSlotChain {
+                    index,
+                    expected: previous.output_slot,
+                    actual: link.input_slot,
+                });
+            }
+        }
+        if let Some(outputs) = &link.transition {
+            match min_sync_committee_participants {
+                Some(expected) if outputs.min_sync_committee_participants != expected => {
+                    return Err(AggregationError::ParticipationThresholdMismatch {
+                        index,
+                        expected,
+                        actual: outputs.min_sync_committee_participants,
+                    });
+                }
+                _ => min_sync_committee_participants = Some(outputs.min_sync_committee_participants),
+            }
+            execution_state_roots.extend_from_slice(outputs.execution_state_root.as_slice());
+            sync_committee_participants = Some(
+                sync_committee_participants
+                    .map_or(outputs.sync_committee_participants, |participants| {
+                        participants.min(outputs.sync_committee_participants)
+                    }),
+            );
+            forced_update |= outputs.forced_update;
+        }
+        transition_outputs_hashes.extend_from_slice(keccak256(public_values.bytes()).as_slice());
+
+        if first.is_none() {
+            first = Some(link.clone());
+        }
+        previous = Some(link);
+    }
+
+    let first = first.unwrap();
+    let last = previous.unwrap();
+
+    // 2. Chain check against the previous program version's aggregation
+    let previous_aggregation_vkey = match &migration {
+        Some(migration) => verify_migration(migration, &first)?,
+        None => B256::ZERO,
+    };
+
+    // 3. Commit
+
+    Ok(AggregationProofOutputs {
+        network_identity_digest: first.network_identity_digest,
+        input_slot: first.input_slot,
+        input_store_hash: first.input_store_hash,
+        output_slot: last.output_slot,
+        output_store_hash: last.output_store_hash,
+        transition_vkey: vkey_to_bytes32(&transition_vkey),
+        rotation_vkey: vkey_to_bytes32(&rotation_vkey),
+        execution_state_roots_hash: keccak256(execution_state_roots),
+        transition_outputs_hash: keccak256(transition_outputs_hashes),
+        n_transitions: transition_public_values.len() as u64,
+        sync_committee_participants: sync_committee_participants.unwrap_or(0),
+        min_sync_committee_participants: min_sync_committee_participants.unwrap_or(0),
+        forced_update,
+        program_version: first.program_version,
+        previous_aggregation_vkey,
+    })
+}
+
+/// Checks that the first entry continues the chain of the previous program version's aggregation, returning the
+/// previous aggregation vkey to commit. Only the chain head of the previous public values is read, so any version of
+/// them is accepted.
+fn verify_migration(
+    migration: &AggregationMigration,
+    first: &ChainLink,
+) -> Result<B256, AggregationError> {
+    let (version, previous) =
+        decode_chain_head(&migration.previous_public_values).map_err(|e| {
+            AggregationError::InvalidMigration {
+                reason: e.to_string(),
+            }
+        })?;
+
+    if previous.networkIdentityDigest != first.network_identity_digest {
+        return Err(AggregationError::InvalidMigration {
+            reason: format!(
+                "previous aggregation (version {}) is bound to network identity {:?}, expected {:?}",
+                version, previous.networkIdentityDigest, first.network_identity_digest
+            ),
+        });
+    }
+    if first.input_store_hash != previous.outputStoreHash {
+        return Err(AggregationError::BrokenHashChain {
+            index: 0,
+            expected: previous.outputStoreHash,
+            actual: first.input_store_hash,
+        });
+    }
+    if first.input_slot != previous.outputSlot {
+        return Err(AggregationError::BrokenSlotChain {
+            index: 0,
+            expected: previous.outputSlot,
+            actual: first.input_slot,
+        });
+    }
+
+    Ok(vkey_to_bytes32(&migration.previous_aggregation_vkey))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NETWORK: B256 = B256::repeat_byte(0xee);
+    const TRANSITION_VKEY: [u32; 8] = [1; 8];
+    const ROTATION_VKEY: [u32; 8] = [2; 8];
+    const PREVIOUS_AGGREGATION_VKEY: [u32; 8] = [3; 8];
+
+    // Store hash of the chain at step `n`
+    fn store_hash(n: u8) -> B256 {
+        B256::repeat_byte(n)
+    }
+
+    // Transition public values moving the store from step `from` at `input_slot` to step `from + 1` at `output_slot`
+    fn transition(from: u8, input_slot: u64, output_slot: u64) -> ProofOutputs {
+        ProofOutputs {
+            network_identity_digest: NETWORK,
+            input_slot,
+            input_store_hash: store_hash(from),
+            output_slot,
+            output_store_hash: store_hash(from + 1),
+            beacon_block_root: B256::repeat_byte(0xb0 + from),
+            execution_block_hash: B256::repeat_byte(0xc0 + from),
+            execution_block_number: output_slot,
+            execution_timestamp: output_slot * 12,
+            execution_state_root: B256::repeat_byte(0xd0 + from),
+            receipts_root: B256::ZERO,
+            verified_contract_storage_slots_root: B256::ZERO,
+            contract_storage_layouts_hash: B256::ZERO,
+            verified_receipt_logs_root: B256::ZERO,
+            verified_contract_storage_deltas_root: B256::ZERO,
+            verified_historical_blocks_root: B256::ZERO,
+            next_sync_committee_hash: B256::ZERO,
+            sync_committee_participants: 500,
+            min_sync_committee_participants: 342,
+            forced_update: false,
+            program_version: 1,
+        }
+    }
+
+    // Rotation public values moving the store from step `from` to step `from + 1`, the finalized slot unchanged
+    fn rotation(from: u8, slot: u64) -> RotationProofOutputs {
+        RotationProofOutputs {
+            network_identity_digest: NETWORK,
+            input_slot: slot,
+            input_store_hash: store_hash(from),
+            output_slot: slot,
+            output_store_hash: store_hash(from + 1),
+            current_sync_committee_hash: B256::repeat_byte(0xa1),
+            next_sync_committee_hash: B256::repeat_byte(0xa2),
+            n_updates: 1,
+            program_version: 1,
+        }
+    }
+
+    fn aggregation_inputs(
+        transition_public_values: Vec<AggregatedPublicValues>,
+        migration: Option<AggregationMigration>,
+    ) -> AggregationProofInputs {
+        AggregationProofInputs {
+            transition_vkey: TRANSITION_VKEY,
+            rotation_vkey: ROTATION_VKEY,
+            transition_public_values,
+            migration,
+        }
+    }
+
+    // Transition, rotation, forced transition: store steps 1 → 4, slots 64 → 192
+    fn chain() -> (ProofOutputs, RotationProofOutputs, ProofOutputs) {
+        let first = transition(1, 64, 128);
+        let rotation = rotation(2, 128);
+        let mut last = transition(3, 128, 192);
+        last.sync_committee_participants = 400;
+        last.forced_update = true;
+        (first, rotation, last)
+    }
+
+    fn chain_public_values() -> Vec<AggregatedPublicValues> {
+        let (first, rotation, last) = chain();
+        vec![
+            AggregatedPublicValues::Transition(first.to_bytes()),
+            AggregatedPublicValues::Rotation(rotation.to_bytes()),
+            AggregatedPublicValues::Transition(last.to_bytes()),
+        ]
+    }
+
+    // Public values of a previous version's aggregation ending at store step `to` and `output_slot`
+    fn previous_aggregation(network: B256, to: u8, output_slot: u64) -> AggregationMigration {
+        let previous = AggregationProofOutputs {
+            network_identity_digest: network,
+            input_slot: 0,
+            input_store_hash: store_hash(0),
+            output_slot,
+            output_store_hash: store_hash(to),
+            transition_vkey: B256::repeat_byte(0x11),
+            rotation_vkey: B256::repeat_byte(0x22),
+            execution_state_roots_hash: B256::ZERO,
+            transition_outputs_hash: B256::ZERO,
+            n_transitions: 1,
+            sync_committee_participants: 500,
+            min_sync_committee_participants: 342,
+            forced_update: false,
+            program_version: 0,
+            previous_aggregation_vkey: B256::ZERO,
+        };
+        AggregationMigration {
+            previous_aggregation_vkey: PREVIOUS_AGGREGATION_VKEY,
+            previous_public_values: previous.to_bytes(),
+        }
+    }
+
+    #[test]
+    fn aggregates_a_chain_of_transitions_and_rotations() {
+        let (first, _, last) = chain();
+        let public_values = chain_public_values();
+        let outputs = aggregation_program(aggregation_inputs(public_values.clone(), None)).unwrap();
+
+        assert_eq!(outputs.network_identity_digest, NETWORK);
+        assert_eq!(outputs.input_slot, 64);
+        assert_eq!(outputs.input_store_hash, store_hash(1));
+        assert_eq!(outputs.output_slot, 192);
+        assert_eq!(outputs.output_store_hash, store_hash(4));
+        assert_eq!(outputs.transition_vkey, vkey_to_bytes32(&TRANSITION_VKEY));
+        assert_eq!(outputs.rotation_vkey, vkey_to_bytes32(&ROTATION_VKEY));
+        assert_eq!(
+            outputs.execution_state_roots_hash,
+            keccak256([first.execution_state_root, last.execution_state_root].concat())
+        );
+        let hashes: Vec<u8> = public_values
+            .iter()
+            .flat_map(|public_values| keccak256(public_values.bytes()).0)
+            .collect();
+        assert_eq!(outputs.transition_outputs_hash, keccak256(hashes));
+        assert_eq!(outputs.n_transitions, 3);
+        assert_eq!(outputs.sync_committee_participants, 400);
+        assert_eq!(outputs.min_sync_committee_participants, 342);
+        assert!(outputs.forced_update);
+        assert_eq!(outputs.program_version, 1);
+        assert_eq!(outputs.previous_aggregation_vkey, B256::ZERO);
+    }
+
+    #[test]
+    fn rejects_an_empty_aggregation() {
+        assert!(matches!(
+            aggregation_program(aggregation_inputs(Vec::new(), None)),
+            Err(AggregationError::NoTransitions)
+        ));
+    }
+
+    #[test]
+    fn rejects_a_broken_hash_link() {
+        let (first, _, last) = chain();
+        let mut rotation = rotation(2, 128);
+        rotation.input_store_hash = store_hash(9);
+        let public_values = vec![
+            AggregatedPublicValues::Transition(first.to_bytes()),
+            AggregatedPublicValues::Rotation(rotation.to_bytes()),
+            AggregatedPublicValues::Transition(last.to_bytes()),
+        ];
+
+        match aggregation_program(aggregation_inputs(public_values, None)) {
+            Err(AggregationError::BrokenHashChain {
+                index,
+                expected,
+                actual,
+            }) => {
+                assert_eq!(index, 1);
+                assert_eq!(expected, store_hash(2));
+                assert_eq!(actual, store_hash(9));
+            }
+            other => panic!("Expected a broken hash chain, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn rejects_a_slot_gap() {
+        let (first, rotation, _) = chain();
+        let last = transition(3, 160, 192);
+        let public_values = vec![
+            AggregatedPublicValues::Transition(first.to_bytes()),
+            AggregatedPublicValues::Rotation(rotation.to_bytes()),
+            AggregatedPublicValues::Transition(last.to_bytes()),
+        ];
+
+        match aggregation_program(aggregation_inputs(public_values, None)) {
+            Err(AggregationError::BrokenSlotChain {
+                index,
+                expected,
+                actual,
+            }) => {
+                assert_eq!(index, 2);
+                assert_eq!(expected, 128);
+                assert_eq!(actual, 160);
+            }
+            other => panic!("Expected a broken slot chain, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn rejects_a_mixed_network_digest() {
+        let (first, mut rotation, last) = chain();
+        rotation.network_identity_digest = B256::repeat_byte(0xef);
+        let public_values = vec![
+            AggregatedPublicValues::Transition(first.to_bytes()),
+            AggregatedPublicValues::Rotation(rotation.to_bytes()),
+            AggregatedPublicValues::Transition(last.to_bytes()),
+        ];
+
+        assert!(matches!(
+            aggregation_program(aggregation_inputs(public_values, None)),
+            Err(AggregationError::NetworkMismatch { index: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn rejects_a_mixed_program_version() {
+        let (first, rotation, mut last) = chain();
+        last.program_version = 2;
+        let public_values = vec![
+            AggregatedPublicValues::Transition(first.to_bytes()),
+            AggregatedPublicValues::Rotation(rotation.to_bytes()),
+            AggregatedPublicValues::Transition(last.to_bytes()),
+        ];
+
+        assert!(matches!(
+            aggregation_program(aggregation_inputs(public_values, None)),
+            Err(AggregationError::ProgramVersionMismatch {
+                index: 2,
+                expected: 1,
+                actual: 2
+            })
+        ));
+    }
+
+    #[test]
+    fn rejects_a_mixed_participation_threshold() {
+        let (first, rotation, mut last) = chain();
+        last.min_sync_committee_participants = 1;
+        let public_values = vec![
+            AggregatedPublicValues::Transition(first.to_bytes()),
+            AggregatedPublicValues::Rotation(rotation.to_bytes()),
+            AggregatedPublicValues::Transition(last.to_bytes()),
+        ];
+
+        assert!(matches!(
+            aggregation_program(aggregation_inputs(public_values, None)),
+            Err(AggregationError::ParticipationThresholdMismatch { index: 2, .. })
+        ));
+    }
+
+    #[test]
+    fn rejects_public_values_of_the_wrong_kind() {
+        let (first, rotation, _) = chain();
+        let public_values = vec![
+            AggregatedPublicValues::Transition(first.to_bytes()),
+            AggregatedPublicValues::Transition(rotation.to_bytes()),
+        ];
+
+        assert!(matches!(
+            aggregation_program(aggregation_inputs(public_values, None)),
+            Err(AggregationError::InvalidTransitionOutputs { index: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn migrates_from_the_previous_aggregation() {
+        let migration = previous_aggregation(NETWORK, 1, 64);
+        let outputs =
+            aggregation_program(aggregation_inputs(chain_public_values(), Some(migration)))
+                .unwrap();
+
+        assert_eq!(
+            outputs.previous_aggregation_vkey,
+            vkey_to_bytes32(&PREVIOUS_AGGREGATION_VKEY)
+        );
+        assert_eq!(outputs.input_store_hash, store_hash(1));
+        assert_eq!(outputs.output_store_hash, store_hash(4));
+    }
+
+    #[test]
+    fn rejects_a_migration_not_continuing_the_previous_head() {
+        let migrate = |migration| {
+            aggregation_program(aggregation_inputs(chain_public_values(), Some(migration)))
+        };
+
+        assert!(matches!(
+            migrate(previous_aggregation(NETWORK, 0, 64)),
+            Err(AggregationError::BrokenHashChain { index: 0, .. })
+        ));
+        assert!(matches!(
+            migrate(previous_aggregation(NETWORK, 1, 32)),
+            Err(AggregationError::BrokenSlotChain {
+                index: 0,
+                expected: 32,
+                actual: 64
+            })
+        ));
+        assert!(matches!(
+            migrate(previous_aggregation(B256::repeat_byte(0xef), 1, 64)),
+            Err(AggregationError::InvalidMigration { .. })
+        ));
+        assert!(matches!(
+            migrate(AggregationMigration {
+                previous_aggregation_vkey: PREVIOUS_AGGREGATION_VKEY,
+                previous_public_values: vec![0u8; 16],
+            }),
+            Err(AggregationError::InvalidMigration { .. })
+        ));
+    }
+}
@@ -0,0 +1,41 @@
+#![no_main]
+sp1_zkvm::entrypoint!(main);
+use golem_symbiotic_consensus_mpt_aggregation_program::aggregation_program;
+use golem_symbiotic_consensus_mpt_types::types::{AggregatedPublicValues, AggregationProofInputs};
+use sha2_v0_10_8::{Digest, Sha256};
+
+pub fn main() {
+    // Read zk input
+    let encoded_inputs = sp1_zkvm::io::read_vec();
+
+    // Decode inputs
+    println!("Decoding inputs");
+    let inputs: AggregationProofInputs = serde_cbor::from_slice(&encoded_inputs).unwrap();
+    println!("Decoded inputs");
+
+    // Recursively verify each transition or rotation proof (written to stdin by the host) against its public values,
+    // with the verification key of the program that produced it
+    for public_values in inputs.transition_public_values.iter() {
+        let vkey = match public_values {
+            AggregatedPublicValues::Transition(_) => &inputs.transition_vkey,
+            AggregatedPublicValues::Rotation(_) => &inputs.rotation_vkey,
+        };
+        let public_values_digest = Sha256::digest(public_values.bytes());
+        sp1_zkvm::lib::verify::verify_sp1_proof(vkey, &public_values_digest.into());
+    }
+
+    // When migrating, recursively verify the previous program version's aggregation proof (written last)
+    if let Some(migration) = &inputs.migration {
+        let public_values_digest = Sha256::digest(&migration.previous_public_values);
+        sp1_zkvm::lib::verify::verify_sp1_proof(
+            &migration.previous_aggregation_vkey,
+            &public_values_digest.into(),
+        );
+    }
+
+    // Run aggregation program
+    let aggregation_outputs = aggregation_program(inputs).unwrap();
+
+    // Write zk output
+    sp1_zkvm::io::commit_slice(&aggregation_outputs.to_bytes());
+}
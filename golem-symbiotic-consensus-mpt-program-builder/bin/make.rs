@@ -11,20 +11,106 @@ fn main() {
     // Use the correct relative paths based on the project root
     let golem_symbiotic_program_path = cargo_dir.join("golem-symbiotic-consensus-mpt-program");
     let golem_symbiotic_elf_dir = cargo_dir.join("golem-symbiotic-consensus-mpt-program-elf");
+    let golem_symbiotic_aggregation_program_path = cargo_dir.join("golem-symbiotic-consensus-mpt-aggregation-program");
+    let golem_symbiotic_aggregation_elf_dir = cargo_dir.join("golem-symbiotic-consensus-mpt-aggregation-program-elf");
 
     println!("Calling build with args");
-    println!("{:?}",golem_symbiotic_program_path.to_str());
-    println!("{:?}",golem_symbiotic_elf_dir.to_str());
     // Build the program using the relative paths
     build_program_with_args(
         golem_symbiotic_program_path.to_str().expect("Invalid path"),
         BuildArgs {
             docker: true,
             tag: "v5.0.0".to_string(),
+            binaries: vec!["golem-symbiotic-consensus-mpt-program".to_string()],
             output_directory: Some(golem_symbiotic_elf_dir.to_str().expect("Invalid path").to_string()),
             ..Default::default()
         },
     );
 
+    // Build the program for the minimal consensus preset (local devnets)
+    build_program_with_args(
+        golem_symbiotic_program_path.to_str().expect("Invalid path"),
+        BuildArgs {
+            docker: true,
+            tag: "v5.0.0".to_string(),
+            features: vec!["minimal".to_string()],
+            binaries: vec!["golem-symbiotic-consensus-mpt-program".to_string()],
+            elf_name: Some("golem-symbiotic-consensus-mpt-program-minimal".to_string()),
+            output_directory: Some(golem_symbiotic_elf_dir.to_str().expect("Invalid path").to_string()),
+            ..Default::default()
+        },
+    );
+
+    // Build the sync committee rotation program, for both consensus presets
+    build_program_with_args(
+        golem_symbiotic_program_path.to_str().expect("Invalid path"),
+        BuildArgs {
+            docker: true,
+            tag: "v5.0.0".to_string(),
+            binaries: vec!["golem-symbiotic-consensus-mpt-rotation-program".to_string()],
+            output_directory: Some(golem_symbiotic_elf_dir.to_str().expect("Invalid path").to_string()),
+            ..Default::default()
+        },
+    );
+    build_program_with_args(
+        golem_symbiotic_program_path.to_str().expect("Invalid path"),
+        BuildArgs {
+            docker: true,
+            tag: "v5.0.0".to_string(),
+            features: vec!["minimal".to_string()],
+            binaries: vec!["golem-symbiotic-consensus-mpt-rotation-program".to_string()],
+            elf_name: Some("golem-symbiotic-consensus-mpt-rotation-program-minimal".to_string()),
+            output_directory: Some(golem_symbiotic_elf_dir.to_str().expect("Invalid path").to_string()),
+            ..Default::default()
+        },
+    );
+
+    // Build the bootstrap program, for both consensus presets
+    build_program_with_args(
+        golem_symbiotic_program_path.to_str().expect("Invalid path"),
+        BuildArgs {
+            docker: true,
+            tag: "v5.0.0".to_string(),
+            binaries: vec!["golem-symbiotic-consensus-mpt-bootstrap-program".to_string()],
+            output_directory: Some(golem_symbiotic_elf_dir.to_str().expect("Invalid path").to_string()),
+            ..Default::default()
+        },
+    );
+    build_program_with_args(
+        golem_symbiotic_program_path.to_str().expect("Invalid path"),
+        BuildArgs {
+            docker: true,
+            tag: "v5.0.0".to_string(),
+            features: vec!["minimal".to_string()],
+            binaries: vec!["golem-symbiotic-consensus-mpt-bootstrap-program".to_string()],
+            elf_name: Some("golem-symbiotic-consensus-mpt-bootstrap-program-minimal".to_string()),
+            output_directory: Some(golem_symbiotic_elf_dir.to_str().expect("Invalid path").to_string()),
+            ..Default::default()
+        },
+    );
+
+    // Build the CBOR decoding bench program, the baseline of bench_guest_input
+    build_program_with_args(
+        golem_symbiotic_program_path.to_str().expect("Invalid path"),
+        BuildArgs {
+            docker: true,
+            tag: "v5.0.0".to_string(),
+            binaries: vec!["golem-symbiotic-consensus-mpt-decode-bench-program".to_string()],
+            output_directory: Some(golem_symbiotic_elf_dir.to_str().expect("Invalid path").to_string()),
+            ..Default::default()
+        },
+    );
+
+    // Build the aggregation program
+    build_program_with_args(
+        golem_symbiotic_aggregation_program_path.to_str().expect("Invalid path"),
+        BuildArgs {
+            docker: true,
+            tag: "v5.0.0".to_string(),
+            output_directory: Some(golem_symbiotic_aggregation_elf_dir.to_str().expect("Invalid path").to_string()),
+            ..Default::default()
+        },
+    );
+
     println!("ZK built.");
 }
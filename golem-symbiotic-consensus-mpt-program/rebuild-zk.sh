@@ -15,4 +15,6 @@ sudo rm -rf target
 # Step 5: Build zk
 cd golem-symbiotic-consensus-mpt-program-builder
 cargo run --release --bin make
-cd ..
\ No newline at end of file
+cd ..
+# Step 6: The relayer loads the ELFs from PROGRAM_ELF_ROOT
+echo "Programs built, run the relayer with PROGRAM_ELF_ROOT=$(pwd)"
@@ -0,0 +1,24 @@
+#![no_main]
+sp1_zkvm::entrypoint!(main);
+#[cfg(not(feature = "minimal"))]
+use helios_consensus_core::consensus_spec::MainnetConsensusSpec as ProgramConsensusSpec;
+#[cfg(feature = "minimal")]
+use helios_consensus_core::consensus_spec::MinimalConsensusSpec as ProgramConsensusSpec;
+use golem_symbiotic_consensus_mpt_types::types::BootstrapProofInputs;
+use golem_symbiotic_consensus_mpt_program::consensus::bootstrap_program;
+
+pub fn main() {
+    // Read zk input
+    let encoded_inputs = sp1_zkvm::io::read_vec();
+
+    // Decode inputs
+    println!("Decoding inputs");
+    let proof_inputs: BootstrapProofInputs<ProgramConsensusSpec> = serde_cbor::from_slice(&encoded_inputs).unwrap();
+    println!("Decoded inputs");
+
+    // Run bootstrap program
+    let proof_outputs = bootstrap_program(proof_inputs, true).unwrap();
+
+    // Write zk output
+    sp1_zkvm::io::commit_slice(&proof_outputs.to_bytes());
+}
@@ -0,0 +1,21 @@
+#![no_main]
+sp1_zkvm::entrypoint!(main);
+use helios_consensus_core::consensus_spec::MainnetConsensusSpec;
+use golem_symbiotic_consensus_mpt_types::types::ProofInputs;
+use golem_symbiotic_consensus_mpt_program::profiling::CycleSpan;
+
+/// Bench only: decodes CBOR `ProofInputs` under the same `decode_inputs` span as the program decodes its guest
+/// input, so that both decodings are measured by the same build (see `bench_guest_input`). Nothing is proven.
+pub fn main() {
+    // Read zk input
+    let encoded_inputs = sp1_zkvm::io::read_vec();
+
+    // Decode inputs
+    println!("Decoding inputs");
+    let proof_inputs: ProofInputs<MainnetConsensusSpec> = {
+        let _span = CycleSpan::new("decode_inputs");
+        serde_cbor::from_slice(&encoded_inputs).unwrap()
+    };
+    println!("Decoded inputs");
+    std::hint::black_box(proof_inputs);
+}
@@ -1,17 +1,32 @@
 use alloy_primitives::B256;
 use helios_consensus_core::{
-    apply_finality_update, apply_update, consensus_spec::ConsensusSpec, verify_finality_update,
-    verify_update,
+    apply_bootstrap, apply_finality_update, apply_update,
+    consensus_spec::ConsensusSpec,
+    types::{Forks, GenericUpdate, LightClientStore, SyncAggregate, Update},
+    verify_bootstrap, verify_finality_update, verify_update,
 };
 use log::debug;
 use nori_hash::sha256_hash::sha256_hash_helios_store;
+use golem_symbiotic_consensus_mpt_types::network::{network_identity_digest, ConsensusSpecName};
 use golem_symbiotic_consensus_mpt_types::types::{
-    ConsensusProofInputs, ConsensusProofOutputs, ProofInputs, ProofOutputs,
+    BootstrapProofInputs, BootstrapProofOutputs, ConsensusProofInputs, ConsensusProofOutputs,
+    ProofInputs, ProofOutputs, RotationProofInputs, RotationProofOutputs,
 };
 use std::fmt;
 use tree_hash::TreeHash;
 
-use crate::mpt::{verify_storage_slot_proofs, MptError};
+use crate::mpt::{
+    verify_contracts_storage_deltas, verify_contracts_storage_slot_proofs, MptError,
+    OUTPUT_BLOCK_SPANS,
+};
+use crate::historical::{verify_historical_blocks, HistoricalBlockError};
+use crate::profiling::CycleSpan;
+use crate::receipts::{verify_receipt_proofs, ReceiptError};
+
+/// Version of the program logic committed by the transition, rotation and bootstrap programs. Bump it with any change
+/// to their logic (new fork, fix), which also changes their vkeys; the store hash chain is carried over to the new
+/// version by a migrating aggregation (see `AggregationMigration`).
+pub const PROGRAM_VERSION: u32 = 1;
 
 /// Custom error type for program execution failures
 #[derive(Debug)]
@@ -20,14 +35,52 @@ pub enum ProgramError {
     HashChainMismatch { expected: B256, actual: B256 },
     /// Error when an update verification fails
     InvalidUpdate { index: usize, reason: String },
+    /// Error when neither a finality update nor a force update is given
+    MissingFinalityUpdate,
+    /// Error when a finality update is given along with a force update, which replaces it
+    FinalityUpdateWithForceUpdate,
     /// Error when finality update verification fails
     InvalidFinalityUpdate { reason: String },
     /// Error when execution root is missing
     MissingExecutionRoot,
+    /// Error when the finalized head did not advance past the input slot
+    NonProgressingTransition { input_slot: u64, output_slot: u64 },
+    /// Error when the finalized head is not an epoch boundary (checkpoint) slot
+    NonCheckpointOutputSlot { output_slot: u64, slots_per_epoch: u64 },
+    /// Error when the updated store has no next sync committee
+    MissingNextSyncCommittee { output_slot: u64 },
+    /// Error when the bootstrap does not verify against the checkpoint
+    InvalidBootstrap { reason: String },
+    /// Error when a rotation proof is given no updates
+    NoUpdates,
+    /// Error when the updates of a rotation proof leave the sync committees unchanged
+    NoSyncCommitteeRotation,
+    /// Error when a force update is given before finality has been stalled for the update timeout
+    ForceUpdateBeforeTimeout {
+        finalized_slot: u64,
+        attested_slot: u64,
+        update_timeout: u64,
+    },
+    /// Error when force update verification fails
+    InvalidForceUpdate { reason: String },
+    /// Error when an update of the transition ranks higher than the force update
+    ForceUpdateNotBestValidUpdate {
+        attested_slot: u64,
+        best_attested_slot: u64,
+    },
+    /// Error when too few sync committee members signed the finality update
+    InsufficientSyncCommitteeParticipation {
+        participants: u64,
+        min_participants: u64,
+    },
     /// Error when store hashing fails
     StoreHashingError(String),
     /// Error for MPT specific errors
     MptError(MptError),
+    /// Error for receipt and log specific errors
+    ReceiptError(ReceiptError),
+    /// Error for historical block specific errors
+    HistoricalBlockError(HistoricalBlockError),
 }
 
 impl fmt::Display for ProgramError {
@@ -49,12 +102,80 @@ impl fmt::Display for ProgramError {
             ProgramError::MissingExecutionRoot => {
                 write!(f, "Missing execution root in proof inputs")
             }
+            ProgramError::NonProgressingTransition {
+                input_slot,
+                output_slot,
+            } => write!(
+                f,
+                "Output slot {} was not greater than input slot {}",
+                output_slot, input_slot
+            ),
+            ProgramError::NonCheckpointOutputSlot {
+                output_slot,
+                slots_per_epoch,
+            } => write!(
+                f,
+                "Output slot {} is not a checkpoint slot (multiple of {})",
+                output_slot, slots_per_epoch
+            ),
+            ProgramError::MissingNextSyncCommittee { output_slot } => write!(
+                f,
+                "Next sync committee is missing at output slot {}",
+                output_slot
+            ),
+            ProgramError::ForceUpdateBeforeTimeout {
+                finalized_slot,
+                attested_slot,
+                update_timeout,
+            } => write!(
+                f,
+                "Force update attested at slot {} before the update timeout of {} slots since finalized slot {}",
+                attested_slot, update_timeout, finalized_slot
+            ),
+            ProgramError::InvalidForceUpdate { reason } => {
+                write!(f, "Invalid force update: {}", reason)
+            }
+            ProgramError::MissingFinalityUpdate => {
+                write!(f, "Neither a finality update nor a force update was given")
+            }
+            ProgramError::FinalityUpdateWithForceUpdate => {
+                write!(f, "A finality update was given along with a force update")
+            }
+            ProgramError::ForceUpdateNotBestValidUpdate {
+                attested_slot,
+                best_attested_slot,
+            } => write!(
+                f,
+                "Force update attested at slot {} is not the best valid update, the update attested at slot {} ranks higher",
+                attested_slot, best_attested_slot
+            ),
+            ProgramError::InvalidBootstrap { reason } => {
+                write!(f, "Invalid bootstrap: {}", reason)
+            }
+            ProgramError::NoUpdates => write!(f, "No sync committee updates to apply"),
+            ProgramError::NoSyncCommitteeRotation => {
+                write!(f, "Updates did not rotate the sync committees")
+            }
+            ProgramError::InsufficientSyncCommitteeParticipation {
+                participants,
+                min_participants,
+            } => write!(
+                f,
+                "Finality update was signed by {} sync committee members, at least {} are required",
+                participants, min_participants
+            ),
             ProgramError::StoreHashingError(reason) => {
                 write!(f, "Failed to hash store: {}", reason)
             }
             ProgramError::MptError(e) => {
                 write!(f, "MPT error: {}", e)
             }
+            ProgramError::ReceiptError(e) => {
+                write!(f, "Receipt error: {}", e)
+            }
+            ProgramError::HistoricalBlockError(e) => {
+                write!(f, "Historical block error: {}", e)
+            }
         }
     }
 }
@@ -66,8 +187,243 @@ impl From<MptError> for ProgramError {
     }
 }
 
+// Conversion from HistoricalBlockError to ProgramError
+impl From<HistoricalBlockError> for ProgramError {
+    fn from(error: HistoricalBlockError) -> Self {
+        ProgramError::HistoricalBlockError(error)
+    }
+}
+
+// Conversion from ReceiptError to ProgramError
+impl From<ReceiptError> for ProgramError {
+    fn from(error: ReceiptError) -> Self {
+        ProgramError::ReceiptError(error)
+    }
+}
+
 impl std::error::Error for ProgramError {}
 
+/// Counts the sync committee members that signed an update (its `sync_aggregate`) and asserts there are at least
+/// `min_participants` of them.
+///
+/// # Errors
+/// - `InsufficientSyncCommitteeParticipation { participants, min_participants }` → Too few signers
+pub fn verify_sync_committee_participation<S: ConsensusSpec>(
+    sync_aggregate: &SyncAggregate<S>,
+    min_participants: u64,
+) -> Result<u64, ProgramError> {
+    let participants = sync_aggregate.sync_committee_bits.num_set_bits() as u64;
+    if participants < min_participants {
+        return Err(ProgramError::InsufficientSyncCommitteeParticipation {
+            participants,
+            min_participants,
+        });
+    }
+    Ok(participants)
+}
+
+/// Sync committee period of `slot`.
+fn sync_committee_period<S: ConsensusSpec>(slot: u64) -> u64 {
+    slot / (S::slots_per_epoch() * S::epochs_per_sync_committee_period())
+}
+
+/// Whether `new_update` ranks higher than `old_update`, as the light client spec's `is_better_update`: sync committee
+/// supermajority first, then a next sync committee relevant to the attested period, finality, finality within the
+/// attested period, participation, and lastly the earlier attested and signature slots.
+pub fn is_better_update<S: ConsensusSpec>(new_update: &Update<S>, old_update: &Update<S>) -> bool {
+    let new_update = GenericUpdate::from(new_update);
+    let old_update = GenericUpdate::from(old_update);
+
+    // Participation
+    let max_active_participants = new_update.sync_aggregate.sync_committee_bits.len() as u64;
+    let new_num_active_participants =
+        new_update.sync_aggregate.sync_committee_bits.num_set_bits() as u64;
+    let old_num_active_participants =
+        old_update.sync_aggregate.sync_committee_bits.num_set_bits() as u64;
+    let new_has_supermajority = new_num_active_participants * 3 >= max_active_participants * 2;
+    let old_has_supermajority = old_num_active_participants * 3 >= max_active_participants * 2;
+    if new_has_supermajority != old_has_supermajority {
+        return new_has_supermajority;
+    }
+    if !new_has_supermajority && new_num_active_participants != old_num_active_participants {
+        return new_num_active_participants > old_num_active_participants;
+    }
+
+    // Relevancy of the next sync committee and finality
+    fn is_set<T: std::ops::Deref<Target = [B256]>>(branch: &Option<T>) -> bool {
+        branch
+            .as_ref()
+            .is_some_and(|branch| branch.iter().any(|node| *node != B256::ZERO))
+    }
+    let has_relevant_sync_committee = |update: &GenericUpdate<S>| {
+        is_set(&update.next_sync_committee_branch)
+            && sync_committee_period::<S>(update.attested_header.beacon().slot)
+                == sync_committee_period::<S>(update.signature_slot)
+    };
+    let new_has_relevant_sync_committee = has_relevant_sync_committee(&new_update);
+    if new_has_relevant_sync_committee != has_relevant_sync_committee(&old_update) {
+        return new_has_relevant_sync_committee;
+    }
+    let new_has_finality = is_set(&new_update.finality_branch);
+    if new_has_finality != is_set(&old_update.finality_branch) {
+        return new_has_finality;
+    }
+    if new_has_finality {
+        let has_sync_committee_finality = |update: &GenericUpdate<S>| {
+            update
+                .finalized_header
+                .as_ref()
+                .is_some_and(|finalized_header| {
+                    sync_committee_period::<S>(finalized_header.beacon().slot)
+                        == sync_committee_period::<S>(update.attested_header.beacon().slot)
+                })
+        };
+        let new_has_sync_committee_finality = has_sync_committee_finality(&new_update);
+        if new_has_sync_committee_finality != has_sync_committee_finality(&old_update) {
+            return new_has_sync_committee_finality;
+        }
+    }
+
+    // Tie breakers
+    if new_num_active_participants != old_num_active_participants {
+        return new_num_active_participants > old_num_active_participants;
+    }
+    let new_attested_slot = new_update.attested_header.beacon().slot;
+    let old_attested_slot = old_update.attested_header.beacon().slot;
+    if new_attested_slot != old_attested_slot {
+        return new_attested_slot < old_attested_slot;
+    }
+    new_update.signature_slot < old_update.signature_slot
+}
+
+/// Tracks the light client spec's `best_valid_update` over the verified updates of a transition, once `update` has
+/// been applied to `store` (whose finalized slot was `previous_finalized_slot`): an update advancing the finalized
+/// header clears it, as finality resumed, otherwise `update` replaces it when it ranks higher (see
+/// `is_better_update`).
+///
+/// Only updates attested after the update timeout since the finalized slot are candidates, as the force update must
+/// prove the timeout by its attested slot (see `apply_force_update`).
+pub fn track_best_valid_update<'a, S: ConsensusSpec>(
+    best_valid_update: Option<&'a Update<S>>,
+    update: &'a Update<S>,
+    previous_finalized_slot: u64,
+    store: &LightClientStore<S>,
+) -> Option<&'a Update<S>> {
+    let finalized_slot = store.finalized_header.beacon().slot;
+    if finalized_slot != previous_finalized_slot {
+        return None;
+    }
+    let update_timeout = S::slots_per_epoch() * S::epochs_per_sync_committee_period();
+    if update.attested_header().beacon().slot <= finalized_slot + update_timeout {
+        return best_valid_update;
+    }
+    match best_valid_update {
+        Some(best_valid_update) if !is_better_update(update, best_valid_update) => {
+            Some(best_valid_update)
+        }
+        _ => Some(update),
+    }
+}
+
+/// Applies a force update to the store, as the light client spec's `process_light_client_store_force_update`: once
+/// the update timeout (one sync committee period) has elapsed since the finalized slot, the best valid update is
+/// applied with its attested header standing in for the finalized header, so the store keeps progressing into later
+/// sync committee periods through a finality incident.
+///
+/// The timeout is measured up to the force update's attested slot rather than `expected_current_slot`: the latter is
+/// given by the host, while the attested header is signed by the sync committee, so the chain has provably reached
+/// that slot. Proofs of forced updates are flagged (`forced_update`) so consumers can reject them.
+///
+/// The force update must be the best valid update: no update of the transition tracked by `best_valid_update` (see
+/// `track_best_valid_update`) may rank higher than it. The store hash does not carry a best valid update across
+/// transitions, the ranking covers the updates of this transition, which the host fetches for every period since
+/// the finalized slot.
+///
+/// # Errors
+/// - `ForceUpdateBeforeTimeout { finalized_slot, attested_slot, update_timeout }` → Finality is not stalled for long
+///   enough
+/// - `ForceUpdateNotBestValidUpdate { attested_slot, best_attested_slot }` → An update of the transition ranks
+///   higher than the force update
+/// - `InvalidForceUpdate { reason }` → `verify_update` fails on the force update
+pub fn apply_force_update<S: ConsensusSpec>(
+    store: &mut LightClientStore<S>,
+    force_update: &Update<S>,
+    best_valid_update: Option<&Update<S>>,
+    expected_current_slot: u64,
+    genesis_root: B256,
+    forks: &Forks,
+) -> Result<(), ProgramError> {
+    let finalized_slot = store.finalized_header.beacon().slot;
+    let attested_slot = force_update.attested_header().beacon().slot;
+    let update_timeout = S::slots_per_epoch() * S::epochs_per_sync_committee_period();
+    if attested_slot <= finalized_slot + update_timeout {
+        return Err(ProgramError::ForceUpdateBeforeTimeout {
+            finalized_slot,
+            attested_slot,
+            update_timeout,
+        });
+    }
+    if let Some(best_valid_update) = best_valid_update {
+        if is_better_update(best_valid_update, force_update) {
+            return Err(ProgramError::ForceUpdateNotBestValidUpdate {
+                attested_slot,
+                best_attested_slot: best_valid_update.attested_header().beacon().slot,
+            });
+        }
+    }
+
+    if let Err(err) = verify_update(force_update, expected_current_slot, store, genesis_root, forks) {
+        return Err(ProgramError::InvalidForceUpdate {
+            reason: format!("{:?}", err),
+        });
+    }
+
+    // The attested header stands in for a finalized header which is not newer than the store's
+    let mut forced_update = force_update.clone();
+    if forced_update.finalized_header().beacon().slot <= finalized_slot {
+        *forced_update.finalized_header_mut() = forced_update.attested_header().clone();
+    }
+    apply_update(store, &forced_update);
+
+    Ok(())
+}
+
+/// Enforces the rules on the new head of every transition committing a store hash (`consensus_program` and
+/// `consensus_mpt_program`): the finalized head advanced past `input_slot`, it is a checkpoint (epoch boundary) slot
+/// unless it was forced, and the store holds the next sync committee. Returns the output slot and the next sync
+/// committee hash.
+///
+/// # Errors
+/// - `NonProgressingTransition { input_slot, output_slot }` → The finalized head did not advance
+/// - `NonCheckpointOutputSlot { output_slot, slots_per_epoch }` → The finalized head is not a checkpoint slot
+/// - `MissingNextSyncCommittee { output_slot }` → The store has no next sync committee
+pub fn verify_transition_head<S: ConsensusSpec>(
+    store: &LightClientStore<S>,
+    input_slot: u64,
+    forced_update: bool,
+) -> Result<(u64, B256), ProgramError> {
+    // A forced head is an attested header, it need not be aligned
+    let output_slot = store.finalized_header.beacon().slot;
+    if output_slot <= input_slot {
+        return Err(ProgramError::NonProgressingTransition {
+            input_slot,
+            output_slot,
+        });
+    }
+    if !forced_update && output_slot % S::slots_per_epoch() != 0 {
+        return Err(ProgramError::NonCheckpointOutputSlot {
+            output_slot,
+            slots_per_epoch: S::slots_per_epoch(),
+        });
+    }
+    let next_sync_committee_hash = match &store.next_sync_committee {
+        Some(next_sync_committee) => next_sync_committee.tree_hash_root(),
+        None => return Err(ProgramError::MissingNextSyncCommittee { output_slot }),
+    };
+
+    Ok((output_slot, next_sync_committee_hash))
+}
+
 /// Zero-Knowledge Consensus State Transition Proof for Ethereum Light Client Updates with Result type
 ///
 /// Cryptographic state machine processing light client updates with hash chaining.
@@ -89,12 +445,14 @@ impl std::error::Error for ProgramError {}
 /// | Name                | Type               | Description                      |
 /// |---------------------|--------------------|----------------------------------|
 /// | `updates`           | `Vec<Update>`      | Ordered sync committee updates   |
-/// | `finality_update`   | `FinalityUpdate`   | Finalized header proof           |
+/// | `finality_update`   | `Option<FinalityUpdate>` | Finalized header proof, `None` with a force update |
+/// | `force_update`      | `Option<Update>`   | Applied instead of `finality_update` once finality stalled |
 /// | `expected_current_slot` | `Slot`        | Current chain slot for validation|
 /// | `store`             | `LightClientStore` | Full client state                |
 /// | `genesis_root`      | `B256`             | Genesis block root               |
 /// | `forks`             | `ForkData`         | Network fork versions            |
 /// | `store_hash`        | `B256`             | SHA-256(store) from last proof   |
+/// | `min_sync_committee_participants` | `u64` | Minimum signers of the finality update |
 ///
 /// # Operations (In Exact Execution Order)
 /// 1. **Initial Hash Validation** (Irreversible Check)
@@ -103,14 +461,19 @@ impl std::error::Error for ProgramError {}
 ///
 /// 2. **State Capture** (Pre-Update Snapshot)
 ///    - Record `input_slot` = `store.finalized_header.beacon().slot`
+///    - Record `input_execution_state_root` = `store.finalized_header.execution()?.state_root()`
+///    - Record `network_identity_digest` = `network_identity_digest::<S>(genesis_root, forks)`, binding the proof
+///      to the network whose genesis root and fork schedule the updates were verified against
 ///
 /// 3. **Update Processing** (Sequential, Atomic)
 ///    - Verify and apply each sync committee update in `updates`
 ///
 /// 4. **Finality Proof** (Header Finalization)
-///    - Verify and apply `finality_update`
+///    - Verify and apply `finality_update`, or `force_update` when given (see `apply_force_update`)
+///    - Assert: at least `min_sync_committee_participants` sync committee members signed the applied update
 ///
 /// 5. **State Commitment** (Commit new state root)
+///    - Assert: the new head is valid (see `verify_transition_head`)
 ///    - Record `output_slot` = `store.finalized_header.beacon().slot`
 ///    - Extract `next_sync_committee_hash` = `store.next_sync_committee.tree_hash_root()`
 ///    - Extract `execution_state_root` = `store.finalized_header.execution()?.state_root()`
@@ -121,12 +484,15 @@ impl std::error::Error for ProgramError {}
 /// # Outputs (All Values Are Hash Commitments)
 /// | Field                     | Type   | Description                              |
 /// |---------------------------|--------|------------------------------------------|
+/// | `network_identity_digest` | `B256` | Genesis root, fork schedule and spec digest |
 /// | `input_slot`              | `u64`  | Slot before updates                      |
 /// | `input_store_hash`        | `B256` | Input store hash                         |
 /// | `output_slot`             | `u64`  | Slot after updates                       |
 /// | `output_store_hash`       | `B256` | Updated store hash                       |
 /// | `execution_state_root`    | `B256` | Execution layer state root               |
 /// | `next_sync_committee_hash`| `B256` | Hash of the next sync committee state    |
+/// | `forced_update`           | `bool` | Store advanced by a force update, without finality |
+/// | `program_version`         | `u32`  | `PROGRAM_VERSION`                        |
 ///
 /// # Error Conditions
 /// 1. **Hash Chain Break**
@@ -134,21 +500,31 @@ impl std::error::Error for ProgramError {}
 /// 2. **Invalid Update**
 ///    Any `verify_update` returns error → Malformed or fraudulent update
 /// 3. **Invalid Finality**
-///    `verify_finality_update` fails → Unverifiable final header
+///    `verify_finality_update` fails → Unverifiable final header, or fewer than `min_sync_committee_participants`
+///    signers → `InsufficientSyncCommitteeParticipation`, neither a finality nor a force update →
+///    `MissingFinalityUpdate`, both → `FinalityUpdateWithForceUpdate`
 /// 4. **Missing Execution Root**
 ///    `store.finalized_header.execution()` is `Err` → Incomplete header data
-pub fn consensus_program<S: ConsensusSpec>(
+/// 5. **Invalid Force Update**
+///    Update timeout not elapsed at its attested slot → `ForceUpdateBeforeTimeout`, an update of the transition
+///    ranks higher → `ForceUpdateNotBestValidUpdate`, `verify_update` fails → `InvalidForceUpdate`
+/// 6. **Invalid Head**
+///    Head did not advance → `NonProgressingTransition`, unforced head not a checkpoint slot →
+///    `NonCheckpointOutputSlot`, no next sync committee → `MissingNextSyncCommittee`
+pub fn consensus_program<S: ConsensusSpecName>(
     proof_inputs: ConsensusProofInputs<S>,
 ) -> Result<ConsensusProofOutputs, ProgramError> {
     // Unpack inputs
     let ConsensusProofInputs {
         updates,
         finality_update,
+        force_update,
         expected_current_slot,
         mut store,
         genesis_root,
         forks,
         store_hash: input_store_hash,
+        min_sync_committee_participants,
     } = proof_inputs;
 
     // 1. Calculate old store hash and assert equality
@@ -167,11 +543,13 @@ pub fn consensus_program<S: ConsensusSpec>(
     );
 
     // 2. State capture
-    debug!("Capturing input_slot.");
+    debug!("Capturing input_slot and network_identity_digest.");
     let input_slot = store.finalized_header.beacon().slot;
-    debug!("input_slot captured.");
+    let network_identity_digest = network_identity_digest::<S>(&genesis_root, &forks);
+    debug!("input_slot and network_identity_digest captured.");
 
-    // 3. Apply sync committee updates, if any
+    // 3. Apply sync committee updates, if any, tracking the best valid update a force update must rank with
+    let mut best_valid_update = None;
     for (index, update) in updates.iter().enumerate() {
         // update.finalized_header.beacon().slot; introduce printing this so we can see if we are applying updates beyond our head
         let finalized_beacon_slot = { update.finalized_header().beacon().slot };
@@ -189,36 +567,61 @@ pub fn consensus_program<S: ConsensusSpec>(
             });
         }
         debug!("Update {} is valid.", index + 1);
+        let finalized_slot = store.finalized_header.beacon().slot;
         apply_update(&mut store, update);
+        best_valid_update =
+            track_best_valid_update(best_valid_update, update, finalized_slot, &store);
         debug!("Applied update {}.", index + 1);
     }
 
-    // 4. Apply finality update
-    debug!("Processing finality update.");
-    if let Err(err) = verify_finality_update(
-        &finality_update,
-        expected_current_slot,
-        &store,
-        genesis_root,
-        &forks,
-    ) {
-        return Err(ProgramError::InvalidFinalityUpdate {
-            reason: format!("{:?}", err),
-        });
-    }
-    debug!("Finality update is valid.");
-    apply_finality_update(&mut store, &finality_update);
-    debug!("Applied finality update.");
-
-    // Should do an assertion here to ensure we have increased our head (we do check this downstream later)
+    // 4. Apply finality update, or the force update while finality is stalled
+    let sync_aggregate = match (&force_update, &finality_update) {
+        (Some(_), Some(_)) => return Err(ProgramError::FinalityUpdateWithForceUpdate),
+        (None, None) => return Err(ProgramError::MissingFinalityUpdate),
+        (Some(force_update), None) => {
+            debug!("Processing force update.");
+            apply_force_update(
+                &mut store,
+                force_update,
+                best_valid_update,
+                expected_current_slot,
+                genesis_root,
+                &forks,
+            )?;
+            debug!("Applied force update.");
+            force_update.sync_aggregate()
+        }
+        (None, Some(finality_update)) => {
+            debug!("Processing finality update.");
+            if let Err(err) = verify_finality_update(
+                finality_update,
+                expected_current_slot,
+                &store,
+                genesis_root,
+                &forks,
+            ) {
+                return Err(ProgramError::InvalidFinalityUpdate {
+                    reason: format!("{:?}", err),
+                });
+            }
+            debug!("Finality update is valid.");
+            apply_finality_update(&mut store, finality_update);
+            debug!("Applied finality update.");
+            finality_update.sync_aggregate()
+        }
+    };
+    let sync_committee_participants =
+        verify_sync_committee_participation(sync_aggregate, min_sync_committee_participants)?;
+    debug!(
+        "Update signed by {} sync committee members.",
+        sync_committee_participants
+    );
+    let forced_update = force_update.is_some();
 
-    // 5. Commit new state root
+    // 5. Enforce progression, checkpoint alignment and the next sync committee, and commit new state root
     debug!("Committing output_slot, next_sync_committee_hash and execution_state_root.");
-    let output_slot = store.finalized_header.beacon().slot;
-    let next_sync_committee_hash: B256 = match &mut store.next_sync_committee {
-        Some(next_sync_committee) => next_sync_committee.tree_hash_root(),
-        None => B256::ZERO,
-    };
+    let (output_slot, next_sync_committee_hash) =
+        verify_transition_head(&store, input_slot, forced_update)?;
     let execution_state_root_result = store.finalized_header.execution();
     if execution_state_root_result.is_err() {
         return Err(ProgramError::MissingExecutionRoot);
@@ -236,12 +639,15 @@ pub fn consensus_program<S: ConsensusSpec>(
 
     debug!("Packing outputs.");
     let proof_outputs = ConsensusProofOutputs {
+        network_identity_digest,
         input_slot,
         input_store_hash,
         output_slot,
         output_store_hash,
         execution_state_root,
-        next_sync_committee_hash
+        next_sync_committee_hash,
+        forced_update,
+        program_version: PROGRAM_VERSION,
     };
     debug!("Packed outputs.");
 
@@ -267,13 +673,18 @@ pub fn consensus_program<S: ConsensusSpec>(
 /// | Name                  | Type               | Description                           |
 /// |-----------------------|--------------------|---------------------------------------|
 /// | `updates`             | `Vec<Update>`      | Ordered sync committee updates        |
-/// | `finality_update`     | `FinalityUpdate`   | Finalized header proof                |
+/// | `finality_update`     | `Option<FinalityUpdate>` | Finalized header proof, `None` with a force update |
+/// | `force_update`        | `Option<Update>`   | Applied instead of `finality_update` once finality stalled |
 /// | `expected_current_slot` | `Slot`           | Current chain slot for validation     |
 /// | `store`               | `LightClientStore` | Full client state                     |
 /// | `genesis_root`        | `B256`             | Genesis block root                    |
 /// | `forks`               | `ForkData`         | Network fork versions                 |
 /// | `store_hash`          | `B256`             | SHA-256(store) from last proof        |
-/// | `contract_storage`    | Contract storage proofs for MPT verification               |
+/// | `min_sync_committee_participants` | `u64`  | Minimum signers of the finality update |
+/// | `contract_storages`   | Contract storage proofs for MPT verification, per contract |
+/// | `input_contract_storages` | Optional proofs of the same slots at the input block, for deltas |
+/// | `receipt_proofs`      | Receipt MPT proofs of the output block and the logs within them to be verified |
+/// | `historical_blocks`   | Older blocks linked to the finalized state, with their storage and receipt proofs (the relayer attaches every block of the window before the output block with logs to prove) |
 ///
 /// # Operations (In Exact Execution Order)
 /// 1. **Initial Hash Validation** (Irreversible Check)
@@ -282,34 +693,91 @@ pub fn consensus_program<S: ConsensusSpec>(
 ///
 /// 2. **State Capture** (Pre-Update Snapshot)
 ///    - Record `input_slot` = `store.finalized_header.beacon().slot`
+///    - Record `input_execution_state_root` = `store.finalized_header.execution()?.state_root()`
+///    - Record `network_identity_digest` = `network_identity_digest::<S>(genesis_root, forks)`, binding the proof
+///      to the network whose genesis root and fork schedule the updates were verified against
 ///
 /// 3. **Update Processing** (Sequential, Atomic)
 ///    - Verify and apply each sync committee update in `updates`
 ///
 /// 4. **Finality Proof** (Header Finalization)
-///    - Verify and apply `finality_update`
+///    - Verify and apply `finality_update`, or `force_update` when given (see `apply_force_update`), recorded as
+///      `forced_update`
+///    - Record `sync_committee_participants`, the number of sync committee members that signed the applied update
+///    - Assert: `sync_committee_participants >= min_sync_committee_participants`
+///    - Assert: `output_slot > input_slot`, every proof advances the head
+///    - Assert: `output_slot % S::slots_per_epoch() == 0` unless forced, so the next proof can bootstrap from the
+///      checkpoint at `output_slot` after a restart
 ///
 /// 5. **Verify Storage Slot Proofs**
 ///    - Extract `execution_state_root` = `store.finalized_header.execution()?.state_root()`
-///    - Verify MPT proofs in `contract_storage`, producing `verified_contract_storage_slots_root`
+///    - Verify MPT proofs of each contract in `contract_storages`, producing a storage slots root per contract
+///    - Missing accounts and zero valued slots are proven by exclusion and committed as absent leaves
+///    - Assert: the contracts are strictly increasing by address and each contract's slots by slot key
+///    - Fold the per contract roots, in address order, into `verified_contract_storage_slots_root` (root-of-roots)
+///    - Commit each contract's storage layout descriptor, producing `contract_storage_layouts_hash`
+///
+/// 6. **Verify Storage Slot Deltas** (Optional)
+///    - When `input_contract_storages` is set, verify them against `input_execution_state_root`
+///    - Assert they prove the same contracts and slots as `contract_storages`
+///    - Commit `|output_value - input_value|` and its direction per slot, producing `verified_contract_storage_deltas_root`
+///      (zero when not requested)
+///
+/// 7. **Verify Receipt Log Proofs**
+///    - Extract `receipts_root` = `store.finalized_header.execution()?.receipts_root()`
+///    - Verify receipt MPT proofs in `receipt_proofs` and their logs, producing `verified_receipt_logs_root`
+///
+/// 8. **Verify Historical Blocks**
+///    - Prove each historical beacon block root within the finalized state's `block_roots` or `historical_summaries`
+///    - Prove each historical execution payload header against its beacon block body
+///    - Verify the storage and receipt proofs of each historical block, producing `verified_historical_blocks_root`
+///      (zero when there are none)
 ///
-/// 6. **State Capture**
+/// 9. **State Capture**
 ///    - Record `output_slot` = `store.finalized_header.beacon().slot`
+///    - Record `beacon_block_root` = `store.finalized_header.beacon().tree_hash_root()`
+///    - Record `execution_block_hash`, `execution_block_number` and `execution_timestamp` from
+///      `store.finalized_header.execution()?`
 ///    - Extract `next_sync_committee_hash` = `store.next_sync_committee.tree_hash_root()`
+///    - Assert: `store.next_sync_committee` is set, otherwise the store at `output_slot` could not be recovered from
+///      a checkpoint bootstrap to verify `output_store_hash` on restart
 ///
-/// 7. **Post-State Hashing** (Output Generation)
+/// 10. **Post-State Hashing** (Output Generation)
 ///    - Compute `output_store_hash` = `SHA-256(store)`
 ///
+/// # Profiling
+/// Each phase runs in a cycle-tracker span (`crate::profiling::CycleSpan`), accumulated per span name:
+/// `hash_input_store`, `verify_update`, `apply_force_update`, `verify_finality_update`, `verify_contract_storages`
+/// (nesting the `crate::mpt::OUTPUT_BLOCK_SPANS`: `verify_account_proof`, `decode_storage_proof_nodes`,
+/// `verify_storage_slot`, `hash_storage_slot` and `merkle_fold`), `verify_contract_storage_deltas` (nesting the
+/// `crate::mpt::INPUT_BLOCK_SPANS`, prefixed `input`), `verify_receipt_proofs`, `verify_historical_blocks` (nesting
+/// the `crate::mpt::HISTORICAL_BLOCK_SPANS`, prefixed `historical`) and `hash_output_store`. `debug_print` only
+/// controls the phase logs.
+///
 /// # Outputs (All Values Are Hash Commitments)
 /// | Field                                  | Type   | Description                              |
 /// |----------------------------------------|--------|------------------------------------------|
+/// | `network_identity_digest`              | `B256` | Genesis root, fork schedule and spec digest |
 /// | `input_slot`                           | `u64`  | Slot before updates                      |
 /// | `input_store_hash`                     | `B256` | Input store hash                         |
 /// | `output_slot`                          | `u64`  | Slot after updates                       |
 /// | `output_store_hash`                    | `B256` | Updated store hash                       |
+/// | `beacon_block_root`                    | `B256` | Finalized beacon block root              |
+/// | `execution_block_hash`                 | `B256` | Finalized execution block hash           |
+/// | `execution_block_number`               | `u64`  | Finalized execution block number         |
+/// | `execution_timestamp`                  | `u64`  | Finalized execution block timestamp      |
 /// | `execution_state_root`                 | `B256` | Execution layer state root               |
-/// | `verified_contract_storage_slots_root` | `B256` | Merkle root of verified storage slots    |
+/// | `receipts_root`                        | `B256` | Execution layer receipts root            |
+/// | `verified_contract_storage_slots_root` | `B256` | Merkle root-of-roots of verified slots, contracts by address and slots by derived slot key |
+/// | `contract_storage_layouts_hash`        | `B256` | Hash of the contracts' storage layouts   |
+/// | `verified_receipt_logs_root`           | `B256` | Commitment to the verified receipt logs  |
+/// | `verified_contract_storage_deltas_root` | `B256` | Commitment to the slot deltas since the input block |
+/// | `verified_historical_blocks_root`      | `B256` | Commitment to the verified historical blocks |
 /// | `next_sync_committee_hash`             | `B256` | Hash of the next sync committee state    |
+/// | `sync_committee_participants`          | `u64`  | Sync committee members that signed the finality update |
+/// | `min_sync_committee_participants`      | `u64`  | Minimum signers enforced                 |
+/// | `forced_update`                        | `bool` | Store advanced by a force update, without finality |
+/// | `program_version`                      | `u32`  | `PROGRAM_VERSION`                        |
 ///
 /// # Error Conditions
 /// 1. **Hash Chain Break**
@@ -317,20 +785,54 @@ pub fn consensus_program<S: ConsensusSpec>(
 /// 2. **Invalid Update**
 ///    Any `verify_update` returns error → Malformed or fraudulent update
 /// 3. **Invalid Finality**
-///    `verify_finality_update` fails → Unverifiable final header
+///    `verify_finality_update` fails → Unverifiable final header, or fewer than `min_sync_committee_participants`
+///    signers → `InsufficientSyncCommitteeParticipation`, neither a finality nor a force update →
+///    `MissingFinalityUpdate`, both → `FinalityUpdateWithForceUpdate`
 /// 4. **Missing Execution Root**
 ///    `store.finalized_header.execution()` is `Err` → Incomplete header data
 /// 5. **Invalid MPT Proof**
-///    `verify_storage_slot_proofs` may fail due to:
+///    `verify_contracts_storage_slot_proofs` may fail due to:
 ///    - `InvalidAccountProof { address, reason }` → Account proof failed
 ///    - `InvalidStorageSlotProof { slot_key, reason }` → Storage slot proof failed
+///    - `InvalidStorageProofNodes { address, reason }` → Storage proof node pool could not be decoded
+///    - `InvalidStorageLayout { slot_key, reason }` → Layout keys do not fit the storage layout descriptor
+///    - `NonCanonicalSlotOrder { address, previous_slot_key, slot_key }` → Slots are not strictly
+///       increasing by slot key (reordered or duplicated)
+///    - `NonCanonicalContractOrder { previous_address, address }` → Contracts are not strictly increasing by
+///       address (reordered or duplicated)
 ///    - `InvalidStorageSlotAddressMapping { slot_key, address, computed_address_slot_key }` → Slot-to-address mapping invalid
 ///    - `MerkleHashError { address, value, reason }` → Merkle hash computation error of verified slots
-///    - `ExceedsMaxTreeDepth { slots, requested_depth, max_depth }` → if the number of storage slots yields a merkle tree 
-///       which is too large.
+///    - `ExceedsMaxTreeDepth { slots, requested_depth, max_depth }` → if the number of storage slots (or contracts) yields
+///       a merkle tree which is too large.
 ///    Any of these returns a `MptError`, wrapped as `ProgramError::MptError`
+/// 6. **Invalid Storage Deltas**
+///    `verify_contracts_storage_deltas` may fail with any of the above, verifying `input_contract_storages`, or:
+///    - `StorageDeltaMismatch { address, reason }` → Input storages do not prove the same slots as `contract_storages`
+/// 7. **Invalid Receipt Proof**
+///    `verify_receipt_proofs` may fail due to:
+///    - `InvalidReceiptProof { tx_index, reason }` → Receipt proof failed
+///    - `InvalidReceiptEncoding { tx_index, reason }` → Verified receipt could not be decoded
+///    - `MissingLog { tx_index, log_index, n_logs }` → Log index out of range for the receipt
+///    - `LogMismatch { tx_index, log_index }` → Log emitter, topics or data differ from the receipt
+///    Any of these returns a `ReceiptError`, wrapped as `ProgramError::ReceiptError`
+/// 8. **Invalid Historical Block**
+///    `verify_historical_blocks` may fail due to:
+///    - `NotHistorical { slot, finalized_slot }` → Block is not older than the finalized block
+///    - `InvalidBlockRootProof { slot, reason }` → Block root not proven within `block_roots` / `historical_summaries`
+///    - `InvalidExecutionProof { slot }` → Execution payload header not proven against the block body
+///    - `InvalidStorageProof { slot, error }` / `InvalidReceiptProof { slot, error }` → Proofs targeting the block failed
+///    Any of these returns a `HistoricalBlockError`, wrapped as `ProgramError::HistoricalBlockError`
+/// 9. **Non-Progressing Transition**
+///    `output_slot <= input_slot` → `NonProgressingTransition`
+/// 10. **Non-Checkpoint Output Slot**
+///    `output_slot % S::slots_per_epoch() != 0` without a force update → `NonCheckpointOutputSlot`
+/// 11. **Missing Next Sync Committee**
+///    `store.next_sync_committee` is `None` → `MissingNextSyncCommittee`
+/// 12. **Invalid Force Update**
+///    Update timeout not elapsed at its attested slot → `ForceUpdateBeforeTimeout`, an update of the transition
+///    ranks higher → `ForceUpdateNotBestValidUpdate`, `verify_update` fails → `InvalidForceUpdate`
 /// 
-pub fn consensus_mpt_program<S: ConsensusSpec>(
+pub fn consensus_mpt_program<S: ConsensusSpecName>(
     proof_inputs: ProofInputs<S>,
     debug_print: bool,
 ) -> Result<ProofOutputs, ProgramError> {
@@ -338,20 +840,28 @@ pub fn consensus_mpt_program<S: ConsensusSpec>(
     let ProofInputs {
         updates,
         finality_update,
+        force_update,
         expected_current_slot,
         mut store,
         genesis_root,
         forks,
         store_hash: input_store_hash,
-        contract_storage,
+        min_sync_committee_participants,
+        contract_storages,
+        input_contract_storages,
+        receipt_proofs,
+        historical_blocks,
     } = proof_inputs;
 
     // 1. Calculate old store hash and assert equality
     if debug_print {
         println!("Hashing old store state and comparing with proof inputs store hash.");
     }
-    let calculated_prev_store_hash = sha256_hash_helios_store(&store)
-        .map_err(|e| ProgramError::StoreHashingError(format!("Failed to hash store: {}", e)))?;
+    let calculated_prev_store_hash = {
+        let _span = CycleSpan::new("hash_input_store");
+        sha256_hash_helios_store(&store)
+            .map_err(|e| ProgramError::StoreHashingError(format!("Failed to hash store: {}", e)))?
+    };
     if calculated_prev_store_hash != input_store_hash {
         return Err(ProgramError::HashChainMismatch {
             expected: input_store_hash,
@@ -367,14 +877,20 @@ pub fn consensus_mpt_program<S: ConsensusSpec>(
 
     // 2. State capture
     if debug_print {
-        println!("Capturing input_slot.");
+        println!("Capturing input_slot and network_identity_digest.");
     }
     let input_slot = store.finalized_header.beacon().slot;
+    let network_identity_digest = network_identity_digest::<S>(&genesis_root, &forks);
+    let input_execution_state_root = store
+        .finalized_header
+        .execution()
+        .map(|execution| *execution.state_root());
     if debug_print {
-        println!("input_slot captured.");
+        println!("input_slot and network_identity_digest captured.");
     }
 
-    // 3. Apply sync committee updates, if any
+    // 3. Apply sync committee updates, if any, tracking the best valid update a force update must rank with
+    let mut best_valid_update = None;
     for (index, update) in updates.iter().enumerate() {
         // update.finalized_header.beacon().slot; introduce printing this so we can see if we are applying updates beyond our head
         let finalized_beacon_slot = { update.finalized_header().beacon().slot };
@@ -386,8 +902,11 @@ pub fn consensus_mpt_program<S: ConsensusSpec>(
                 finalized_beacon_slot
             );
         }
-        if let Err(err) = verify_update(update, expected_current_slot, &store, genesis_root, &forks)
-        {
+        let verify_update_result = {
+            let _span = CycleSpan::new("verify_update");
+            verify_update(update, expected_current_slot, &store, genesis_root, &forks)
+        };
+        if let Err(err) = verify_update_result {
             return Err(ProgramError::InvalidUpdate {
                 index,
                 reason: format!("{:?}", err),
@@ -396,37 +915,85 @@ pub fn consensus_mpt_program<S: ConsensusSpec>(
         if debug_print {
             println!("Update {} is valid.", index + 1);
         }
+        let finalized_slot = store.finalized_header.beacon().slot;
         apply_update(&mut store, update);
+        best_valid_update =
+            track_best_valid_update(best_valid_update, update, finalized_slot, &store);
         if debug_print {
             println!("Applied update {}.", index + 1);
         }
     }
 
-    // 4. Apply finality update
-    if debug_print {
-        println!("Processing finality update.");
-    }
-    if let Err(err) = verify_finality_update(
-        &finality_update,
-        expected_current_slot,
-        &store,
-        genesis_root,
-        &forks,
-    ) {
-        return Err(ProgramError::InvalidFinalityUpdate {
-            reason: format!("{:?}", err),
-        });
-    }
+    // 4. Apply finality update, or the force update while finality is stalled
+    let sync_aggregate = match (&force_update, &finality_update) {
+        (Some(_), Some(_)) => return Err(ProgramError::FinalityUpdateWithForceUpdate),
+        (None, None) => return Err(ProgramError::MissingFinalityUpdate),
+        (Some(force_update), None) => {
+            if debug_print {
+                println!("Processing force update.");
+            }
+            {
+                let _span = CycleSpan::new("apply_force_update");
+                apply_force_update(
+                    &mut store,
+                    force_update,
+                    best_valid_update,
+                    expected_current_slot,
+                    genesis_root,
+                    &forks,
+                )?;
+            }
+            if debug_print {
+                println!("Applied force update.");
+            }
+            force_update.sync_aggregate()
+        }
+        (None, Some(finality_update)) => {
+            if debug_print {
+                println!("Processing finality update.");
+            }
+            let verify_finality_update_result = {
+                let _span = CycleSpan::new("verify_finality_update");
+                verify_finality_update(
+                    finality_update,
+                    expected_current_slot,
+                    &store,
+                    genesis_root,
+                    &forks,
+                )
+            };
+            if let Err(err) = verify_finality_update_result {
+                return Err(ProgramError::InvalidFinalityUpdate {
+                    reason: format!("{:?}", err),
+                });
+            }
+            if debug_print {
+                println!("Finality update is valid.");
+            }
+            apply_finality_update(&mut store, finality_update);
+            if debug_print {
+                println!("Applied finality update.");
+            }
+            finality_update.sync_aggregate()
+        }
+    };
+    let sync_committee_participants =
+        verify_sync_committee_participation(sync_aggregate, min_sync_committee_participants)?;
     if debug_print {
-        println!("Finality update is valid.");
+        println!(
+            "Update signed by {} sync committee members.",
+            sync_committee_participants
+        );
     }
-    apply_finality_update(&mut store, &finality_update);
+    let forced_update = force_update.is_some();
+
+    // Enforce progression, checkpoint alignment and the next sync committee of the new head
+    let (output_slot, next_sync_committee_hash) =
+        verify_transition_head(&store, input_slot, forced_update)?;
     if debug_print {
-        println!("Applied finality update.");
+        println!("Finalized head advanced to slot {}.", output_slot);
     }
 
-    // Should do an assertion here to ensure we have increased our head (we do check this downstream later)
-
     // 5. Verify storage slot proofs
     let execution_state_root_result = store.finalized_header.execution();
     if execution_state_root_result.is_err() {
@@ -434,38 +1001,89 @@ pub fn consensus_mpt_program<S: ConsensusSpec>(
     }
     let execution = execution_state_root_result.unwrap();
     let execution_state_root = *execution.state_root();
+    let receipts_root = *execution.receipts_root();
+    let execution_block_hash = *execution.block_hash();
+    let execution_block_number = *execution.block_number();
+    let execution_timestamp = *execution.timestamp();
     if debug_print {
         println!("Verifying contract storage slots.");
     }
-    let verified_slots_result = verify_storage_slot_proofs(execution_state_root, contract_storage);
+    let verified_slots_result = {
+        let _span = CycleSpan::new("verify_contract_storages");
+        verify_contracts_storage_slot_proofs(
+            execution_state_root,
+            &contract_storages,
+            &OUTPUT_BLOCK_SPANS,
+        )
+    };
     if let Err(verified_slots_err) = verified_slots_result {
         return Err(ProgramError::MptError(verified_slots_err));
     }
-    let verified_contract_storage_slots_root = verified_slots_result.unwrap();
+    let (verified_contract_storage_slots_root, contract_storage_layouts_hash) =
+        verified_slots_result.unwrap();
     if debug_print {
         println!("Contract storage slots are valid.");
     }
 
-    // 6. Commit new state root, header, and sync committee
+    // 6. Verify storage slot deltas since the input block, if requested
+    let verified_contract_storage_deltas_root = match input_contract_storages {
+        Some(input_contract_storages) => {
+            if debug_print {
+                println!("Verifying contract storage slot deltas.");
+            }
+            let input_execution_state_root =
+                input_execution_state_root.map_err(|_| ProgramError::MissingExecutionRoot)?;
+            let _span = CycleSpan::new("verify_contract_storage_deltas");
+            let verified_contract_storage_deltas_root = verify_contracts_storage_deltas(
+                input_execution_state_root,
+                &input_contract_storages,
+                &contract_storages,
+            )?;
+            if debug_print {
+                println!("Contract storage slot deltas are valid.");
+            }
+            verified_contract_storage_deltas_root
+        }
+        None => B256::ZERO,
+    };
+
+    // 7. Verify receipt log proofs
     if debug_print {
-        println!("Committing output_slot, next_sync_committee_hash.");
+        println!("Verifying receipt logs.");
     }
-    let output_slot = store.finalized_header.beacon().slot;
-    let next_sync_committee_hash: B256 = match &mut store.next_sync_committee {
-        Some(next_sync_committee) => next_sync_committee.tree_hash_root(),
-        None => B256::ZERO,
+    let verified_receipt_logs_root = {
+        let _span = CycleSpan::new("verify_receipt_proofs");
+        verify_receipt_proofs(receipts_root, receipt_proofs)?
     };
     if debug_print {
-        println!("output_slot, next_sync_committee_hash committed.");
+        println!("Receipt logs are valid.");
     }
 
-    // 7. Calculated updated store hash to be validated in the next round
+    // 8. Verify historical block proofs against the finalized state
+    if debug_print {
+        println!("Verifying historical blocks.");
+    }
+    let verified_historical_blocks_root = {
+        let _span = CycleSpan::new("verify_historical_blocks");
+        verify_historical_blocks::<S>(historical_blocks, store.finalized_header.beacon(), &forks)?
+    };
+    if debug_print {
+        println!("Historical blocks are valid.");
+    }
+
+    // 9. Commit new state root and header (the sync committee is committed with the head checks)
+    let beacon_block_root = store.finalized_header.beacon().tree_hash_root();
+
+    // 10. Calculated updated store hash to be validated in the next round
     if debug_print {
         println!("Hashing updated store.");
     }
-    let output_store_hash = sha256_hash_helios_store(&store).map_err(|e| {
-        ProgramError::StoreHashingError(format!("Failed to hash updated store: {}", e))
-    })?;
+    let output_store_hash = {
+        let _span = CycleSpan::new("hash_output_store");
+        sha256_hash_helios_store(&store).map_err(|e| {
+            ProgramError::StoreHashingError(format!("Failed to hash updated store: {}", e))
+        })?
+    };
     if debug_print {
         println!("Hashing updated store complete: {}", output_store_hash);
     }
@@ -474,13 +1092,27 @@ pub fn consensus_mpt_program<S: ConsensusSpec>(
         println!("Packing outputs.");
     }
     let proof_outputs = ProofOutputs {
+        network_identity_digest,
         input_slot,
         input_store_hash,
         output_slot,
         output_store_hash,
+        beacon_block_root,
+        execution_block_hash,
+        execution_block_number,
+        execution_timestamp,
         execution_state_root,
+        receipts_root,
         verified_contract_storage_slots_root,
-        next_sync_committee_hash
+        contract_storage_layouts_hash,
+        verified_receipt_logs_root,
+        verified_contract_storage_deltas_root,
+        verified_historical_blocks_root,
+        next_sync_committee_hash,
+        sync_committee_participants,
+        min_sync_committee_participants,
+        forced_update,
+        program_version: PROGRAM_VERSION,
     };
     if debug_print {
         println!("Packed outputs.");
@@ -488,3 +1120,312 @@ pub fn consensus_mpt_program<S: ConsensusSpec>(
 
     Ok(proof_outputs)
 }
+
+/// Zero-Knowledge Bootstrap Proof for the Ethereum Light Client Store
+///
+/// Bootstraps a store from a weak subjectivity checkpoint and commits its hash, so that the `input_store_hash` the
+/// whole proof chain starts from is proven rather than asserted by the host. The checkpoint is committed, verifiers
+/// must compare it with the root they pinned.
+///
+/// # Inputs
+/// | Name                    | Type               | Description                           |
+/// |-------------------------|--------------------|---------------------------------------|
+/// | `checkpoint`            | `B256`             | Weak subjectivity checkpoint (beacon block root) |
+/// | `bootstrap`             | `Bootstrap`        | Light client bootstrap for `checkpoint` |
+/// | `first_update`          | `Update`           | Update carrying the next sync committee |
+/// | `expected_current_slot` | `Slot`             | Current chain slot for validation     |
+/// | `genesis_root`          | `B256`             | Genesis block root                    |
+/// | `forks`                 | `ForkData`         | Network fork versions                 |
+///
+/// # Operations (In Exact Execution Order)
+/// 1. **Bootstrap Verification**
+///    - Assert: `bootstrap.header.beacon().tree_hash_root() == checkpoint` and the current sync committee branch is
+///      valid (`verify_bootstrap`)
+///    - Initialise the store from `bootstrap`
+///
+/// 2. **Next Sync Committee**
+///    - Verify `first_update` against the bootstrapped store and apply it to a copy
+///    - Take its `next_sync_committee` and participation counters, keeping the bootstrapped finalized header, as
+///      `Client::get_store_with_next_sync_committee` does on cold start
+///    - Assert: `store.next_sync_committee` is set
+///
+/// 3. **State Capture**
+///    - Record `output_slot` = `store.finalized_header.beacon().slot` and `network_identity_digest`
+///    - Extract `current_sync_committee_hash` and `next_sync_committee_hash`
+///
+/// 4. **Post-State Hashing**
+///    - Compute `output_store_hash` = `SHA-256(store)`
+///
+/// # Outputs
+/// | Field                         | Type   | Description                              |
+/// |-------------------------------|--------|------------------------------------------|
+/// | `network_identity_digest`     | `B256` | Genesis root, fork schedule and spec digest |
+/// | `checkpoint`                  | `B256` | Checkpoint the store was bootstrapped from |
+/// | `output_slot`                 | `u64`  | Finalized slot of the bootstrapped store |
+/// | `output_store_hash`           | `B256` | Bootstrapped store hash                  |
+/// | `current_sync_committee_hash` | `B256` | Hash of the current sync committee       |
+/// | `next_sync_committee_hash`    | `B256` | Hash of the next sync committee          |
+/// | `program_version`             | `u32`  | `PROGRAM_VERSION`                        |
+///
+/// # Error Conditions
+/// 1. **Invalid Bootstrap**
+///    `verify_bootstrap` returns error → `InvalidBootstrap`
+/// 2. **Invalid Update**
+///    `verify_update` fails on `first_update` → `InvalidUpdate`
+/// 3. **Missing Next Sync Committee**
+///    `first_update` carries no next sync committee → `MissingNextSyncCommittee`
+pub fn bootstrap_program<S: ConsensusSpecName>(
+    proof_inputs: BootstrapProofInputs<S>,
+    debug_print: bool,
+) -> Result<BootstrapProofOutputs, ProgramError> {
+    // Unpack inputs
+    let BootstrapProofInputs {
+        checkpoint,
+        bootstrap,
+        first_update,
+        expected_current_slot,
+        genesis_root,
+        forks,
+    } = proof_inputs;
+
+    // 1. Verify the bootstrap against the checkpoint
+    if debug_print {
+        println!("Verifying bootstrap against checkpoint {}.", checkpoint);
+    }
+    let verify_bootstrap_result = {
+        let _span = CycleSpan::new("verify_bootstrap");
+        verify_bootstrap(&bootstrap, checkpoint, &forks)
+    };
+    if let Err(err) = verify_bootstrap_result {
+        return Err(ProgramError::InvalidBootstrap {
+            reason: format!("{:?}", err),
+        });
+    }
+    let mut store = LightClientStore::<S>::default();
+    apply_bootstrap(&mut store, &bootstrap);
+    if debug_print {
+        println!(
+            "Bootstrapped store at slot {}.",
+            store.finalized_header.beacon().slot
+        );
+    }
+
+    // 2. Take the next sync committee from the first update, without advancing the bootstrapped store
+    let verify_update_result = {
+        let _span = CycleSpan::new("verify_update");
+        verify_update(&first_update, expected_current_slot, &store, genesis_root, &forks)
+    };
+    if let Err(err) = verify_update_result {
+        return Err(ProgramError::InvalidUpdate {
+            index: 0,
+            reason: format!("{:?}", err),
+        });
+    }
+    let mut synced_store = store.clone();
+    apply_update(&mut synced_store, &first_update);
+    store.next_sync_committee = synced_store.next_sync_committee;
+    store.previous_max_active_participants = synced_store.previous_max_active_participants;
+    store.current_max_active_participants = synced_store.current_max_active_participants;
+
+    // 3. State capture
+    let output_slot = store.finalized_header.beacon().slot;
+    if store.next_sync_committee.is_none() {
+        return Err(ProgramError::MissingNextSyncCommittee { output_slot });
+    }
+    let network_identity_digest = network_identity_digest::<S>(&genesis_root, &forks);
+    let (current_sync_committee_hash, next_sync_committee_hash) = sync_committee_hashes(&store);
+    if debug_print {
+        println!(
+            "Sync committees: current {}, next {}.",
+            current_sync_committee_hash, next_sync_committee_hash
+        );
+    }
+
+    // 4. Calculate the bootstrapped store hash the first transition starts from
+    let output_store_hash = {
+        let _span = CycleSpan::new("hash_output_store");
+        sha256_hash_helios_store(&store).map_err(|e| {
+            ProgramError::StoreHashingError(format!("Failed to hash bootstrapped store: {}", e))
+        })?
+    };
+    if debug_print {
+        println!("Bootstrapped store hash: {}.", output_store_hash);
+    }
+
+    Ok(BootstrapProofOutputs {
+        network_identity_digest,
+        checkpoint,
+        output_slot,
+        output_store_hash,
+        current_sync_committee_hash,
+        next_sync_committee_hash,
+        program_version: PROGRAM_VERSION,
+    })
+}
+
+/// Zero-Knowledge Sync Committee Rotation Proof for Ethereum Light Client Updates
+///
+/// Applies sync committee updates only, without a finality update, so that the sync committee chain (and the store
+/// hash chain) can be kept alive while finality is stalled and `consensus_mpt_program` cannot make progress. No
+/// execution state is committed.
+///
+/// # Inputs
+/// | Name                    | Type               | Description                           |
+/// |-------------------------|--------------------|---------------------------------------|
+/// | `updates`               | `Vec<Update>`      | Ordered sync committee updates        |
+/// | `expected_current_slot` | `Slot`             | Current chain slot for validation     |
+/// | `store`                 | `LightClientStore` | Full client state                     |
+/// | `genesis_root`          | `B256`             | Genesis block root                    |
+/// | `forks`                 | `ForkData`         | Network fork versions                 |
+/// | `store_hash`            | `B256`             | SHA-256(store) from last proof        |
+///
+/// # Operations (In Exact Execution Order)
+/// 1. **Initial Hash Validation**
+///    - Compute `SHA-256(store)`
+///    - Assert: `calculated_prev_store_hash == input_store_hash`
+///
+/// 2. **State Capture**
+///    - Record `input_slot` and `network_identity_digest`, as in `consensus_mpt_program`
+///    - Record the input `current_sync_committee` and `next_sync_committee` hashes
+///
+/// 3. **Update Processing** (Sequential, Atomic)
+///    - Assert: `updates` is not empty
+///    - Verify and apply each sync committee update in `updates`
+///
+/// 4. **State Capture**
+///    - Record `output_slot` = `store.finalized_header.beacon().slot`
+///    - Extract `current_sync_committee_hash` and `next_sync_committee_hash` (zero when absent)
+///    - Assert: the sync committee hashes differ from the input ones, every proof rotates the committee chain
+///
+/// 5. **Post-State Hashing**
+///    - Compute `output_store_hash` = `SHA-256(store)`
+///
+/// # Outputs
+/// | Field                         | Type   | Description                              |
+/// |-------------------------------|--------|------------------------------------------|
+/// | `network_identity_digest`     | `B256` | Genesis root, fork schedule and spec digest |
+/// | `input_slot`                  | `u64`  | Slot before updates                      |
+/// | `input_store_hash`            | `B256` | Input store hash                         |
+/// | `output_slot`                 | `u64`  | Finalized slot after updates             |
+/// | `output_store_hash`           | `B256` | Updated store hash                       |
+/// | `current_sync_committee_hash` | `B256` | Hash of the current sync committee       |
+/// | `next_sync_committee_hash`    | `B256` | Hash of the next sync committee          |
+/// | `n_updates`                   | `u64`  | Number of applied updates                |
+/// | `program_version`             | `u32`  | `PROGRAM_VERSION`                        |
+///
+/// # Error Conditions
+/// 1. **Hash Chain Break**
+///    `calculated_prev_store_hash != input_store_hash` → `HashChainMismatch`
+/// 2. **No Updates**
+///    `updates` is empty → `NoUpdates`
+/// 3. **Invalid Update**
+///    Any `verify_update` returns error → `InvalidUpdate`
+/// 4. **No Rotation**
+///    The sync committees are unchanged by `updates` → `NoSyncCommitteeRotation`
+pub fn rotation_program<S: ConsensusSpecName>(
+    proof_inputs: RotationProofInputs<S>,
+    debug_print: bool,
+) -> Result<RotationProofOutputs, ProgramError> {
+    // Unpack inputs
+    let RotationProofInputs {
+        updates,
+        expected_current_slot,
+        mut store,
+        genesis_root,
+        forks,
+        store_hash: input_store_hash,
+    } = proof_inputs;
+
+    // 1. Calculate old store hash and assert equality
+    if debug_print {
+        println!("Hashing old store state and comparing with proof inputs store hash.");
+    }
+    let calculated_prev_store_hash = {
+        let _span = CycleSpan::new("hash_input_store");
+        sha256_hash_helios_store(&store)
+            .map_err(|e| ProgramError::StoreHashingError(format!("Failed to hash store: {}", e)))?
+    };
+    if calculated_prev_store_hash != input_store_hash {
+        return Err(ProgramError::HashChainMismatch {
+            expected: input_store_hash,
+            actual: calculated_prev_store_hash,
+        });
+    }
+
+    // 2. State capture
+    let input_slot = store.finalized_header.beacon().slot;
+    let network_identity_digest = network_identity_digest::<S>(&genesis_root, &forks);
+    let input_sync_committee_hashes = sync_committee_hashes(&store);
+
+    // 3. Apply sync committee updates
+    if updates.is_empty() {
+        return Err(ProgramError::NoUpdates);
+    }
+    for (index, update) in updates.iter().enumerate() {
+        if debug_print {
+            println!(
+                "Processing update {} of {}. Update beacon finalized slot: {}",
+                index + 1,
+                updates.len(),
+                update.finalized_header().beacon().slot
+            );
+        }
+        let verify_update_result = {
+            let _span = CycleSpan::new("verify_update");
+            verify_update(update, expected_current_slot, &store, genesis_root, &forks)
+        };
+        if let Err(err) = verify_update_result {
+            return Err(ProgramError::InvalidUpdate {
+                index,
+                reason: format!("{:?}", err),
+            });
+        }
+        apply_update(&mut store, update);
+        if debug_print {
+            println!("Applied update {}.", index + 1);
+        }
+    }
+
+    // 4. Commit sync committees
+    let output_slot = store.finalized_header.beacon().slot;
+    let (current_sync_committee_hash, next_sync_committee_hash) = sync_committee_hashes(&store);
+    if (current_sync_committee_hash, next_sync_committee_hash) == input_sync_committee_hashes {
+        return Err(ProgramError::NoSyncCommitteeRotation);
+    }
+    if debug_print {
+        println!(
+            "Sync committees rotated: current {}, next {}.",
+            current_sync_committee_hash, next_sync_committee_hash
+        );
+    }
+
+    // 5. Calculated updated store hash to be validated in the next round
+    let output_store_hash = {
+        let _span = CycleSpan::new("hash_output_store");
+        sha256_hash_helios_store(&store).map_err(|e| {
+            ProgramError::StoreHashingError(format!("Failed to hash updated store: {}", e))
+        })?
+    };
+
+    Ok(RotationProofOutputs {
+        network_identity_digest,
+        input_slot,
+        input_store_hash,
+        output_slot,
+        output_store_hash,
+        current_sync_committee_hash,
+        next_sync_committee_hash,
+        n_updates: updates.len() as u64,
+        program_version: PROGRAM_VERSION,
+    })
+}
+
+/// Hashes of the store's current and next (zero when absent) sync committees.
+fn sync_committee_hashes<S: ConsensusSpec>(store: &LightClientStore<S>) -> (B256, B256) {
+    let current_sync_committee_hash = store.current_sync_committee.tree_hash_root();
+    let next_sync_committee_hash = match &store.next_sync_committee {
+        Some(next_sync_committee) => next_sync_committee.tree_hash_root(),
+        None => B256::ZERO,
+    };
+    (current_sync_committee_hash, next_sync_committee_hash)
+}
@@ -0,0 +1,630 @@
+use alloy_primitives::{keccak256, B256};
+use golem_symbiotic_consensus_mpt_types::{
+    network::ConsensusSpecName,
+    types::{HistoricalBlockProof, HistoricalBlockRootProof},
+};
+use helios_consensus_core::types::{BeaconBlockHeader, Forks};
+use sha2_v0_10_8::{Digest, Sha256};
+use std::fmt;
+use tree_hash::TreeHash;
+
+use crate::mpt::{verify_contracts_storage_slot_proofs, MptError, HISTORICAL_BLOCK_SPANS};
+use crate::receipts::{verify_receipt_proofs, ReceiptError};
+
+// SSZ layout constants of the beacon state and beacon block body, `block_roots` is sized by the spec
+// (`ConsensusSpecName::slots_per_historical_root`)
+
+const BEACON_STATE_DEPTH: usize = 5; // 28 fields (Capella, Deneb)
+const BEACON_STATE_DEPTH_ELECTRA: usize = 6; // 37 fields (Electra)
+const BEACON_STATE_BLOCK_ROOTS_INDEX: u64 = 5;
+const BEACON_STATE_HISTORICAL_SUMMARIES_INDEX: u64 = 27;
+const HISTORICAL_SUMMARIES_LIMIT_DEPTH: usize = 24; // log2(HISTORICAL_ROOTS_LIMIT)
+const BEACON_BLOCK_BODY_DEPTH: usize = 4;
+const BEACON_BLOCK_BODY_EXECUTION_PAYLOAD_INDEX: u64 = 9;
+
+/// Custom historical block errors
+
+#[derive(Debug)]
+pub enum HistoricalBlockError {
+    NotHistorical {
+        slot: u64,
+        finalized_slot: u64,
+    },
+    InvalidBlockRootProof {
+        slot: u64,
+        reason: String,
+    },
+    InvalidExecutionProof {
+        slot: u64,
+    },
+    InvalidStorageProof {
+        slot: u64,
+        error: MptError,
+    },
+    InvalidReceiptProof {
+        slot: u64,
+        error: ReceiptError,
+    },
+}
+
+impl fmt::Display for HistoricalBlockError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HistoricalBlockError::NotHistorical {
+                slot,
+                finalized_slot,
+            } => write!(
+                f,
+                "Historical block slot {} is not before the finalized slot {}",
+                slot, finalized_slot
+            ),
+            HistoricalBlockError::InvalidBlockRootProof { slot, reason } => write!(
+                f,
+                "Block root proof of historical block at slot {} is invalid: {}",
+                slot, reason
+            ),
+            HistoricalBlockError::InvalidExecutionProof { slot } => write!(
+                f,
+                "Execution payload proof of historical block at slot {} is invalid",
+                slot
+            ),
+            HistoricalBlockError::InvalidStorageProof { slot, error } => write!(
+                f,
+                "Storage proof of historical block at slot {} is invalid: {}",
+                slot, error
+            ),
+            HistoricalBlockError::InvalidReceiptProof { slot, error } => write!(
+                f,
+                "Receipt proof of historical block at slot {} is invalid: {}",
+                slot, error
+            ),
+        }
+    }
+}
+
+/// Verifies an SSZ Merkle branch of `depth` from `leaf` at `index` (relative to the subtree) up to `root`.
+pub fn is_valid_merkle_branch(
+    leaf: B256,
+    branch: &[B256],
+    depth: usize,
+    index: u64,
+    root: B256,
+) -> bool {
+    if branch.len() != depth {
+        return false;
+    }
+
+    let mut value = leaf;
+    for (i, node) in branch.iter().enumerate() {
+        let mut hasher = Sha256::new();
+        if (index >> i) & 1 == 1 {
+            hasher.update(node.as_slice());
+            hasher.update(value.as_slice());
+        } else {
+            hasher.update(value.as_slice());
+            hasher.update(node.as_slice());
+        }
+        value = B256::from_slice(&hasher.finalize());
+    }
+
+    value == root
+}
+
+/// Depth of the beacon state container at `slot`, which grew with the Electra fork.
+fn beacon_state_depth<S: ConsensusSpecName>(slot: u64, forks: &Forks) -> usize {
+    if slot / S::slots_per_epoch() >= forks.electra.epoch {
+        BEACON_STATE_DEPTH_ELECTRA
+    } else {
+        BEACON_STATE_DEPTH
+    }
+}
+
+/// Verifies that `header` is an ancestor of the finalized beacon block, by proving its root within the finalized
+/// beacon state's `block_roots` or `historical_summaries`, and returns the historical beacon block root.
+///
+/// - `BlockRoots`: the block must be within the last `SLOTS_PER_HISTORICAL_ROOT` slots of the finalized state (from
+///   the spec `S`).
+///   The leaf `state.block_roots[slot % SLOTS_PER_HISTORICAL_ROOT]` is proven against the finalized state root.
+/// - `HistoricalSummaries`: the block's period must be complete and after the Capella fork. The block root is proven
+///   within `block_summary_root`, which is proven as `state.historical_summaries[period - capella_period].block_summary_root`
+///   against the finalized state root.
+pub fn verify_historical_block_root<S: ConsensusSpecName>(
+    header: &BeaconBlockHeader,
+    block_root_proof: &HistoricalBlockRootProof,
+    finalized_header: &BeaconBlockHeader,
+    forks: &Forks,
+) -> Result<B256, HistoricalBlockError> {
+    let slot = header.slot;
+    let finalized_slot = finalized_header.slot;
+    if slot >= finalized_slot {
+        return Err(HistoricalBlockError::NotHistorical {
+            slot,
+            finalized_slot,
+        });
+    }
+
+    let block_root = header.tree_hash_root();
+    let state_depth = beacon_state_depth::<S>(finalized_slot, forks);
+    let slots_per_historical_root = S::slots_per_historical_root();
+    let block_roots_depth = slots_per_historical_root.trailing_zeros() as usize;
+    let block_roots_index = slot % slots_per_historical_root;
+
+    match block_root_proof {
+        HistoricalBlockRootProof::BlockRoots { branch } => {
+            if finalized_slot > slot + slots_per_historical_root {
+                return Err(HistoricalBlockError::InvalidBlockRootProof {
+                    slot,
+                    reason: "block is no longer within the finalized state's block_roots".to_string(),
+                });
+            }
+            let index = BEACON_STATE_BLOCK_ROOTS_INDEX * slots_per_historical_root + block_roots_index;
+            if !is_valid_merkle_branch(
+                block_root,
+                branch,
+                state_depth + block_roots_depth,
+                index,
+                finalized_header.state_root,
+            ) {
+                return Err(HistoricalBlockError::InvalidBlockRootProof {
+                    slot,
+                    reason: "block_roots branch does not match the finalized state root".to_string(),
+                });
+            }
+        }
+        HistoricalBlockRootProof::HistoricalSummaries {
+            block_summary_root,
+            block_roots_branch,
+            historical_summary_branch,
+        } => {
+            let period = slot / slots_per_historical_root;
+            let capella_period =
+                forks.capella.epoch * S::slots_per_epoch() / slots_per_historical_root;
+            if period < capella_period {
+                return Err(HistoricalBlockError::InvalidBlockRootProof {
+                    slot,
+                    reason: "historical_summaries only cover blocks from the Capella fork onwards"
+                        .to_string(),
+                });
+            }
+            if (period + 1) * slots_per_historical_root > finalized_slot {
+                return Err(HistoricalBlockError::InvalidBlockRootProof {
+                    slot,
+                    reason: "block's historical period is not yet summarized".to_string(),
+                });
+            }
+
+            // Block root within the period's block_roots vector
+            if !is_valid_merkle_branch(
+                block_root,
+                block_roots_branch,
+                block_roots_depth,
+                block_roots_index,
+                *block_summary_root,
+            ) {
+                return Err(HistoricalBlockError::InvalidBlockRootProof {
+                    slot,
+                    reason: "block_roots branch does not match the block summary root".to_string(),
+                });
+            }
+
+            // block_summary_root of historical_summaries[summary_index] within the finalized state:
+            // state field -> list data root (length mix-in) -> list element -> HistoricalSummary field 0
+            let summary_index = period - capella_period;
+            let index = (((BEACON_STATE_HISTORICAL_SUMMARIES_INDEX << 1)
+                << HISTORICAL_SUMMARIES_LIMIT_DEPTH)
+                | summary_index)
+                << 1;
+            if !is_valid_merkle_branch(
+                *block_summary_root,
+                historical_summary_branch,
+                state_depth + 1 + HISTORICAL_SUMMARIES_LIMIT_DEPTH + 1,
+                index,
+                finalized_header.state_root,
+            ) {
+                return Err(HistoricalBlockError::InvalidBlockRootProof {
+                    slot,
+                    reason: "historical_summaries branch does not match the finalized state root"
+                        .to_string(),
+                });
+            }
+        }
+    }
+
+    Ok(block_root)
+}
+
+/// Verifies a historical block against the finalized beacon state and the storage and receipt proofs targeting it,
+/// returning the keccak256 leaf commitment of the verified block.
+///
+/// Leaf layout (`abi.encodePacked`):
+/// - `bytes32 beacon_block_root`
+/// - `uint64 slot`
+/// - `uint64 block_number`
+/// - `bytes32 block_hash`
+/// - `bytes32 execution_state_root`
+/// - `bytes32 receipts_root`
+/// - `uint64 timestamp`
+/// - `bytes32 verified_contract_storage_slots_root`
+/// - `bytes32 contract_storage_layouts_hash`
+/// - `bytes32 verified_receipt_logs_root`
+pub fn verify_historical_block<S: ConsensusSpecName>(
+    historical_block: HistoricalBlockProof<S>,
+    finalized_header: &BeaconBlockHeader,
+    forks: &Forks,
+) -> Result<B256, HistoricalBlockError> {
+    let HistoricalBlockProof {
+        header,
+        block_root_proof,
+        execution,
+        execution_branch,
+        contract_storages,
+        receipt_proofs,
+    } = historical_block;
+    let slot = header.slot;
+
+    // 1) Link the historical beacon block to the finalized state
+    let beacon_block_root =
+        verify_historical_block_root::<S>(&header, &block_root_proof, finalized_header, forks)?;
+
+    // 2) Link the execution payload header to the historical beacon block body
+    if !is_valid_merkle_branch(
+        execution.tree_hash_root(),
+        &execution_branch,
+        BEACON_BLOCK_BODY_DEPTH,
+        BEACON_BLOCK_BODY_EXECUTION_PAYLOAD_INDEX,
+        header.body_root,
+    ) {
+        return Err(HistoricalBlockError::InvalidExecutionProof { slot });
+    }
+    let execution_state_root = *execution.state_root();
+    let receipts_root = *execution.receipts_root();
+    let block_hash = *execution.block_hash();
+    let block_number = *execution.block_number();
+    let timestamp = *execution.timestamp();
+
+    // 3) Verify the storage and receipt proofs targeting the historical block
+    let (verified_contract_storage_slots_root, contract_storage_layouts_hash) =
+        verify_contracts_storage_slot_proofs(
+            execution_state_root,
+            &contract_storages,
+            &HISTORICAL_BLOCK_SPANS,
+        )
+        .map_err(|error| HistoricalBlockError::InvalidStorageProof { slot, error })?;
+    let verified_receipt_logs_root = verify_receipt_proofs(receipts_root, receipt_proofs)
+        .map_err(|error| HistoricalBlockError::InvalidReceiptProof { slot, error })?;
+
+    let mut encoded = Vec::with_capacity(32 + 8 + 8 + 32 + 32 + 32 + 8 + 32 + 32 + 32);
+    encoded.extend_from_slice(beacon_block_root.as_slice());
+    encoded.extend_from_slice(&slot.to_be_bytes());
+    encoded.extend_from_slice(&block_number.to_be_bytes());
+    encoded.extend_from_slice(block_hash.as_slice());
+    encoded.extend_from_slice(execution_state_root.as_slice());
+    encoded.extend_from_slice(receipts_root.as_slice());
+    encoded.extend_from_slice(&timestamp.to_be_bytes());
+    encoded.extend_from_slice(verified_contract_storage_slots_root.as_slice());
+    encoded.extend_from_slice(contract_storage_layouts_hash.as_slice());
+    encoded.extend_from_slice(verified_receipt_logs_root.as_slice());
+
+    Ok(keccak256(encoded))
+}
+
+/// Verifies each historical block with `verify_historical_block` and returns
+/// `keccak256(leaf_0 ‖ leaf_1 ‖ … ‖ leaf_n)` in input order, or `B256::ZERO` when there are none.
+pub fn verify_historical_blocks<S: ConsensusSpecName>(
+    historical_blocks: Vec<HistoricalBlockProof<S>>,
+    finalized_header: &BeaconBlockHeader,
+    forks: &Forks,
+) -> Result<B256, HistoricalBlockError> {
+    let mut block_leaves: Vec<u8> = Vec::new();
+
+    for historical_block in historical_blocks {
+        block_leaves.extend_from_slice(
+            verify_historical_block::<S>(historical_block, finalized_header, forks)?.as_slice(),
+        );
+    }
+
+    if block_leaves.is_empty() {
+        return Ok(B256::ZERO);
+    }
+
+    Ok(keccak256(block_leaves))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::b256;
+    use helios_consensus_core::consensus_spec::MinimalConsensusSpec;
+    use helios_consensus_core::types::{ExecutionPayloadHeader, ExecutionPayloadHeaderDeneb};
+
+    type S = MinimalConsensusSpec; // 8 slots per epoch, 64 slots per historical root
+
+    fn hash_pair(left: &B256, right: &B256) -> B256 {
+        let mut hasher = Sha256::new();
+        hasher.update(left.as_slice());
+        hasher.update(right.as_slice());
+        B256::from_slice(&hasher.finalize())
+    }
+
+    // Root of the zero padded tree of `depth` over `leaves`, and the branch of the leaf at `index`
+    fn merkle_root_and_branch(leaves: &[B256], depth: usize, index: usize) -> (B256, Vec<B256>) {
+        let mut level = leaves.to_vec();
+        let mut zero_hash = B256::ZERO;
+        let mut branch = Vec::with_capacity(depth);
+        for i in 0..depth {
+            branch.push(level.get((index >> i) ^ 1).copied().unwrap_or(zero_hash));
+            level = level
+                .chunks(2)
+                .map(|pair| hash_pair(&pair[0], pair.get(1).unwrap_or(&zero_hash)))
+                .collect();
+            if level.is_empty() {
+                level.push(hash_pair(&zero_hash, &zero_hash));
+            }
+            zero_hash = hash_pair(&zero_hash, &zero_hash);
+        }
+        (level[0], branch)
+    }
+
+    // Fork schedule with Capella from genesis and Electra from `electra_epoch`
+    fn forks(electra_epoch: u64) -> Forks {
+        let mut forks = Forks::default();
+        forks.electra.epoch = electra_epoch;
+        forks
+    }
+
+    fn header(slot: u64, body_root: B256) -> BeaconBlockHeader {
+        BeaconBlockHeader {
+            slot,
+            proposer_index: 7,
+            parent_root: B256::repeat_byte(0x0a),
+            state_root: B256::repeat_byte(0x0b),
+            body_root,
+        }
+    }
+
+    fn finalized_header(slot: u64, state_root: B256) -> BeaconBlockHeader {
+        BeaconBlockHeader {
+            slot,
+            state_root,
+            ..Default::default()
+        }
+    }
+
+    // Block roots of a period, `block_root` at `slot`, others distinct
+    fn block_roots(slot: u64, block_root: B256) -> Vec<B256> {
+        (0..64u8)
+            .map(|i| if i as u64 == slot % 64 { block_root } else { B256::with_last_byte(i + 1) })
+            .collect()
+    }
+
+    // Beacon state fields of `depth`, with `field` at `index` and the others distinct
+    fn state_root_and_branch(depth: usize, index: usize, field: B256) -> (B256, Vec<B256>) {
+        let fields: Vec<B256> = (0..(if depth == 6 { 37 } else { 28 }))
+            .map(|i| if i == index { field } else { B256::repeat_byte(0xf0 | i as u8 % 16) })
+            .collect();
+        merkle_root_and_branch(&fields, depth, index)
+    }
+
+    // BlockRoots proof of `header` against a finalized state of `state_depth` at `finalized_slot`
+    fn block_roots_proof(
+        header: &BeaconBlockHeader,
+        state_depth: usize,
+        finalized_slot: u64,
+    ) -> (HistoricalBlockRootProof, BeaconBlockHeader) {
+        let roots = block_roots(header.slot, header.tree_hash_root());
+        let (block_roots_root, mut branch) = merkle_root_and_branch(&roots, 6, (header.slot % 64) as usize);
+        let (state_root, state_branch) = state_root_and_branch(state_depth, 5, block_roots_root);
+        branch.extend(state_branch);
+        (
+            HistoricalBlockRootProof::BlockRoots { branch },
+            finalized_header(finalized_slot, state_root),
+        )
+    }
+
+    // HistoricalSummaries proof of `header`, the `summary_index`-th of `summaries` summaries of a finalized state of
+    // `state_depth` at `finalized_slot`
+    fn historical_summaries_proof(
+        header: &BeaconBlockHeader,
+        summary_index: usize,
+        summaries: usize,
+        state_depth: usize,
+        finalized_slot: u64,
+    ) -> (HistoricalBlockRootProof, BeaconBlockHeader) {
+        let roots = block_roots(header.slot, header.tree_hash_root());
+        let (block_summary_root, block_roots_branch) =
+            merkle_root_and_branch(&roots, 6, (header.slot % 64) as usize);
+        let state_summary_root = B256::repeat_byte(0x5e);
+        let summary_roots: Vec<B256> = (0..summaries)
+            .map(|i| {
+                if i == summary_index {
+                    hash_pair(&block_summary_root, &state_summary_root)
+                } else {
+                    B256::repeat_byte(0x50 + i as u8)
+                }
+            })
+            .collect();
+        let (data_root, data_branch) =
+            merkle_root_and_branch(&summary_roots, HISTORICAL_SUMMARIES_LIMIT_DEPTH, summary_index);
+        let mut length = B256::ZERO;
+        length[..8].copy_from_slice(&(summaries as u64).to_le_bytes());
+        let (state_root, state_branch) =
+            state_root_and_branch(state_depth, 27, hash_pair(&data_root, &length));
+
+        let mut historical_summary_branch = vec![state_summary_root];
+        historical_summary_branch.extend(data_branch);
+        historical_summary_branch.push(length);
+        historical_summary_branch.extend(state_branch);
+        (
+            HistoricalBlockRootProof::HistoricalSummaries {
+                block_summary_root,
+                block_roots_branch,
+                historical_summary_branch,
+            },
+            finalized_header(finalized_slot, state_root),
+        )
+    }
+
+    #[test]
+    fn zero_subtree_branches_fold_to_the_known_zero_hashes() {
+        // Roots of the all zero trees of depth 1, 2 and 3 (the deposit contract's zero hashes)
+        let zero_hashes = [
+            B256::ZERO,
+            b256!("f5a5fd42d16a20302798ef6ed309979b43003d2320d9f0e8ea9831a92759fb4b"),
+            b256!("db56114e00fdd4c1f85c892bf35ac9a89289aaecb1ebd0a96cde606a748b5d71"),
+            b256!("c78009fdf07fc56a11f122370658a353aaa542ed63e44c4bc15ff4cd105ab33c"),
+        ];
+        for index in 0..8 {
+            assert!(is_valid_merkle_branch(B256::ZERO, &zero_hashes[..3], 3, index, zero_hashes[3]));
+        }
+        assert!(!is_valid_merkle_branch(B256::ZERO, &zero_hashes[..3], 2, 0, zero_hashes[3]));
+        assert!(!is_valid_merkle_branch(B256::ZERO, &zero_hashes[..2], 2, 0, zero_hashes[3]));
+    }
+
+    #[test]
+    fn every_leaf_verifies_at_its_own_index_only() {
+        let leaves: Vec<B256> = (1..=5).map(B256::repeat_byte).collect();
+        for index in 0..8 {
+            let (root, branch) = merkle_root_and_branch(&leaves, 3, index);
+            let leaf = leaves.get(index).copied().unwrap_or(B256::ZERO);
+            assert!(is_valid_merkle_branch(leaf, &branch, 3, index as u64, root));
+            // Pairs of zero padding leaves are symmetric, the first level pairs are not
+            assert!(!is_valid_merkle_branch(leaf, &branch, 3, index as u64 ^ 2, root));
+            // Indices beyond the depth are not part of the path
+            assert!(is_valid_merkle_branch(leaf, &branch, 3, index as u64 | 8, root));
+        }
+    }
+
+    #[test]
+    fn proves_recent_blocks_within_block_roots() {
+        let header = header(100, B256::repeat_byte(0xbd));
+        let (proof, finalized) = block_roots_proof(&header, BEACON_STATE_DEPTH, 128);
+        let HistoricalBlockRootProof::BlockRoots { branch } = &proof else { unreachable!() };
+        assert_eq!(branch.len(), BEACON_STATE_DEPTH + 6); // block_roots vector, then the 28 state fields
+
+        assert_eq!(
+            verify_historical_block_root::<S>(&header, &proof, &finalized, &forks(u64::MAX)).unwrap(),
+            header.tree_hash_root()
+        );
+        // A block a whole period old is still within block_roots, one slot more is not
+        let (proof, finalized) = block_roots_proof(&header, BEACON_STATE_DEPTH, 164);
+        assert!(verify_historical_block_root::<S>(&header, &proof, &finalized, &forks(u64::MAX)).is_ok());
+        let (proof, finalized) = block_roots_proof(&header, BEACON_STATE_DEPTH, 165);
+        assert!(matches!(
+            verify_historical_block_root::<S>(&header, &proof, &finalized, &forks(u64::MAX)),
+            Err(HistoricalBlockError::InvalidBlockRootProof { slot: 100, .. })
+        ));
+        // Only blocks before the finalized one are historical
+        let (proof, finalized) = block_roots_proof(&header, BEACON_STATE_DEPTH, 100);
+        assert!(matches!(
+            verify_historical_block_root::<S>(&header, &proof, &finalized, &forks(u64::MAX)),
+            Err(HistoricalBlockError::NotHistorical { slot: 100, finalized_slot: 100 })
+        ));
+    }
+
+    #[test]
+    fn proves_older_blocks_within_historical_summaries() {
+        // Period 3 (slots 192..256), the fourth summary since Capella (genesis)
+        let header = header(200, B256::repeat_byte(0xbd));
+        let (proof, finalized) = historical_summaries_proof(&header, 3, 5, BEACON_STATE_DEPTH, 400);
+        let HistoricalBlockRootProof::HistoricalSummaries { block_roots_branch, historical_summary_branch, .. } =
+            &proof
+        else {
+            unreachable!()
+        };
+        assert_eq!(block_roots_branch.len(), 6);
+        // state_summary_root, the list data, its length and the 28 state fields
+        assert_eq!(historical_summary_branch.len(), 1 + 24 + 1 + BEACON_STATE_DEPTH);
+
+        assert_eq!(
+            verify_historical_block_root::<S>(&header, &proof, &finalized, &forks(u64::MAX)).unwrap(),
+            header.tree_hash_root()
+        );
+        // The summary index counts periods from the Capella fork, at epoch 8 (period 1) it is 2
+        let mut capella_at_period_1 = forks(u64::MAX);
+        capella_at_period_1.capella.epoch = 8;
+        assert!(verify_historical_block_root::<S>(&header, &proof, &finalized, &capella_at_period_1).is_err());
+        let (proof, finalized) = historical_summaries_proof(&header, 2, 5, BEACON_STATE_DEPTH, 400);
+        assert!(verify_historical_block_root::<S>(&header, &proof, &finalized, &capella_at_period_1).is_ok());
+
+        // The period is summarized once the finalized state reaches its end
+        let (proof, finalized) = historical_summaries_proof(&header, 3, 4, BEACON_STATE_DEPTH, 255);
+        assert!(verify_historical_block_root::<S>(&header, &proof, &finalized, &forks(u64::MAX)).is_err());
+    }
+
+    #[test]
+    fn electra_states_need_one_more_level() {
+        let header = header(100, B256::repeat_byte(0xbd));
+
+        // A 37 field Electra state is proven with depth 6 branches
+        let (proof, finalized) = block_roots_proof(&header, BEACON_STATE_DEPTH_ELECTRA, 128);
+        assert!(verify_historical_block_root::<S>(&header, &proof, &finalized, &forks(0)).is_ok());
+        // and refused where the finalized state predates Electra
+        assert!(verify_historical_block_root::<S>(&header, &proof, &finalized, &forks(u64::MAX)).is_err());
+
+        // A depth 5 branch is one level short of an Electra state root
+        let (proof, finalized) = block_roots_proof(&header, BEACON_STATE_DEPTH, 128);
+        assert!(verify_historical_block_root::<S>(&header, &proof, &finalized, &forks(0)).is_err());
+
+        // The fork is decided by the finalized slot, Electra from epoch 16 (slot 128)
+        let (proof, finalized) = historical_summaries_proof(&header, 1, 2, BEACON_STATE_DEPTH_ELECTRA, 128);
+        assert!(verify_historical_block_root::<S>(&header, &proof, &finalized, &forks(16)).is_ok());
+        let (proof, finalized) = historical_summaries_proof(&header, 1, 2, BEACON_STATE_DEPTH, 128);
+        assert!(verify_historical_block_root::<S>(&header, &proof, &finalized, &forks(16)).is_err());
+    }
+
+    #[test]
+    fn verifies_the_execution_payload_of_a_historical_block() {
+        let execution = ExecutionPayloadHeader::<S>::Deneb(ExecutionPayloadHeaderDeneb {
+            block_number: 42,
+            timestamp: 1_700_000_000,
+            block_hash: B256::repeat_byte(0xbb),
+            state_root: B256::repeat_byte(0x5a),
+            receipts_root: B256::repeat_byte(0x7e),
+            ..Default::default()
+        });
+        let body_fields: Vec<B256> = (0..12u8)
+            .map(|i| if i == 9 { execution.tree_hash_root() } else { B256::repeat_byte(i) })
+            .collect();
+        let (body_root, execution_branch) = merkle_root_and_branch(&body_fields, BEACON_BLOCK_BODY_DEPTH, 9);
+        let header = header(100, body_root);
+        let (block_root_proof, finalized) = block_roots_proof(&header, BEACON_STATE_DEPTH, 128);
+        let historical_block = HistoricalBlockProof::<S> {
+            header: header.clone(),
+            block_root_proof,
+            execution,
+            execution_branch,
+            contract_storages: Vec::new(),
+            receipt_proofs: Vec::new(),
+        };
+
+        let leaf = verify_historical_block::<S>(historical_block.clone(), &finalized, &forks(u64::MAX)).unwrap();
+        let (slots_root, layouts_hash) =
+            verify_contracts_storage_slot_proofs(B256::repeat_byte(0x5a), &[], &HISTORICAL_BLOCK_SPANS).unwrap();
+        let mut encoded = Vec::new();
+        encoded.extend_from_slice(header.tree_hash_root().as_slice());
+        encoded.extend_from_slice(&100u64.to_be_bytes());
+        encoded.extend_from_slice(&42u64.to_be_bytes());
+        encoded.extend_from_slice(B256::repeat_byte(0xbb).as_slice());
+        encoded.extend_from_slice(B256::repeat_byte(0x5a).as_slice());
+        encoded.extend_from_slice(B256::repeat_byte(0x7e).as_slice());
+        encoded.extend_from_slice(&1_700_000_000u64.to_be_bytes());
+        encoded.extend_from_slice(slots_root.as_slice());
+        encoded.extend_from_slice(layouts_hash.as_slice());
+        encoded.extend_from_slice(B256::ZERO.as_slice()); // no receipt logs
+        assert_eq!(leaf, keccak256(encoded));
+
+        // The execution payload must sit at field 9 of the body
+        let mut tampered = historical_block.clone();
+        tampered.execution_branch.swap(0, 1);
+        assert!(matches!(
+            verify_historical_block::<S>(tampered, &finalized, &forks(u64::MAX)),
+            Err(HistoricalBlockError::InvalidExecutionProof { slot: 100 })
+        ));
+        let mut short = historical_block;
+        short.execution_branch.pop();
+        assert!(matches!(
+            verify_historical_block::<S>(short, &finalized, &forks(u64::MAX)),
+            Err(HistoricalBlockError::InvalidExecutionProof { slot: 100 })
+        ));
+    }
+}
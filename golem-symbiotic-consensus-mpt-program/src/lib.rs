@@ -1,2 +1,6 @@
 pub mod consensus;
-pub mod mpt;
\ No newline at end of file
+pub mod historical;
+pub mod mpt;
+pub mod multiproof;
+pub mod profiling;
+pub mod receipts;
\ No newline at end of file
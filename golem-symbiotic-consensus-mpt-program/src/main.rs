@@ -1,8 +1,13 @@
 #![no_main]
 sp1_zkvm::entrypoint!(main);
-use helios_consensus_core::consensus_spec::MainnetConsensusSpec;
+#[cfg(not(feature = "minimal"))]
+use helios_consensus_core::consensus_spec::MainnetConsensusSpec as ProgramConsensusSpec;
+#[cfg(feature = "minimal")]
+use helios_consensus_core::consensus_spec::MinimalConsensusSpec as ProgramConsensusSpec;
+use golem_symbiotic_consensus_mpt_types::guest_input::decode_guest_inputs;
 use golem_symbiotic_consensus_mpt_types::types::ProofInputs;
 use golem_symbiotic_consensus_mpt_program::consensus::consensus_mpt_program;
+use golem_symbiotic_consensus_mpt_program::profiling::CycleSpan;
 
 pub fn main() {
     // Read zk input
@@ -10,7 +15,10 @@ pub fn main() {
 
     // Decode inputs
     println!("Decoding inputs");
-    let proof_inputs: ProofInputs<MainnetConsensusSpec> = serde_cbor::from_slice(&encoded_inputs).unwrap();
+    let proof_inputs: ProofInputs<ProgramConsensusSpec> = {
+        let _span = CycleSpan::new("decode_inputs");
+        decode_guest_inputs(&encoded_inputs).unwrap()
+    };
     println!("Decoded inputs");
 
     // Run nori sp1 helios zk program
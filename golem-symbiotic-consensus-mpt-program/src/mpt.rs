@@ -1,15 +1,16 @@
 use alloy_primitives::{keccak256, Address, Bytes, FixedBytes, Uint, B256, U256};
 use alloy_rlp::Encodable;
-use alloy_trie::{proof, Nibbles};
+use alloy_trie::{proof, Nibbles, EMPTY_ROOT_HASH};
 use anyhow::Result;
 use nori_hash::merkle_poseidon_fixed::{
     compute_merkle_tree_depth_and_size, fold_merkle_left, get_merkle_zeros,
     MAX_TREE_DEPTH,
 };
-use golem_symbiotic_consensus_mpt_types::types::{
-    SOURCE_CONTRACT_LOCKED_TOKENS_STORAGE_INDEX,
-    get_storage_location_for_key,
-    ContractStorage,
+use crate::multiproof::MptNodePool;
+use crate::profiling::CycleSpan;
+use golem_symbiotic_consensus_mpt_types::{
+    storage_layout::StorageLayout,
+    types::ContractStorage,
 };
 use o1_utils::FieldHelpers;
 use std::fmt;
@@ -39,6 +40,10 @@ pub enum MptError {
         address: Address,
         computed_address_slot_key: B256,
     },
+    InvalidStorageLayout {
+        slot_key: B256,
+        reason: String,
+    },
     MerkleHashError {
         address: Address,
         value: Uint<256, 4>,
@@ -49,6 +54,28 @@ pub enum MptError {
         requested_depth: usize,
         max_depth: usize,
     },
+    StorageDeltaMismatch {
+        address: Address,
+        reason: String,
+    },
+    /// The storage slots of a contract are not strictly increasing by derived slot key. This is the canonical slot
+    /// order committed in `verified_contract_storage_slots_root`, not `slot_key_address`, which nested mappings
+    /// repeat across slots.
+    NonCanonicalSlotOrder {
+        address: Address,
+        previous_slot_key: B256,
+        slot_key: B256,
+    },
+    /// The contract storages are not strictly increasing by contract address, the canonical contract order
+    /// committed in `verified_contract_storage_slots_root`.
+    NonCanonicalContractOrder {
+        previous_address: Address,
+        address: Address,
+    },
+    InvalidStorageProofNodes {
+        address: Address,
+        reason: String,
+    },
 }
 
 impl fmt::Display for MptError {
@@ -73,6 +100,12 @@ impl fmt::Display for MptError {
                 address,
                 computed_address_slot_key
             ),
+            MptError::InvalidStorageLayout { slot_key, reason } => write!(
+                f,
+                "MPT storage layout could not derive slot {:?}: {:?}",
+                slot_key,
+                reason
+            ),
             MptError::MerkleHashError { address, value , reason} => write!(
                 f,
                 "MPT error computing merkle hash of verified slots, address {:?} and value {:?}: {:?}",
@@ -91,6 +124,38 @@ impl fmt::Display for MptError {
                 slots,
                 max_depth
             ),
+            MptError::StorageDeltaMismatch { address, reason } => write!(
+                f,
+                "Input and output storage of contract {:?} do not prove the same slots: {:?}",
+                address,
+                reason
+            ),
+            MptError::NonCanonicalSlotOrder {
+                address,
+                previous_slot_key,
+                slot_key,
+            } => write!(
+                f,
+                "Storage slots of contract {:?} are not strictly increasing by slot key: {:?} follows {:?}",
+                address,
+                slot_key,
+                previous_slot_key
+            ),
+            MptError::NonCanonicalContractOrder {
+                previous_address,
+                address,
+            } => write!(
+                f,
+                "Contract storages are not strictly increasing by address: {:?} follows {:?}",
+                address,
+                previous_address
+            ),
+            MptError::InvalidStorageProofNodes { address, reason } => write!(
+                f,
+                "MPT storage proof nodes of contract {:?} are invalid: {:?}",
+                address,
+                reason
+            ),
         }
     }
 }
@@ -105,46 +170,148 @@ pub fn poseidon_hash(input: &[Fp]) -> Fp {
 }
 
 
-/// Computes a Poseidon hash for a storage slot leaf node given a contract address
-/// and a 32-byte value. No attestation_hash is included.
+/// Marker byte of a leaf whose key was proven to be absent from its trie (non-inclusion proof).
+pub const ABSENT_LEAF_FLAG: u8 = 1u8;
+
+/// Computes a Poseidon hash for a storage slot leaf node given the address the slot is attributed to,
+/// the derived storage slot key and a 32-byte value. No attestation_hash is included.
+///
+/// The slot key is committed so that the leaf says which slot was proven for any layout, including
+/// layouts whose keys are not addresses (the address is then zero).
+///
+/// Zero values are never stored in the storage trie, so a zero value is always proven by a
+/// non-inclusion proof and committed as an explicit absent leaf. This lets consumers tell a slot
+/// proven to be zero apart from a slot which was not proven at all (no leaf).
 ///
 /// Layout:
-/// - First field: 20-byte address + first byte of value (21 bytes total, padded to 32)
+/// - First field: 20-byte address + first byte of value + absent flag (22 bytes total, padded to 32)
 /// - Second field: remaining 31 bytes of value (padded to 32)
+/// - Third field: first 16 bytes of the slot key (padded to 32)
+/// - Fourth field: last 16 bytes of the slot key (padded to 32)
 pub fn hash_storage_slot(
     address: &Address,
+    slot_key: &B256,
     value: &U256,
 ) -> Result<Fp> {
     let address_slice = address.as_slice();
     let value_bytes = value.to_be_bytes::<32>();
 
-    // First field: address (20 bytes) + first byte of value
+    // First field: address (20 bytes) + first byte of value + absent flag
     let mut first_field_bytes = [0u8; 32];
     first_field_bytes[0..20].copy_from_slice(&address_slice[0..20]);
     first_field_bytes[20] = value_bytes[0];
+    if value.is_zero() {
+        first_field_bytes[21] = ABSENT_LEAF_FLAG;
+    }
 
     // Second field: remaining 31 bytes of value
     let mut second_field_bytes = [0u8; 32];
     second_field_bytes[0..31].copy_from_slice(&value_bytes[1..32]);
 
+    // Third and fourth fields: the slot key, split in halves
+    let mut third_field_bytes = [0u8; 32];
+    third_field_bytes[0..16].copy_from_slice(&slot_key[0..16]);
+    let mut fourth_field_bytes = [0u8; 32];
+    fourth_field_bytes[0..16].copy_from_slice(&slot_key[16..32]);
+
     let first_field = Fp::from_bytes(&first_field_bytes)?;
     let second_field = Fp::from_bytes(&second_field_bytes)?;
+    let third_field = Fp::from_bytes(&third_field_bytes)?;
+    let fourth_field = Fp::from_bytes(&fourth_field_bytes)?;
+
+    let hash = poseidon_hash(&[first_field, second_field, third_field, fourth_field]);
 
-    let hash = poseidon_hash(&[first_field, second_field]);
+    Ok(hash)
+}
+
+
+/// Computes a Poseidon hash for a contract leaf node of the verified contract storage slots root,
+/// given a contract address, the Merkle root of its verified storage slots and whether the account
+/// was proven to be absent from the state trie.
+///
+/// Layout:
+/// - First field: 20-byte address + absent flag (21 bytes total, padded to 32)
+/// - Second field: the contract's verified storage slots root
+pub fn hash_contract_storage_root(
+    address: &Address,
+    storage_slots_root: &Fp,
+    account_absent: bool,
+) -> Result<Fp> {
+    let mut first_field_bytes = [0u8; 32];
+    first_field_bytes[0..20].copy_from_slice(address.as_slice());
+    if account_absent {
+        first_field_bytes[20] = ABSENT_LEAF_FLAG;
+    }
+
+    let first_field = Fp::from_bytes(&first_field_bytes)?;
+
+    let hash = poseidon_hash(&[first_field, *storage_slots_root]);
 
     Ok(hash)
 }
 
 
+/// Converts a Poseidon field element (e.g. a Merkle root) into its 32-byte committed representation.
+pub fn fp_to_fixed_bytes(value: &Fp) -> FixedBytes<32> {
+    let mut fixed_bytes = [0u8; 32];
+    fixed_bytes[..32].copy_from_slice(&value.to_bytes());
+
+    FixedBytes::new(fixed_bytes)
+}
+
+
+/// Cycle-tracker span names of the storage proof phases. Each block the proofs target (output, input or historical)
+/// has its own names, so an execute-only report does not merge the phases of different blocks.
+pub struct StorageProofSpans {
+    pub verify_account_proof: &'static str,
+    pub decode_storage_proof_nodes: &'static str,
+    pub verify_storage_slot: &'static str,
+    pub hash_storage_slot: &'static str,
+    pub merkle_fold: &'static str,
+}
+
+/// Spans of the storage proofs against the window's output block.
+pub const OUTPUT_BLOCK_SPANS: StorageProofSpans = StorageProofSpans {
+    verify_account_proof: "verify_account_proof",
+    decode_storage_proof_nodes: "decode_storage_proof_nodes",
+    verify_storage_slot: "verify_storage_slot",
+    hash_storage_slot: "hash_storage_slot",
+    merkle_fold: "merkle_fold",
+};
+
+/// Spans of the storage proofs against the window's input block, for storage deltas.
+pub const INPUT_BLOCK_SPANS: StorageProofSpans = StorageProofSpans {
+    verify_account_proof: "verify_input_account_proof",
+    decode_storage_proof_nodes: "decode_input_storage_proof_nodes",
+    verify_storage_slot: "verify_input_storage_slot",
+    hash_storage_slot: "hash_input_storage_slot",
+    merkle_fold: "input_merkle_fold",
+};
+
+/// Spans of the storage proofs against historical blocks.
+pub const HISTORICAL_BLOCK_SPANS: StorageProofSpans = StorageProofSpans {
+    verify_account_proof: "verify_historical_account_proof",
+    decode_storage_proof_nodes: "decode_historical_storage_proof_nodes",
+    verify_storage_slot: "verify_historical_storage_slot",
+    hash_storage_slot: "hash_historical_storage_slot",
+    merkle_fold: "historical_merkle_fold",
+};
+
+
 /// Verifies the Merkle Patricia Trie (MPT) proofs for a contract's storage slots against the execution state root,
 /// then computes and returns the Merkle root of the verified storage slots.
 ///
 /// This function performs two main verifications:
 /// 1. **Account Verification**: Validates that the contract's `TrieAccount` (RLP-encoded) is present in the global state trie
 ///    by verifying the provided MPT proof against the `execution_state_root`. The contract's address is hashed with `keccak256`
-///    and converted to nibbles to traverse the trie.
-/// 2. **Storage Slot Verification**: For each storage slot, verifies its existence in the contract's storage trie using the
+///    and converted to nibbles to traverse the trie. When `expected_value` is `None` the proof must instead prove that
+///    the account is absent, in which case its storage root is the empty trie root.
+/// 2. **Storage Slot Verification**: For each storage slot, derives the expected slot key from the contract's
+///    `layout` descriptor and the slot's `layout_keys`, then verifies its existence in the contract's storage trie using the
 ///    `storage_root` from the verified `TrieAccount`. The slot key is hashed with `keccak256` and converted to nibbles for the proof.
+///    A zero `expected_value` must be proven by a non-inclusion proof, as zero values are never stored in the trie.
+///    Slot proofs are a multiproof: the contract's `storage_proof_nodes` pool holds every node once (hashed and
+///    decoded once), and each slot gives its path from the storage root as indices into the pool.
 ///
 /// After successful verification of each storage slots, the function:
 /// - Hashes the verified storage slot details into a Merkle leaf, collecting them into a vector.
@@ -154,14 +321,20 @@ pub fn hash_storage_slot(
 ///
 /// # Parameters
 /// - `execution_state_root`: The root hash of the Ethereum global state trie.
-/// - `contract_storage`: Contains the contract's address, MPT proof for the account, storage slots, and expected values.
+/// - `contract_storage`: Contains the contract's address, MPT proof for the account, storage layout, storage slots,
+///   and expected values.
+/// - `spans`: Cycle-tracker span names of the block the proofs target.
 ///
 /// # Returns
-/// The Merkle root of the verified storage slot details as `FixedBytes<32>`.
+/// The Merkle root of the verified storage slot details as a Poseidon field element.
 ///
 /// # Errors
 /// - `MptError::InvalidAccountProof` if the account proof verification fails
-/// - `MptError::InvalidStorageSlotAddressMapping` if address-to-slot mapping is invalid
+/// - `MptError::InvalidStorageProofNodes` if a node of the storage proof node pool cannot be decoded
+/// - `MptError::InvalidStorageLayout` if the layout keys do not fit the layout or are not attributed to `slot_key_address`
+/// - `MptError::NonCanonicalSlotOrder` if the storage slots are not strictly increasing by slot key, which
+///   makes the committed root a function of the state alone (no reordering or duplicated leaves)
+/// - `MptError::InvalidStorageSlotAddressMapping` if the slot key derived from the layout does not match the slot key
 /// - `MptError::InvalidStorageSlotProof` if any storage slot proof is invalid
 /// - `MptError::MerkleHashError` if hashing a storage slot leaf fails
 /// - `MptError::ExceedsMaxTreeDepth` if the number of storage slots yields a merkle tree
@@ -170,37 +343,45 @@ pub fn hash_storage_slot(
 /// # Steps
 /// 1. Verify contract account exists in global state trie
 /// 2. For each storage slot:
-///    a. Verify address-to-slot-key mapping
-///    b. Verify slot exists in contract's storage trie
-///    c. Hash verified slot details into Merkle leaf
+///    a. Verify layout-to-slot-key derivation and that the slot key is greater than the previous slot's
+///    b. Verify slot exists in (or is absent from) contract's storage trie, walking its path through the shared node pool
+///    c. Hash verified slot details (attributed address, slot key and value) into Merkle leaf
 /// 3. Compute Merkle root from leaves via in-place folding
 /// 4. Return computed Merkle root
 pub fn verify_storage_slot_proofs(
     execution_state_root: FixedBytes<32>,
-    contract_storage: ContractStorage,
-) -> Result<FixedBytes<32>, MptError> {
+    contract_storage: &ContractStorage,
+    spans: &StorageProofSpans,
+) -> Result<Fp, MptError> {
     // Convert the contract address into nibbles for the global MPT proof
     // We need to keccak256 the address before converting to nibbles for the MPT proof
     let address_hash = keccak256(contract_storage.address.as_slice());
     let address_nibbles = Nibbles::unpack(Bytes::copy_from_slice(address_hash.as_ref()));
     // RLP-encode the `TrieAccount`. This is what's actually stored in the global MPT
-    let mut rlp_encoded_trie_account = Vec::new();
-    contract_storage
-        .expected_value
-        .encode(&mut rlp_encoded_trie_account);
+    // An absent account has no value in the global MPT and an empty storage trie
+    let (storage_root, rlp_encoded_trie_account) = match &contract_storage.expected_value {
+        Some(trie_account) => {
+            let mut rlp_encoded_trie_account = Vec::new();
+            trie_account.encode(&mut rlp_encoded_trie_account);
+            (trie_account.storage_root, Some(rlp_encoded_trie_account))
+        }
+        None => (EMPTY_ROOT_HASH, None),
+    };
 
     // 1) Verify the contract's account node in the global MPT:
-    //    We expect to find `rlp_encoded_trie_account` as the trie value for this address.
+    //    We expect to find `rlp_encoded_trie_account` as the trie value for this address (or nothing if absent).
+    let account_proof_span = CycleSpan::new(spans.verify_account_proof);
     proof::verify_proof(
         execution_state_root,
         address_nibbles,
-        Some(rlp_encoded_trie_account),
+        rlp_encoded_trie_account,
         &contract_storage.mpt_proof,
     )
     .map_err(|e| MptError::InvalidAccountProof {
         address: contract_storage.address,
         reason: e.to_string(),
     })?;
+    drop(account_proof_span);
 
     // Calculate tree depth which is ceil(log2(number)) and padded size (leaves to the nearest power of 2)
     let n_leaves = contract_storage.storage_slots.len();
@@ -216,22 +397,64 @@ pub fn verify_storage_slot_proofs(
     }
 
     // 2) Now that we've verified the contract's `TrieAccount`, use it to verify each storage slot proof
+    //    Storage proofs share their nodes (multiproof), each node is hashed and decoded once
+    let storage_proof_nodes_span = CycleSpan::new(spans.decode_storage_proof_nodes);
+    let storage_proof_nodes = MptNodePool::new(&contract_storage.storage_proof_nodes).map_err(|e| {
+        MptError::InvalidStorageProofNodes {
+            address: contract_storage.address,
+            reason: e.to_string(),
+        }
+    })?;
+    drop(storage_proof_nodes_span);
     let mut merkle_nodes = Vec::with_capacity(padded_size);
+    let mut previous_slot_key: Option<B256> = None;
 
-    for slot in contract_storage.storage_slots {
+    for slot in contract_storage.storage_slots.iter() {
+        let slot_span = CycleSpan::new(spans.verify_storage_slot);
         let key = slot.key;
         let value = slot.expected_value;
         // We need to keccak256 the slot key before converting to nibbles for the MPT proof
         let key_hash = keccak256(key.as_slice());
         let key_nibbles = Nibbles::unpack(Bytes::copy_from_slice(key_hash.as_ref()));
         // RLP-encode expected value. This is what's actually stored in the contract MPT
-        let mut rlp_encoded_value = Vec::new();
-        value.encode(&mut rlp_encoded_value);
+        // Zero values are not stored in the contract MPT and are proven by exclusion
+        let rlp_encoded_value = if value.is_zero() {
+            None
+        } else {
+            let mut rlp_encoded_value = Vec::new();
+            value.encode(&mut rlp_encoded_value);
+            Some(rlp_encoded_value)
+        };
 
-        // Verify slot address mapping
+        // Verify slot key derivation from the storage layout
         let address = slot.slot_key_address;
-        let computed_address_attestation_slot_key =
-            get_storage_location_for_key(address, SOURCE_CONTRACT_LOCKED_TOKENS_STORAGE_INDEX);
+        let computed_address_attestation_slot_key = contract_storage
+            .layout
+            .storage_location(&slot.layout_keys)
+            .map_err(|e| MptError::InvalidStorageLayout {
+                slot_key: key,
+                reason: e.to_string(),
+            })?;
+        let leaf_address = StorageLayout::leaf_address(&slot.layout_keys);
+        if leaf_address != address {
+            return Err(MptError::InvalidStorageLayout {
+                slot_key: key,
+                reason: format!(
+                    "layout keys attribute the slot to {:?} but slot_key_address is {:?}",
+                    leaf_address, address
+                ),
+            });
+        }
+        if let Some(previous_slot_key) = previous_slot_key {
+            if key <= previous_slot_key {
+                return Err(MptError::NonCanonicalSlotOrder {
+                    address: contract_storage.address,
+                    previous_slot_key,
+                    slot_key: key,
+                });
+            }
+        }
+        previous_slot_key = Some(key);
         if computed_address_attestation_slot_key != key {
             return Err(MptError::InvalidStorageSlotAddressMapping {
                 slot_key: key,
@@ -241,18 +464,16 @@ pub fn verify_storage_slot_proofs(
         }
 
         // Verify the storage proof under the *contract's* storage root
-        proof::verify_proof(
-            contract_storage.expected_value.storage_root,
-            key_nibbles,
-            Some(rlp_encoded_value),
-            &slot.mpt_proof,
-        )
-        .map_err(|e| MptError::InvalidStorageSlotProof {
+        storage_proof_nodes
+            .verify(storage_root, &key_nibbles, rlp_encoded_value, &slot.mpt_proof_path)
+            .map_err(|e| MptError::InvalidStorageSlotProof {
             slot_key: key,
             reason: e.to_string(),
         })?;
+        drop(slot_span);
 
-        let slot_merkle_leaf_result = hash_storage_slot(&address, &value);
+        let _span = CycleSpan::new(spans.hash_storage_slot);
+        let slot_merkle_leaf_result = hash_storage_slot(&address, &key, &value);
         let slot_merkle_leaf = match slot_merkle_leaf_result {
             Ok(val) => val,
             Err(error) => {
@@ -267,10 +488,340 @@ pub fn verify_storage_slot_proofs(
     }
 
     // Calculate the root hash
-    let root = fold_merkle_left(&mut merkle_nodes, padded_size, depth, &get_merkle_zeros());
+    let root = {
+        let _span = CycleSpan::new(spans.merkle_fold);
+        fold_merkle_left(&mut merkle_nodes, padded_size, depth, &get_merkle_zeros())
+    };
 
-    let mut fixed_bytes = [0u8; 32];
-    fixed_bytes[..32].copy_from_slice(&root.to_bytes());
+    Ok(root)
+}
+
+
+/// Verifies the storage slot proofs of every contract in `contract_storages` against the execution state root,
+/// then computes the root-of-roots of the verified contract storage slots and a commitment to the storage layouts used.
+///
+/// The contracts must be strictly increasing by address, checked before any proof is verified, so that the
+/// root-of-roots is a function of the state alone (no reordered or duplicated contracts). Each contract is then
+/// verified with `verify_storage_slot_proofs`, producing that contract's storage slots root. The contract's address
+/// and storage slots root are hashed into a contract leaf (`hash_contract_storage_root`) and the contract leaves are
+/// folded, in address order, into the root-of-roots.
+///
+/// # Parameters
+/// - `execution_state_root`: The root hash of the Ethereum global state trie.
+/// - `contract_storages`: The contracts whose accounts and storage slots should be verified.
+/// - `spans`: Cycle-tracker span names of the block the proofs target.
+///
+/// # Returns
+/// A tuple of:
+/// - The Merkle root-of-roots of the verified contract storage slots as `FixedBytes<32>`.
+/// - `keccak256(address_0 ‖ layout_hash_0 ‖ … ‖ address_n ‖ layout_hash_n)` committing to each contract's storage layout.
+///
+/// # Errors
+/// - `MptError::NonCanonicalContractOrder` if the contracts are not strictly increasing by address
+/// - Any error of `verify_storage_slot_proofs`
+/// - `MptError::MerkleHashError` if hashing a contract leaf fails
+/// - `MptError::ExceedsMaxTreeDepth` if the number of contracts yields a merkle tree which is too large.
+pub fn verify_contracts_storage_slot_proofs(
+    execution_state_root: FixedBytes<32>,
+    contract_storages: &[ContractStorage],
+    spans: &StorageProofSpans,
+) -> Result<(FixedBytes<32>, B256), MptError> {
+    // Calculate tree depth and padded size of the root-of-roots
+    let n_leaves = contract_storages.len();
+    let (depth, padded_size) = compute_merkle_tree_depth_and_size(n_leaves);
+
+    // Validate
+    if depth > MAX_TREE_DEPTH {
+        return Err(MptError::ExceedsMaxTreeDepth {
+            slots: n_leaves,
+            requested_depth: depth,
+            max_depth: MAX_TREE_DEPTH,
+        });
+    }
+
+    for pair in contract_storages.windows(2) {
+        if pair[1].address <= pair[0].address {
+            return Err(MptError::NonCanonicalContractOrder {
+                previous_address: pair[0].address,
+                address: pair[1].address,
+            });
+        }
+    }
+
+    let mut merkle_nodes = Vec::with_capacity(padded_size);
+    let mut encoded_layouts = Vec::with_capacity(n_leaves * (20 + 32));
+
+    for contract_storage in contract_storages.iter() {
+        let address = contract_storage.address;
+        let account_absent = contract_storage.expected_value.is_none();
+        encoded_layouts.extend_from_slice(address.as_slice());
+        encoded_layouts.extend_from_slice(contract_storage.layout.hash().as_slice());
+
+        let storage_slots_root = verify_storage_slot_proofs(execution_state_root, contract_storage, spans)?;
+
+        let contract_merkle_leaf =
+            hash_contract_storage_root(&address, &storage_slots_root, account_absent)
+            .map_err(|error| MptError::MerkleHashError {
+                address,
+                value: U256::ZERO,
+                reason: error.to_string(),
+            })?;
+        merkle_nodes.push(contract_merkle_leaf);
+    }
+
+    // Calculate the root-of-roots hash
+    let root = {
+        let _span = CycleSpan::new(spans.merkle_fold);
+        fold_merkle_left(&mut merkle_nodes, padded_size, depth, &get_merkle_zeros())
+    };
+
+    Ok((fp_to_fixed_bytes(&root), keccak256(encoded_layouts)))
+}
+
+
+/// Computes the keccak256 leaf commitment of a storage slot delta between the input and output blocks of a window.
+///
+/// Layout (`abi.encodePacked`):
+/// - `address contract_address`
+/// - `address slot_key_address`
+/// - `bytes32 slot_key`
+/// - `uint8 decreased` (1 if the output value is lower than the input value, 0 otherwise)
+/// - `uint256 delta` = `|output_value - input_value|`
+pub fn hash_storage_slot_delta(
+    contract_address: &Address,
+    slot_key_address: &Address,
+    slot_key: &B256,
+    input_value: &U256,
+    output_value: &U256,
+) -> B256 {
+    let decreased = output_value < input_value;
+    let delta = if decreased {
+        input_value - output_value
+    } else {
+        output_value - input_value
+    };
+
+    let mut encoded = Vec::with_capacity(20 + 20 + 32 + 1 + 32);
+    encoded.extend_from_slice(contract_address.as_slice());
+    encoded.extend_from_slice(slot_key_address.as_slice());
+    encoded.extend_from_slice(slot_key.as_slice());
+    encoded.push(decreased as u8);
+    encoded.extend_from_slice(&delta.to_be_bytes::<32>());
+
+    keccak256(encoded)
+}
+
+
+/// Verifies the storage slot proofs of every contract in `input_contract_storages` against the input block's execution
+/// state root and commits the per slot deltas relative to the already verified `output_contract_storages`.
+///
+/// The input storages must prove exactly the same contracts, layouts and slots (in the same order) as the output
+/// storages. Each input contract is verified with `verify_storage_slot_proofs`, so slots or accounts which did not
+/// exist at the input block are proven by exclusion and contribute their full output value as delta.
+///
+/// # Parameters
+/// - `input_execution_state_root`: The root hash of the global state trie at the input block of the window.
+/// - `input_contract_storages`: The contracts' accounts and storage slots proven at the input block.
+/// - `output_contract_storages`: The contracts' accounts and storage slots proven at the output block.
+///
+/// # Returns
+/// `keccak256(leaf_0 ‖ leaf_1 ‖ … ‖ leaf_n)` where each leaf is `hash_storage_slot_delta`, in input order.
+///
+/// # Errors
+/// - Any error of `verify_storage_slot_proofs`
+/// - `MptError::StorageDeltaMismatch` if the input storages do not prove the same slots as the output storages
+pub fn verify_contracts_storage_deltas(
+    input_execution_state_root: FixedBytes<32>,
+    input_contract_storages: &[ContractStorage],
+    output_contract_storages: &[ContractStorage],
+) -> Result<B256, MptError> {
+    if input_contract_storages.len() != output_contract_storages.len() {
+        return Err(MptError::StorageDeltaMismatch {
+            address: Address::ZERO,
+            reason: format!(
+                "{} input contracts for {} output contracts",
+                input_contract_storages.len(),
+                output_contract_storages.len()
+            ),
+        });
+    }
+
+    let mut delta_leaves: Vec<u8> = Vec::new();
+
+    for (input_contract_storage, output_contract_storage) in
+        input_contract_storages.iter().zip(output_contract_storages.iter())
+    {
+        let address = output_contract_storage.address;
+        if input_contract_storage.address != address
+            || input_contract_storage.layout != output_contract_storage.layout
+            || input_contract_storage.storage_slots.len() != output_contract_storage.storage_slots.len()
+        {
+            return Err(MptError::StorageDeltaMismatch {
+                address,
+                reason: "contract address, layout or number of slots differ".to_string(),
+            });
+        }
+
+        verify_storage_slot_proofs(
+            input_execution_state_root,
+            input_contract_storage,
+            &INPUT_BLOCK_SPANS,
+        )?;
+
+        for (input_slot, output_slot) in input_contract_storage
+            .storage_slots
+            .iter()
+            .zip(output_contract_storage.storage_slots.iter())
+        {
+            if input_slot.key != output_slot.key {
+                return Err(MptError::StorageDeltaMismatch {
+                    address,
+                    reason: format!(
+                        "input slot {:?} does not match output slot {:?}",
+                        input_slot.key, output_slot.key
+                    ),
+                });
+            }
+
+            delta_leaves.extend_from_slice(
+                hash_storage_slot_delta(
+                    &address,
+                    &output_slot.slot_key_address,
+                    &output_slot.key,
+                    &input_slot.expected_value,
+                    &output_slot.expected_value,
+                )
+                .as_slice(),
+            );
+        }
+    }
+
+    if delta_leaves.is_empty() {
+        return Ok(B256::ZERO);
+    }
+
+    Ok(keccak256(delta_leaves))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::address;
+    use golem_symbiotic_consensus_mpt_types::{storage_layout::StorageKey, types::StorageSlot};
 
-    Ok(FixedBytes::new(fixed_bytes))
+    const CONTRACT_A: Address = address!("00000000000000000000000000000000000000a1");
+    const CONTRACT_B: Address = address!("00000000000000000000000000000000000000b2");
+
+    // The zero valued `lockedTokens` slot of `owner`, proven absent from an empty storage trie
+    fn absent_slot(owner: u8) -> StorageSlot {
+        let owner = Address::with_last_byte(owner);
+        let layout_keys = vec![StorageKey::Address(owner)];
+        StorageSlot {
+            key: StorageLayout::locked_tokens()
+                .storage_location(&layout_keys)
+                .unwrap(),
+            slot_key_address: owner,
+            layout_keys,
+            expected_value: U256::ZERO,
+            mpt_proof_path: vec![],
+        }
+    }
+
+    // A contract proven absent from an empty state trie, so neither it nor its slots need proof nodes
+    fn absent_contract(address: Address, storage_slots: Vec<StorageSlot>) -> ContractStorage {
+        ContractStorage {
+            address,
+            expected_value: None,
+            mpt_proof: vec![],
+            layout: StorageLayout::locked_tokens(),
+            storage_proof_nodes: vec![],
+            storage_slots,
+        }
+    }
+
+    // The slots of `owners`, in slot key order
+    fn ordered_slots(owners: &[u8]) -> Vec<StorageSlot> {
+        let mut slots: Vec<StorageSlot> = owners.iter().map(|owner| absent_slot(*owner)).collect();
+        slots.sort_by_key(|slot| slot.key);
+        slots
+    }
+
+    fn verify(contract_storages: &[ContractStorage]) -> Result<(FixedBytes<32>, B256), MptError> {
+        verify_contracts_storage_slot_proofs(EMPTY_ROOT_HASH, contract_storages, &OUTPUT_BLOCK_SPANS)
+    }
+
+    #[test]
+    fn verifies_contracts_and_slots_in_canonical_order() {
+        let contract_storages = [
+            absent_contract(CONTRACT_A, ordered_slots(&[1, 2, 3])),
+            absent_contract(CONTRACT_B, ordered_slots(&[1, 2])),
+        ];
+        assert!(verify(&contract_storages).is_ok());
+    }
+
+    #[test]
+    fn rejects_interleaved_contracts() {
+        let contract_storages = [
+            absent_contract(CONTRACT_A, ordered_slots(&[1])),
+            absent_contract(CONTRACT_B, ordered_slots(&[2])),
+            absent_contract(CONTRACT_A, ordered_slots(&[3])),
+        ];
+        assert!(matches!(
+            verify(&contract_storages),
+            Err(MptError::NonCanonicalContractOrder { previous_address, address })
+                if previous_address == CONTRACT_B && address == CONTRACT_A
+        ));
+    }
+
+    #[test]
+    fn rejects_duplicate_contracts() {
+        let contract_storages = [
+            absent_contract(CONTRACT_A, ordered_slots(&[1])),
+            absent_contract(CONTRACT_A, ordered_slots(&[1])),
+        ];
+        assert!(matches!(
+            verify(&contract_storages),
+            Err(MptError::NonCanonicalContractOrder { previous_address, address })
+                if previous_address == CONTRACT_A && address == CONTRACT_A
+        ));
+    }
+
+    #[test]
+    fn rejects_reordered_slots_of_the_second_contract() {
+        let mut reordered_slots = ordered_slots(&[1, 2, 3]);
+        reordered_slots.swap(1, 2);
+        let contract_storages = [
+            absent_contract(CONTRACT_A, ordered_slots(&[1, 2, 3])),
+            absent_contract(CONTRACT_B, reordered_slots),
+        ];
+        assert!(matches!(
+            verify(&contract_storages),
+            Err(MptError::NonCanonicalSlotOrder { address, .. }) if address == CONTRACT_B
+        ));
+    }
+
+    #[test]
+    fn rejects_duplicate_slots() {
+        let contract_storages = [
+            absent_contract(CONTRACT_A, vec![absent_slot(1), absent_slot(1)]),
+            absent_contract(CONTRACT_B, ordered_slots(&[1])),
+        ];
+        assert!(matches!(
+            verify(&contract_storages),
+            Err(MptError::NonCanonicalSlotOrder { address, previous_slot_key, slot_key })
+                if address == CONTRACT_A && previous_slot_key == slot_key
+        ));
+    }
+
+    #[test]
+    fn the_same_slots_of_another_contract_are_not_duplicates() {
+        // Contract B proves the same slot keys as contract A, only keys within a contract must increase
+        let contract_storages = [
+            absent_contract(CONTRACT_A, ordered_slots(&[1, 2])),
+            absent_contract(CONTRACT_B, ordered_slots(&[1, 2])),
+        ];
+        let (root, _) = verify(&contract_storages).unwrap();
+        let (single_contract_root, _) = verify(&contract_storages[..1]).unwrap();
+        assert_ne!(root, single_contract_root);
+    }
 }
@@ -0,0 +1,454 @@
+use alloy_primitives::{keccak256, Bytes, B256};
+use alloy_rlp::Decodable;
+use alloy_trie::{
+    nodes::{BranchNode, RlpNode, TrieNode},
+    Nibbles, EMPTY_ROOT_HASH,
+};
+use std::borrow::Cow;
+use std::fmt;
+
+/// Custom multiproof errors
+
+#[derive(Debug)]
+pub enum MultiproofError {
+    InvalidNode {
+        index: usize,
+        reason: String,
+    },
+    InvalidEmbeddedNode {
+        walked: usize,
+        reason: String,
+    },
+    MissingNode {
+        index: usize,
+        n_nodes: usize,
+    },
+    NodeMismatch {
+        index: usize,
+        expected: B256,
+        actual: B256,
+    },
+    IncompletePath {
+        walked: usize,
+    },
+    UnusedPathNodes {
+        unused: usize,
+    },
+    ValueMismatch,
+}
+
+impl fmt::Display for MultiproofError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MultiproofError::InvalidNode { index, reason } => write!(
+                f,
+                "Multiproof node {} could not be decoded: {:?}",
+                index,
+                reason
+            ),
+            MultiproofError::InvalidEmbeddedNode { walked, reason } => write!(
+                f,
+                "Multiproof node embedded after {} nibbles could not be decoded: {:?}",
+                walked,
+                reason
+            ),
+            MultiproofError::MissingNode { index, n_nodes } => write!(
+                f,
+                "Multiproof path references node {} but the pool has {} nodes",
+                index,
+                n_nodes
+            ),
+            MultiproofError::NodeMismatch {
+                index,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "Multiproof node {} hashes to {:?} but its parent references {:?}",
+                index,
+                actual,
+                expected
+            ),
+            MultiproofError::IncompletePath { walked } => write!(
+                f,
+                "Multiproof path ends after {} nibbles, before the key is resolved",
+                walked
+            ),
+            MultiproofError::UnusedPathNodes { unused } => write!(
+                f,
+                "Multiproof path has {} nodes after the key was resolved",
+                unused
+            ),
+            MultiproofError::ValueMismatch => {
+                write!(f, "Multiproof value does not match the expected value")
+            }
+        }
+    }
+}
+
+/// A deduplicated pool of trie nodes shared by several MPT proofs of the same trie.
+///
+/// Every node is keccak-hashed and RLP-decoded exactly once when the pool is built, proofs are then given as paths of
+/// indices into the pool and walked without re-hashing or re-decoding the nodes they share (typically the top of the
+/// trie).
+pub struct MptNodePool {
+    nodes: Vec<TrieNode>,
+    hashes: Vec<B256>,
+}
+
+/// Child of `branch` at `nibble`, if any. Children are stored compactly, in nibble order, for the bits set in the mask.
+fn branch_child(branch: &BranchNode, nibble: u8) -> Option<&RlpNode> {
+    if !branch.state_mask.is_bit_set(nibble) {
+        return None;
+    }
+    let index = (branch.state_mask.get() & ((1u16 << nibble) - 1)).count_ones() as usize;
+    branch.stack.get(index)
+}
+
+impl MptNodePool {
+    /// Hashes and decodes each RLP encoded node of the pool.
+    pub fn new(nodes: &[Bytes]) -> Result<Self, MultiproofError> {
+        let mut decoded_nodes = Vec::with_capacity(nodes.len());
+        let mut hashes = Vec::with_capacity(nodes.len());
+        for (index, node) in nodes.iter().enumerate() {
+            hashes.push(keccak256(node));
+            decoded_nodes.push(TrieNode::decode(&mut &node[..]).map_err(|e| {
+                MultiproofError::InvalidNode {
+                    index,
+                    reason: e.to_string(),
+                }
+            })?);
+        }
+        Ok(MptNodePool {
+            nodes: decoded_nodes,
+            hashes,
+        })
+    }
+
+    /// Walks `key` from `root` through the pool nodes at `path`, returning the value stored at `key`, or `None` when
+    /// the walk proves the key is absent from the trie.
+    ///
+    /// Nodes referenced by hash must be the next node of `path`, nodes shorter than 32 bytes are embedded in their
+    /// parent and decoded in place.
+    pub fn get(
+        &self,
+        root: B256,
+        key: &Nibbles,
+        path: &[u32],
+    ) -> Result<Option<Vec<u8>>, MultiproofError> {
+        // An empty trie has no nodes
+        if root == EMPTY_ROOT_HASH && path.is_empty() {
+            return Ok(None);
+        }
+
+        let mut path_nodes = path.iter();
+        let mut walked = 0usize;
+        let mut next = RlpNode::word_rlp(&root);
+
+        let value = loop {
+            // Resolve the next node, from the pool when referenced by hash or in place when embedded
+            let node: Cow<'_, TrieNode> = match next.as_hash() {
+                Some(expected) => {
+                    let index = *path_nodes
+                        .next()
+                        .ok_or(MultiproofError::IncompletePath { walked })?
+                        as usize;
+                    let actual = *self.hashes.get(index).ok_or(MultiproofError::MissingNode {
+                        index,
+                        n_nodes: self.nodes.len(),
+                    })?;
+                    if actual != expected {
+                        return Err(MultiproofError::NodeMismatch {
+                            index,
+                            expected,
+                            actual,
+                        });
+                    }
+                    Cow::Borrowed(&self.nodes[index])
+                }
+                None => Cow::Owned(TrieNode::decode(&mut &next[..]).map_err(|e| {
+                    MultiproofError::InvalidEmbeddedNode {
+                        walked,
+                        reason: e.to_string(),
+                    }
+                })?),
+            };
+
+            match node.as_ref() {
+                TrieNode::Branch(branch) => {
+                    let Some(nibble) = key.get(walked).copied() else {
+                        return Err(MultiproofError::IncompletePath { walked });
+                    };
+                    walked += 1;
+                    match branch_child(branch, nibble) {
+                        Some(child) => next = child.clone(),
+                        None => break None,
+                    }
+                }
+                TrieNode::Extension(extension) => {
+                    if key[walked..].starts_with(&extension.key[..]) {
+                        walked += extension.key.len();
+                        next = extension.child.clone();
+                    } else {
+                        break None;
+                    }
+                }
+                TrieNode::Leaf(leaf) => {
+                    if key[walked..] == leaf.key[..] {
+                        break Some(leaf.value.clone());
+                    } else {
+                        break None;
+                    }
+                }
+                TrieNode::EmptyRoot => break None,
+            }
+        };
+
+        let unused = path_nodes.count();
+        if unused > 0 {
+            return Err(MultiproofError::UnusedPathNodes { unused });
+        }
+
+        Ok(value)
+    }
+
+    /// Verifies that `key` holds `expected_value` (or is absent when `None`) in the trie with root `root`.
+    pub fn verify(
+        &self,
+        root: B256,
+        key: &Nibbles,
+        expected_value: Option<Vec<u8>>,
+        path: &[u32],
+    ) -> Result<(), MultiproofError> {
+        if self.get(root, key, path)? != expected_value {
+            return Err(MultiproofError::ValueMismatch);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_trie::{
+        proof::{self, ProofRetainer},
+        HashBuilder,
+    };
+    use std::collections::BTreeMap;
+
+    // A trie of `leaves` with the proofs of `targets` pooled as the host pools them: each node once, each target
+    // given as the indices of its proof nodes
+    fn multiproof(
+        leaves: &BTreeMap<Nibbles, Vec<u8>>,
+        targets: &[Nibbles],
+    ) -> (B256, Vec<Bytes>, Vec<Vec<u32>>) {
+        let mut hash_builder =
+            HashBuilder::default().with_proof_retainer(ProofRetainer::new(targets.to_vec()));
+        for (key, value) in leaves.iter() {
+            hash_builder.add_leaf(key.clone(), value);
+        }
+        let root = hash_builder.root();
+        let proof_nodes = hash_builder.take_proof_nodes();
+
+        let mut nodes: Vec<Bytes> = Vec::new();
+        let paths = targets
+            .iter()
+            .map(|target| {
+                proof_nodes
+                    .matching_nodes_sorted(target)
+                    .into_iter()
+                    .map(|(_, node)| match nodes.iter().position(|pooled| *pooled == node) {
+                        Some(index) => index as u32,
+                        None => {
+                            nodes.push(node);
+                            (nodes.len() - 1) as u32
+                        }
+                    })
+                    .collect()
+            })
+            .collect();
+
+        (root, nodes, paths)
+    }
+
+    fn hashed_key(index: u64) -> Nibbles {
+        Nibbles::unpack(keccak256(index.to_be_bytes()))
+    }
+
+    // Keys sharing their first 31 bytes, so the trie is an extension to a single branch
+    fn extension_key(last_byte: u8) -> Nibbles {
+        let mut key = [0x11u8; 32];
+        key[31] = last_byte;
+        Nibbles::unpack(key)
+    }
+
+    fn value(index: u64) -> Vec<u8> {
+        keccak256(index.to_le_bytes()).to_vec()
+    }
+
+    #[test]
+    fn round_trips_hash_builder_proofs() {
+        let leaves: BTreeMap<Nibbles, Vec<u8>> =
+            (0..64).map(|index| (hashed_key(index), value(index))).collect();
+        let targets: Vec<Nibbles> = [3, 17, 18, 42, 1000, 1001].into_iter().map(hashed_key).collect();
+        let (root, nodes, paths) = multiproof(&leaves, &targets);
+
+        // The proofs share the top of the trie
+        let n_path_nodes: usize = paths.iter().map(Vec::len).sum();
+        assert!(nodes.len() < n_path_nodes);
+
+        let pool = MptNodePool::new(&nodes).unwrap();
+        for (target, path) in targets.iter().zip(paths.iter()) {
+            let expected = leaves.get(target).cloned();
+            assert_eq!(pool.get(root, target, path).unwrap(), expected);
+            pool.verify(root, target, expected.clone(), path).unwrap();
+
+            // The same nodes verify as a regular proof
+            let proof: Vec<Bytes> = path.iter().map(|index| nodes[*index as usize].clone()).collect();
+            proof::verify_proof(root, target.clone(), expected, &proof).unwrap();
+        }
+    }
+
+    #[test]
+    fn empty_trie_has_no_values() {
+        let pool = MptNodePool::new(&[]).unwrap();
+        assert_eq!(pool.get(EMPTY_ROOT_HASH, &hashed_key(0), &[]).unwrap(), None);
+    }
+
+    #[test]
+    fn rejects_a_path_to_a_missing_node() {
+        let leaves: BTreeMap<Nibbles, Vec<u8>> =
+            (0..16).map(|index| (hashed_key(index), value(index))).collect();
+        let (root, nodes, paths) = multiproof(&leaves, &[hashed_key(5)]);
+        let pool = MptNodePool::new(&nodes).unwrap();
+
+        let mut path = paths[0].clone();
+        *path.last_mut().unwrap() = nodes.len() as u32;
+        assert!(matches!(
+            pool.get(root, &hashed_key(5), &path),
+            Err(MultiproofError::MissingNode { .. })
+        ));
+
+        // A path ending before the key is resolved
+        path.pop();
+        assert!(matches!(
+            pool.get(root, &hashed_key(5), &path),
+            Err(MultiproofError::IncompletePath { .. })
+        ));
+    }
+
+    #[test]
+    fn rejects_a_node_not_matching_its_parent_hash() {
+        let leaves: BTreeMap<Nibbles, Vec<u8>> =
+            (0..16).map(|index| (hashed_key(index), value(index))).collect();
+        let targets = [hashed_key(5), hashed_key(9)];
+        let (root, nodes, paths) = multiproof(&leaves, &targets);
+        let pool = MptNodePool::new(&nodes).unwrap();
+
+        // The leaf of another slot in place of the slot's leaf
+        let mut path = paths[0].clone();
+        *path.last_mut().unwrap() = *paths[1].last().unwrap();
+        assert!(matches!(
+            pool.get(root, &targets[0], &path),
+            Err(MultiproofError::NodeMismatch { .. })
+        ));
+
+        // A wrong root
+        assert!(matches!(
+            pool.get(keccak256(root), &targets[0], &paths[0]),
+            Err(MultiproofError::NodeMismatch { index, .. }) if index == paths[0][0] as usize
+        ));
+    }
+
+    #[test]
+    fn rejects_nodes_after_the_key_is_resolved() {
+        let leaves: BTreeMap<Nibbles, Vec<u8>> =
+            (0..16).map(|index| (hashed_key(index), value(index))).collect();
+        let (root, nodes, paths) = multiproof(&leaves, &[hashed_key(5)]);
+        let pool = MptNodePool::new(&nodes).unwrap();
+
+        let mut path = paths[0].clone();
+        path.push(path[0]);
+        assert!(matches!(
+            pool.get(root, &hashed_key(5), &path),
+            Err(MultiproofError::UnusedPathNodes { unused: 1 })
+        ));
+    }
+
+    #[test]
+    fn proves_absence_through_a_branch() {
+        let leaves: BTreeMap<Nibbles, Vec<u8>> =
+            (0..16).map(|index| (hashed_key(index), value(index))).collect();
+        let absent = hashed_key(100);
+        let (root, nodes, paths) = multiproof(&leaves, std::slice::from_ref(&absent));
+        let pool = MptNodePool::new(&nodes).unwrap();
+
+        assert!(matches!(
+            pool.nodes[*paths[0].last().unwrap() as usize],
+            TrieNode::Branch(_)
+        ));
+        assert_eq!(pool.get(root, &absent, &paths[0]).unwrap(), None);
+        pool.verify(root, &absent, None, &paths[0]).unwrap();
+        assert!(matches!(
+            pool.verify(root, &absent, Some(value(100)), &paths[0]),
+            Err(MultiproofError::ValueMismatch)
+        ));
+    }
+
+    #[test]
+    fn proves_absence_through_an_extension() {
+        let leaves: BTreeMap<Nibbles, Vec<u8>> = [0x01u8, 0x02, 0x13]
+            .into_iter()
+            .map(|last_byte| (extension_key(last_byte), value(last_byte as u64)))
+            .collect();
+        let mut absent = [0x11u8; 32];
+        absent[30] = 0x22;
+        let absent = Nibbles::unpack(absent);
+        let (root, nodes, paths) = multiproof(&leaves, &[absent.clone(), extension_key(0x02)]);
+        let pool = MptNodePool::new(&nodes).unwrap();
+
+        // The key leaves the extension, which is the only node of its proof
+        assert_eq!(paths[0].len(), 1);
+        assert!(matches!(pool.nodes[paths[0][0] as usize], TrieNode::Extension(_)));
+        assert_eq!(pool.get(root, &absent, &paths[0]).unwrap(), None);
+
+        // Present keys walk through the extension
+        assert_eq!(
+            pool.get(root, &extension_key(0x02), &paths[1]).unwrap(),
+            Some(value(0x02))
+        );
+    }
+
+    #[test]
+    fn leaf_path_mismatch_proves_absence() {
+        let leaves: BTreeMap<Nibbles, Vec<u8>> = [0x01u8, 0x02, 0x13]
+            .into_iter()
+            .map(|last_byte| (extension_key(last_byte), value(last_byte as u64)))
+            .collect();
+        // Shares the branch nibble of 0x13's leaf, but not the rest of its path
+        let absent = extension_key(0x14);
+        let (root, nodes, paths) = multiproof(&leaves, &[absent.clone(), extension_key(0x13)]);
+        let pool = MptNodePool::new(&nodes).unwrap();
+
+        assert_eq!(paths[0], paths[1]);
+        assert!(matches!(
+            pool.nodes[*paths[0].last().unwrap() as usize],
+            TrieNode::Leaf(_)
+        ));
+        assert_eq!(pool.get(root, &absent, &paths[0]).unwrap(), None);
+
+        // The leaf of 0x13 does not prove 0x13's value for 0x14
+        assert!(matches!(
+            pool.verify(root, &absent, Some(value(0x13)), &paths[0]),
+            Err(MultiproofError::ValueMismatch)
+        ));
+    }
+
+    #[test]
+    fn rejects_undecodable_nodes() {
+        assert!(matches!(
+            MptNodePool::new(&[Bytes::from_static(&[0xc2, 0x80])]),
+            Err(MultiproofError::InvalidNode { index: 0, .. })
+        ));
+    }
+}
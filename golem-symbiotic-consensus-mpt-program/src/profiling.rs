@@ -0,0 +1,25 @@
+/// SP1 cycle-tracker span, open from `CycleSpan::new` until dropped.
+///
+/// Inside the zkVM the span's cycles are accumulated per name (over all invocations) into the `cycle_tracker` of the
+/// execution report, which the host reads back from an execute-only run. Outside the zkVM spans are no-ops.
+///
+/// Spans may be nested, the cycles of a nested span are also counted by its parents.
+pub struct CycleSpan {
+    #[cfg_attr(not(target_os = "zkvm"), allow(dead_code))]
+    name: &'static str,
+}
+
+impl CycleSpan {
+    pub fn new(name: &'static str) -> Self {
+        #[cfg(target_os = "zkvm")]
+        println!("cycle-tracker-report-start: {}", name);
+        CycleSpan { name }
+    }
+}
+
+impl Drop for CycleSpan {
+    fn drop(&mut self) {
+        #[cfg(target_os = "zkvm")]
+        println!("cycle-tracker-report-end: {}", self.name);
+    }
+}
@@ -0,0 +1,416 @@
+use alloy_primitives::{keccak256, Address, Bytes, B256};
+use alloy_rlp::{Decodable, Header, RlpDecodable};
+use alloy_trie::{proof, Nibbles};
+use golem_symbiotic_consensus_mpt_types::types::{ReceiptLog, ReceiptProof};
+use std::fmt;
+
+/// Custom receipt errors
+
+#[derive(Debug)]
+pub enum ReceiptError {
+    InvalidReceiptProof {
+        tx_index: u64,
+        reason: String,
+    },
+    InvalidReceiptEncoding {
+        tx_index: u64,
+        reason: String,
+    },
+    MissingLog {
+        tx_index: u64,
+        log_index: u32,
+        n_logs: usize,
+    },
+    LogMismatch {
+        tx_index: u64,
+        log_index: u32,
+    },
+}
+
+impl fmt::Display for ReceiptError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReceiptError::InvalidReceiptProof { tx_index, reason } => write!(
+                f,
+                "MPT receipt proof failed for transaction index {}: {:?}",
+                tx_index,
+                reason
+            ),
+            ReceiptError::InvalidReceiptEncoding { tx_index, reason } => write!(
+                f,
+                "Receipt for transaction index {} could not be decoded: {:?}",
+                tx_index,
+                reason
+            ),
+            ReceiptError::MissingLog {
+                tx_index,
+                log_index,
+                n_logs,
+            } => write!(
+                f,
+                "Receipt for transaction index {} has {} logs, log index {} does not exist",
+                tx_index,
+                n_logs,
+                log_index
+            ),
+            ReceiptError::LogMismatch { tx_index, log_index } => write!(
+                f,
+                "Log {} of the receipt for transaction index {} does not match the expected emitter, topics or data",
+                log_index,
+                tx_index
+            ),
+        }
+    }
+}
+
+/// A log as it is RLP encoded within a receipt: `[address, [topics...], data]`.
+#[derive(Debug, RlpDecodable)]
+struct RlpReceiptLog {
+    address: Address,
+    topics: Vec<B256>,
+    data: Bytes,
+}
+
+/// Decodes the logs from an EIP-2718 encoded receipt.
+///
+/// Legacy receipts are a bare RLP list, typed receipts are prefixed with a single transaction type byte.
+/// In both cases the RLP payload is `[status, cumulative_gas_used, logs_bloom, logs]`.
+fn decode_receipt_logs(receipt: &[u8]) -> Result<Vec<RlpReceiptLog>, alloy_rlp::Error> {
+    let mut buf = match receipt.first() {
+        Some(first) if *first < 0x7f => &receipt[1..],
+        Some(_) => receipt,
+        None => return Err(alloy_rlp::Error::InputTooShort),
+    };
+
+    let header = Header::decode(&mut buf)?;
+    if !header.list {
+        return Err(alloy_rlp::Error::UnexpectedString);
+    }
+
+    // status (or pre-byzantium post state root)
+    Header::decode_bytes(&mut buf, false)?;
+    // cumulative gas used
+    u64::decode(&mut buf)?;
+    // logs bloom
+    Header::decode_bytes(&mut buf, false)?;
+
+    Vec::<RlpReceiptLog>::decode(&mut buf)
+}
+
+/// Computes the keccak256 leaf commitment of a verified receipt log.
+///
+/// Layout (`abi.encodePacked`):
+/// - `uint64 tx_index`
+/// - `uint32 log_index`
+/// - `address emitter`
+/// - `bytes32 topics_hash` = `keccak256(abi.encodePacked(topics))`
+/// - `bytes32 data_hash` = `keccak256(data)`
+pub fn hash_receipt_log(tx_index: u64, log: &ReceiptLog) -> B256 {
+    let mut topics_bytes = Vec::with_capacity(log.topics.len() * 32);
+    for topic in log.topics.iter() {
+        topics_bytes.extend_from_slice(topic.as_slice());
+    }
+    let topics_hash = keccak256(&topics_bytes);
+    let data_hash = keccak256(&log.data);
+
+    let mut encoded = Vec::with_capacity(8 + 4 + 20 + 32 + 32);
+    encoded.extend_from_slice(&tx_index.to_be_bytes());
+    encoded.extend_from_slice(&log.log_index.to_be_bytes());
+    encoded.extend_from_slice(log.emitter.as_slice());
+    encoded.extend_from_slice(topics_hash.as_slice());
+    encoded.extend_from_slice(data_hash.as_slice());
+
+    keccak256(encoded)
+}
+
+/// Verifies the Merkle Patricia Trie (MPT) proofs of transaction receipts against the execution payload's
+/// `receipts_root`, checks the requested logs within each receipt and returns a commitment to the verified logs.
+///
+/// For each receipt proof:
+/// 1. **Receipt Verification**: The receipts trie is keyed by `rlp(tx_index)` (the key is not hashed), the leaf value
+///    is the EIP-2718 encoded receipt. The proof is verified against `receipts_root`.
+/// 2. **Log Verification**: The verified receipt is decoded and for each expected log the emitter, topics and data
+///    must match the log at `log_index` within the receipt exactly.
+///
+/// After successful verification of all logs, the function:
+/// - Computes `keccak256(leaf_0 ‖ leaf_1 ‖ … ‖ leaf_n)` where each leaf is `hash_receipt_log`, in input order.
+/// - Returns `B256::ZERO` when no logs were requested.
+///
+/// # Parameters
+/// - `receipts_root`: The receipts root of the finalized execution payload.
+/// - `receipt_proofs`: Receipts with their MPT proofs and the logs within them which should be proven.
+///
+/// # Errors
+/// - `ReceiptError::InvalidReceiptProof` if a receipt proof is invalid
+/// - `ReceiptError::InvalidReceiptEncoding` if a verified receipt cannot be decoded
+/// - `ReceiptError::MissingLog` if a log index is out of range for its receipt
+/// - `ReceiptError::LogMismatch` if a log does not match its expected emitter, topics or data
+pub fn verify_receipt_proofs(
+    receipts_root: B256,
+    receipt_proofs: Vec<ReceiptProof>,
+) -> Result<B256, ReceiptError> {
+    let mut log_leaves: Vec<u8> = Vec::new();
+
+    for receipt_proof in receipt_proofs {
+        let tx_index = receipt_proof.tx_index;
+        let key_nibbles = Nibbles::unpack(alloy_rlp::encode(tx_index));
+
+        // 1) Verify the receipt under the block's receipts root
+        proof::verify_proof(
+            receipts_root,
+            key_nibbles,
+            Some(receipt_proof.receipt.to_vec()),
+            &receipt_proof.mpt_proof,
+        )
+        .map_err(|e| ReceiptError::InvalidReceiptProof {
+            tx_index,
+            reason: e.to_string(),
+        })?;
+
+        // 2) Decode the verified receipt and check each expected log
+        let receipt_logs = decode_receipt_logs(&receipt_proof.receipt).map_err(|e| {
+            ReceiptError::InvalidReceiptEncoding {
+                tx_index,
+                reason: e.to_string(),
+            }
+        })?;
+
+        for log in receipt_proof.logs.iter() {
+            let receipt_log = receipt_logs.get(log.log_index as usize).ok_or(
+                ReceiptError::MissingLog {
+                    tx_index,
+                    log_index: log.log_index,
+                    n_logs: receipt_logs.len(),
+                },
+            )?;
+
+            if receipt_log.address != log.emitter
+                || receipt_log.topics != log.topics
+                || receipt_log.data != log.data
+            {
+                return Err(ReceiptError::LogMismatch {
+                    tx_index,
+                    log_index: log.log_index,
+                });
+            }
+
+            log_leaves.extend_from_slice(hash_receipt_log(tx_index, log).as_slice());
+        }
+    }
+
+    if log_leaves.is_empty() {
+        return Ok(B256::ZERO);
+    }
+
+    Ok(keccak256(log_leaves))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::{address, b256};
+    use alloy_rlp::RlpEncodable;
+    use alloy_trie::{proof::ProofRetainer, HashBuilder};
+
+    const ENDPOINT: Address = address!("1a44076050125825900e736c501f859c50fe728c");
+    const OTHER_EMITTER: Address = address!("00000000000000000000000000000000000000e1");
+    // keccak256("PacketSent(bytes,bytes,address)")
+    const PACKET_SENT: B256 =
+        b256!("1ab700d4ced0c005b164c0f789fd09fcbb0156d4c2041b8a3bfbcd961cd1567f");
+
+    #[derive(RlpEncodable)]
+    struct TestLog {
+        address: Address,
+        topics: Vec<B256>,
+        data: Bytes,
+    }
+
+    #[derive(RlpEncodable)]
+    struct TestReceipt {
+        status: u8,
+        cumulative_gas_used: u64,
+        logs_bloom: Bytes,
+        logs: Vec<TestLog>,
+    }
+
+    // An EIP-2718 encoded receipt with `logs` of `(emitter, data)`, typed when `tx_type` is set
+    fn encode_receipt(tx_type: Option<u8>, logs: &[(Address, &[u8])]) -> Vec<u8> {
+        let receipt = TestReceipt {
+            status: 1,
+            cumulative_gas_used: 21_000,
+            logs_bloom: Bytes::from(vec![0u8; 256]),
+            logs: logs
+                .iter()
+                .map(|(address, data)| TestLog {
+                    address: *address,
+                    topics: vec![PACKET_SENT, B256::with_last_byte(1)],
+                    data: Bytes::copy_from_slice(data),
+                })
+                .collect(),
+        };
+        let mut encoded = tx_type.map(|tx_type| vec![tx_type]).unwrap_or_default();
+        encoded.extend(alloy_rlp::encode(receipt));
+        encoded
+    }
+
+    fn expected_log(log_index: u32, emitter: Address, data: &[u8]) -> ReceiptLog {
+        ReceiptLog {
+            log_index,
+            emitter,
+            topics: vec![PACKET_SENT, B256::with_last_byte(1)],
+            data: Bytes::copy_from_slice(data),
+        }
+    }
+
+    // Receipts trie over `receipts` keyed by `rlp(tx_index)`, with the proofs of `proven` receipts
+    fn receipts_trie(receipts: &[Vec<u8>], proven: &[(u64, Vec<ReceiptLog>)]) -> (B256, Vec<ReceiptProof>) {
+        let key = |tx_index: u64| Nibbles::unpack(alloy_rlp::encode(tx_index));
+        let targets = proven.iter().map(|(tx_index, _)| key(*tx_index)).collect();
+        let mut leaves: Vec<(Nibbles, &Vec<u8>)> = receipts
+            .iter()
+            .enumerate()
+            .map(|(tx_index, receipt)| (key(tx_index as u64), receipt))
+            .collect();
+        leaves.sort_by(|a, b| a.0.cmp(&b.0));
+
+        let mut hash_builder = HashBuilder::default().with_proof_retainer(ProofRetainer::new(targets));
+        for (leaf_key, receipt) in leaves {
+            hash_builder.add_leaf(leaf_key, receipt);
+        }
+        let receipts_root = hash_builder.root();
+        let proof_nodes = hash_builder.take_proof_nodes();
+
+        let receipt_proofs = proven
+            .iter()
+            .map(|(tx_index, logs)| ReceiptProof {
+                tx_index: *tx_index,
+                receipt: Bytes::from(receipts[*tx_index as usize].clone()),
+                mpt_proof: proof_nodes
+                    .matching_nodes_sorted(&key(*tx_index))
+                    .into_iter()
+                    .map(|(_, node)| node)
+                    .collect(),
+                logs: logs.clone(),
+            })
+            .collect();
+        (receipts_root, receipt_proofs)
+    }
+
+    // A block of a legacy receipt with one endpoint log, a typed receipt with an unrelated log followed by an
+    // endpoint log, and a receipt without logs
+    fn block_receipts() -> Vec<Vec<u8>> {
+        vec![
+            encode_receipt(None, &[(ENDPOINT, b"legacy")]),
+            encode_receipt(Some(0x02), &[(OTHER_EMITTER, b"other"), (ENDPOINT, b"typed")]),
+            encode_receipt(Some(0x02), &[]),
+        ]
+    }
+
+    #[test]
+    fn decodes_legacy_and_typed_receipts() {
+        let receipts = block_receipts();
+
+        let legacy_logs = decode_receipt_logs(&receipts[0]).unwrap();
+        assert_eq!(legacy_logs.len(), 1);
+        assert_eq!(legacy_logs[0].address, ENDPOINT);
+        assert_eq!(legacy_logs[0].topics, vec![PACKET_SENT, B256::with_last_byte(1)]);
+        assert_eq!(legacy_logs[0].data.as_ref(), b"legacy");
+
+        let typed_logs = decode_receipt_logs(&receipts[1]).unwrap();
+        assert_eq!(typed_logs.len(), 2);
+        assert_eq!(typed_logs[0].address, OTHER_EMITTER);
+        assert_eq!(typed_logs[1].address, ENDPOINT);
+        assert_eq!(typed_logs[1].data.as_ref(), b"typed");
+
+        assert!(decode_receipt_logs(&receipts[2]).unwrap().is_empty());
+        assert!(decode_receipt_logs(&[]).is_err());
+        // The payload after the type byte must be an RLP list
+        assert!(decode_receipt_logs(&[0x02, 0x01]).is_err());
+    }
+
+    #[test]
+    fn commits_the_logs_in_input_order() {
+        let receipts = block_receipts();
+        let legacy = (0, vec![expected_log(0, ENDPOINT, b"legacy")]);
+        let typed = (1, vec![expected_log(1, ENDPOINT, b"typed")]);
+
+        let (receipts_root, receipt_proofs) = receipts_trie(&receipts, &[legacy.clone(), typed.clone()]);
+        let root = verify_receipt_proofs(receipts_root, receipt_proofs).unwrap();
+        let mut leaves = Vec::new();
+        leaves.extend_from_slice(hash_receipt_log(0, &legacy.1[0]).as_slice());
+        leaves.extend_from_slice(hash_receipt_log(1, &typed.1[0]).as_slice());
+        assert_eq!(root, keccak256(&leaves));
+
+        // The commitment follows the proofs' order
+        let (_, reversed_proofs) = receipts_trie(&receipts, &[typed, legacy]);
+        assert_ne!(verify_receipt_proofs(receipts_root, reversed_proofs).unwrap(), root);
+
+        assert_eq!(verify_receipt_proofs(receipts_root, Vec::new()).unwrap(), B256::ZERO);
+    }
+
+    #[test]
+    fn hashes_log_fields_in_leaf_order() {
+        let log = expected_log(3, ENDPOINT, b"payload");
+        let mut topics = Vec::new();
+        topics.extend_from_slice(PACKET_SENT.as_slice());
+        topics.extend_from_slice(B256::with_last_byte(1).as_slice());
+        let mut encoded = Vec::new();
+        encoded.extend_from_slice(&7u64.to_be_bytes());
+        encoded.extend_from_slice(&3u32.to_be_bytes());
+        encoded.extend_from_slice(ENDPOINT.as_slice());
+        encoded.extend_from_slice(keccak256(&topics).as_slice());
+        encoded.extend_from_slice(keccak256(b"payload").as_slice());
+        assert_eq!(hash_receipt_log(7, &log), keccak256(encoded));
+        assert_ne!(hash_receipt_log(3, &expected_log(7, ENDPOINT, b"payload")), hash_receipt_log(7, &log));
+    }
+
+    #[test]
+    fn rejects_logs_of_another_emitter() {
+        let receipts = block_receipts();
+
+        // Log 0 of the typed receipt was emitted by another contract
+        let claimed = (1, vec![expected_log(0, ENDPOINT, b"other")]);
+        let (receipts_root, receipt_proofs) = receipts_trie(&receipts, &[claimed]);
+        assert!(matches!(
+            verify_receipt_proofs(receipts_root, receipt_proofs),
+            Err(ReceiptError::LogMismatch { tx_index: 1, log_index: 0 })
+        ));
+
+        let missing = (1, vec![expected_log(2, ENDPOINT, b"typed")]);
+        let (receipts_root, receipt_proofs) = receipts_trie(&receipts, &[missing]);
+        assert!(matches!(
+            verify_receipt_proofs(receipts_root, receipt_proofs),
+            Err(ReceiptError::MissingLog { tx_index: 1, log_index: 2, n_logs: 2 })
+        ));
+    }
+
+    #[test]
+    fn rejects_receipts_not_under_the_receipts_root() {
+        let receipts = block_receipts();
+        let typed = (1, vec![expected_log(1, ENDPOINT, b"typed")]);
+        let (receipts_root, receipt_proofs) = receipts_trie(&receipts, &[typed]);
+
+        let mut tampered_root = receipts_root;
+        tampered_root[0] ^= 1;
+        assert!(matches!(
+            verify_receipt_proofs(tampered_root, receipt_proofs.clone()),
+            Err(ReceiptError::InvalidReceiptProof { tx_index: 1, .. })
+        ));
+
+        // A receipt swapped for another one of the block
+        let mut swapped = receipt_proofs.clone();
+        swapped[0].receipt = Bytes::from(receipts[0].clone());
+        assert!(matches!(
+            verify_receipt_proofs(receipts_root, swapped),
+            Err(ReceiptError::InvalidReceiptProof { tx_index: 1, .. })
+        ));
+
+        // The proven receipt at another index
+        let mut moved = receipt_proofs;
+        moved[0].tx_index = 0;
+        assert!(matches!(
+            verify_receipt_proofs(receipts_root, moved),
+            Err(ReceiptError::InvalidReceiptProof { tx_index: 0, .. })
+        ));
+    }
+}
@@ -0,0 +1,840 @@
+use alloy_primitives::{Address, Bytes, B256, U256};
+use alloy_trie::TrieAccount;
+use anyhow::{anyhow, bail, Context, Result};
+use helios_consensus_core::consensus_spec::ConsensusSpec;
+use helios_consensus_core::types::{
+    BeaconBlockHeader, FinalityUpdate, Forks, LightClientHeader, LightClientStore, SyncCommittee,
+    Update,
+};
+use serde::{Deserialize, Serialize};
+use ssz::{Decode, Encode};
+
+use crate::storage_layout::{
+    StorageBase, StorageKey, StorageKeyKind, StorageLayout, StoragePathStep,
+};
+use crate::types::{
+    ContractStorage, HistoricalBlockProof, HistoricalBlockRootProof, ProofInputs, ReceiptLog,
+    ReceiptProof, StorageSlot,
+};
+
+/// Marker of the sectioned guest input layout.
+pub const GUEST_INPUT_MAGIC: [u8; 4] = *b"GSMI";
+/// Version of the sectioned guest input layout.
+pub const GUEST_INPUT_VERSION: u16 = 2;
+
+/// Size of a BLS public key (compressed G1 point).
+const PUBKEY_SIZE: usize = 48;
+
+/// Light client headers and updates of the consensus section: helios superstruct types, which only support serde.
+/// They are small next to the store's sync committees, updates are only given on sync committee period boundaries.
+#[derive(Serialize, Deserialize)]
+struct ConsensusUpdates<S: ConsensusSpec> {
+    finalized_header: LightClientHeader,
+    optimistic_header: LightClientHeader,
+    updates: Vec<Update<S>>,
+    finality_update: Option<FinalityUpdate<S>>,
+    force_update: Option<Update<S>>,
+    forks: Forks,
+}
+
+/// Encodes `ProofInputs` in the sectioned guest input layout:
+///
+/// `GUEST_INPUT_MAGIC ‖ version as u16 ‖ consensus ‖ execution ‖ historical`, each section prefixed by its length
+/// (u32). All integers are little endian, except the big endian 256 bit words.
+///
+/// - `consensus`: fixed fields, then the store's sync committees at fixed offsets, then the light client headers
+///   and updates:
+///
+///   | Offset                  | Size              | Field                                                 |
+///   |-------------------------|-------------------|-------------------------------------------------------|
+///   | 0                       | 8                 | `expected_current_slot`                               |
+///   | 8                       | 32                | `genesis_root`                                        |
+///   | 40                      | 32                | `store_hash`                                          |
+///   | 72                      | 8                 | `min_sync_committee_participants`                     |
+///   | 80                      | 8                 | `store.previous_max_active_participants`              |
+///   | 88                      | 8                 | `store.current_max_active_participants`               |
+///   | 96                      | 4                 | sync committee size `n`                               |
+///   | 100                     | 1                 | 1 when the store has a next sync committee, else 0    |
+///   | 101                     | `(n + 1) * 48`    | `store.current_sync_committee`: pubkeys, aggregate    |
+///   | 101 + `(n + 1) * 48`    | `(n + 1) * 48`    | `store.next_sync_committee`, zeroed without one       |
+///   | 101 + `2 * (n + 1) * 48`| 4 + len           | CBOR `ConsensusUpdates` (headers, updates, forks)     |
+///
+///   Public keys are copied as their SSZ bytes (compressed points), so the bulk of the section decodes without
+///   parsing. The store must not hold fields outside this layout (e.g. a best valid update), which is checked here.
+/// - `execution`: the contract storages, input contract storages and receipt proofs in a flat binary layout of fixed
+///   width fields and raw byte strings, with no field names or hex strings to parse. This is where the bulk of a
+///   window's input is (MPT nodes, one storage slot per depositor).
+/// - `historical`: the historical block proofs in the same flat layout, but for their execution payload headers
+///   (helios superstruct types, CBOR encoded).
+///
+/// This is a partial layout: only the store's sync committees sit at fixed offsets, the light client headers,
+/// updates and forks and the historical execution payload headers remain CBOR, and everything else is flat but
+/// length prefixed. It is not zero-copy either: decoding copies every field, sync committee public keys included,
+/// into the owned types the program takes (helios verifies owned stores and updates), so the layout saves the
+/// parsing of CBOR, not the copies. Borrowing the committees from the input buffer is out of scope, it would take a
+/// store verification over borrowed public keys which helios does not offer.
+pub fn encode_guest_inputs<S: ConsensusSpec>(inputs: &ProofInputs<S>) -> Result<Vec<u8>> {
+    let store = &inputs.store;
+    let mut consensus = FlatWriter::default();
+    consensus.u64(inputs.expected_current_slot);
+    consensus.fixed(inputs.genesis_root.as_slice());
+    consensus.fixed(inputs.store_hash.as_slice());
+    consensus.u64(inputs.min_sync_committee_participants);
+    consensus.u64(store.previous_max_active_participants);
+    consensus.u64(store.current_max_active_participants);
+    let committee_size = store.current_sync_committee.pubkeys.len();
+    consensus.len(committee_size)?;
+    consensus.u8(store.next_sync_committee.is_some() as u8);
+    consensus.sync_committee(&store.current_sync_committee);
+    match &store.next_sync_committee {
+        Some(next_sync_committee) => {
+            if next_sync_committee.pubkeys.len() != committee_size {
+                bail!(
+                    "Next sync committee has {} members, the current one {}",
+                    next_sync_committee.pubkeys.len(),
+                    committee_size
+                );
+            }
+            consensus.sync_committee(next_sync_committee);
+        }
+        None => consensus.fixed(&vec![0u8; (committee_size + 1) * PUBKEY_SIZE]),
+    }
+    consensus.bytes(
+        &serde_cbor::to_vec(&ConsensusUpdates::<S> {
+            finalized_header: store.finalized_header.clone(),
+            optimistic_header: store.optimistic_header.clone(),
+            updates: inputs.updates.clone(),
+            finality_update: inputs.finality_update.clone(),
+            force_update: inputs.force_update.clone(),
+            forks: inputs.forks.clone(),
+        })
+        .context("Failed to encode consensus updates")?,
+    )?;
+
+    let mut execution = FlatWriter::default();
+    execution.contract_storages(&inputs.contract_storages)?;
+    match &inputs.input_contract_storages {
+        Some(input_contract_storages) => {
+            execution.u8(1);
+            execution.contract_storages(input_contract_storages)?;
+        }
+        None => execution.u8(0),
+    }
+    execution.receipt_proofs(&inputs.receipt_proofs)?;
+
+    let mut historical = FlatWriter::default();
+    historical.len(inputs.historical_blocks.len())?;
+    for historical_block in inputs.historical_blocks.iter() {
+        historical.historical_block(historical_block)?;
+    }
+
+    let mut encoded = FlatWriter::default();
+    encoded.fixed(&GUEST_INPUT_MAGIC);
+    encoded.fixed(&GUEST_INPUT_VERSION.to_le_bytes());
+    encoded.bytes(&consensus.0)?;
+    encoded.bytes(&execution.0)?;
+    encoded.bytes(&historical.0)?;
+
+    // The store is rebuilt from the fields above, any other field would be lost
+    let decoded = decode_guest_inputs::<S>(&encoded.0)?;
+    if serde_cbor::to_vec(&decoded.store)? != serde_cbor::to_vec(store)? {
+        bail!("Store holds fields the guest input layout does not carry");
+    }
+
+    Ok(encoded.0)
+}
+
+/// Decodes guest inputs in the sectioned layout (see `encode_guest_inputs`) into owned `ProofInputs`.
+pub fn decode_guest_inputs<S: ConsensusSpec>(bytes: &[u8]) -> Result<ProofInputs<S>> {
+    if !bytes.starts_with(&GUEST_INPUT_MAGIC) {
+        bail!("Guest input does not start with the guest input marker");
+    }
+
+    let mut reader = FlatReader::new(&bytes[GUEST_INPUT_MAGIC.len()..]);
+    let version = u16::from_le_bytes(reader.fixed::<2>()?);
+    if version != GUEST_INPUT_VERSION {
+        bail!(
+            "Unsupported guest input version {}, expected {}",
+            version,
+            GUEST_INPUT_VERSION
+        );
+    }
+
+    let mut consensus = FlatReader::new(reader.bytes()?);
+    let expected_current_slot = consensus.u64()?;
+    let genesis_root = consensus.b256()?;
+    let store_hash = consensus.b256()?;
+    let min_sync_committee_participants = consensus.u64()?;
+    let previous_max_active_participants = consensus.u64()?;
+    let current_max_active_participants = consensus.u64()?;
+    let committee_size = consensus.len()?;
+    let has_next_sync_committee = match consensus.u8()? {
+        0 => false,
+        1 => true,
+        flag => bail!("Invalid next sync committee flag {}", flag),
+    };
+    let current_sync_committee = consensus.sync_committee::<S>(committee_size)?;
+    let next_sync_committee = if has_next_sync_committee {
+        Some(consensus.sync_committee::<S>(committee_size)?)
+    } else {
+        consensus.take((committee_size + 1) * PUBKEY_SIZE)?;
+        None
+    };
+    let consensus_updates: ConsensusUpdates<S> =
+        serde_cbor::from_slice(consensus.bytes()?).context("Failed to decode consensus updates")?;
+    consensus.finish()?;
+
+    let mut store = LightClientStore::<S>::default();
+    store.finalized_header = consensus_updates.finalized_header;
+    store.current_sync_committee = current_sync_committee;
+    store.next_sync_committee = next_sync_committee;
+    store.optimistic_header = consensus_updates.optimistic_header;
+    store.previous_max_active_participants = previous_max_active_participants;
+    store.current_max_active_participants = current_max_active_participants;
+
+    let mut execution = FlatReader::new(reader.bytes()?);
+    let contract_storages = execution.contract_storages()?;
+    let input_contract_storages = match execution.u8()? {
+        0 => None,
+        1 => Some(execution.contract_storages()?),
+        flag => bail!("Invalid input contract storages flag {}", flag),
+    };
+    let receipt_proofs = execution.receipt_proofs()?;
+    execution.finish()?;
+
+    let mut historical = FlatReader::new(reader.bytes()?);
+    let n_historical_blocks = historical.len()?;
+    let mut historical_blocks = Vec::with_capacity(n_historical_blocks);
+    for _ in 0..n_historical_blocks {
+        historical_blocks.push(historical.historical_block::<S>()?);
+    }
+    historical.finish()?;
+    reader.finish()?;
+
+    Ok(ProofInputs {
+        updates: consensus_updates.updates,
+        finality_update: consensus_updates.finality_update,
+        force_update: consensus_updates.force_update,
+        expected_current_slot,
+        store,
+        genesis_root,
+        forks: consensus_updates.forks,
+        store_hash,
+        min_sync_committee_participants,
+        contract_storages,
+        input_contract_storages,
+        receipt_proofs,
+        historical_blocks,
+    })
+}
+
+#[derive(Default)]
+struct FlatWriter(Vec<u8>);
+
+impl FlatWriter {
+    fn u8(&mut self, value: u8) {
+        self.0.push(value);
+    }
+
+    fn u32(&mut self, value: u32) {
+        self.0.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn u64(&mut self, value: u64) {
+        self.0.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn len(&mut self, len: usize) -> Result<()> {
+        self.u32(u32::try_from(len).map_err(|_| anyhow!("Length {} does not fit in a u32", len))?);
+        Ok(())
+    }
+
+    fn fixed(&mut self, bytes: &[u8]) {
+        self.0.extend_from_slice(bytes);
+    }
+
+    fn bytes(&mut self, bytes: &[u8]) -> Result<()> {
+        self.len(bytes.len())?;
+        self.fixed(bytes);
+        Ok(())
+    }
+
+    fn nodes(&mut self, nodes: &[Bytes]) -> Result<()> {
+        self.len(nodes.len())?;
+        for node in nodes.iter() {
+            self.bytes(node)?;
+        }
+        Ok(())
+    }
+
+    fn branch(&mut self, branch: &[B256]) -> Result<()> {
+        self.len(branch.len())?;
+        for node in branch.iter() {
+            self.fixed(node.as_slice());
+        }
+        Ok(())
+    }
+
+    fn sync_committee<S: ConsensusSpec>(&mut self, sync_committee: &SyncCommittee<S>) {
+        for pubkey in sync_committee.pubkeys.iter() {
+            self.fixed(&pubkey.as_ssz_bytes());
+        }
+        self.fixed(&sync_committee.aggregate_pubkey.as_ssz_bytes());
+    }
+
+    fn storage_key_kind(&mut self, kind: StorageKeyKind) {
+        self.u8(match kind {
+            StorageKeyKind::Address => 0,
+            StorageKeyKind::Uint256 => 1,
+            StorageKeyKind::Bytes32 => 2,
+        });
+    }
+
+    fn layout(&mut self, layout: &StorageLayout) -> Result<()> {
+        match &layout.base {
+            StorageBase::Slot(slot) => {
+                self.u8(0);
+                self.fixed(&slot.to_be_bytes::<32>());
+            }
+            StorageBase::Erc7201(namespace) => {
+                self.u8(1);
+                self.bytes(namespace.as_bytes())?;
+            }
+        }
+        self.len(layout.path.len())?;
+        for step in layout.path.iter() {
+            match step {
+                StoragePathStep::Mapping(kind) => {
+                    self.u8(0);
+                    self.storage_key_kind(*kind);
+                }
+                StoragePathStep::ArrayElement { element_slots } => {
+                    self.u8(1);
+                    self.u64(*element_slots);
+                }
+                StoragePathStep::Offset(offset) => {
+                    self.u8(2);
+                    self.u64(*offset);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn storage_key(&mut self, key: &StorageKey) {
+        match key {
+            StorageKey::Address(address) => {
+                self.u8(0);
+                self.fixed(address.as_slice());
+            }
+            StorageKey::Uint256(value) => {
+                self.u8(1);
+                self.fixed(&value.to_be_bytes::<32>());
+            }
+            StorageKey::Bytes32(value) => {
+                self.u8(2);
+                self.fixed(value.as_slice());
+            }
+        }
+    }
+
+    fn contract_storages(&mut self, contract_storages: &[ContractStorage]) -> Result<()> {
+        self.len(contract_storages.len())?;
+        for contract_storage in contract_storages.iter() {
+            self.fixed(contract_storage.address.as_slice());
+            match &contract_storage.expected_value {
+                Some(account) => {
+                    self.u8(1);
+                    self.u64(account.nonce);
+                    self.fixed(&account.balance.to_be_bytes::<32>());
+                    self.fixed(account.storage_root.as_slice());
+                    self.fixed(account.code_hash.as_slice());
+                }
+                None => self.u8(0),
+            }
+            self.nodes(&contract_storage.mpt_proof)?;
+            self.layout(&contract_storage.layout)?;
+            self.nodes(&contract_storage.storage_proof_nodes)?;
+            self.len(contract_storage.storage_slots.len())?;
+            for slot in contract_storage.storage_slots.iter() {
+                self.fixed(slot.key.as_slice());
+                self.fixed(slot.slot_key_address.as_slice());
+                self.len(slot.layout_keys.len())?;
+                for key in slot.layout_keys.iter() {
+                    self.storage_key(key);
+                }
+                self.fixed(&slot.expected_value.to_be_bytes::<32>());
+                self.len(slot.mpt_proof_path.len())?;
+                for index in slot.mpt_proof_path.iter() {
+                    self.u32(*index);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn receipt_proof(&mut self, receipt_proof: &ReceiptProof) -> Result<()> {
+        self.u64(receipt_proof.tx_index);
+        self.bytes(&receipt_proof.receipt)?;
+        self.nodes(&receipt_proof.mpt_proof)?;
+        self.len(receipt_proof.logs.len())?;
+        for log in receipt_proof.logs.iter() {
+            self.u32(log.log_index);
+            self.fixed(log.emitter.as_slice());
+            self.len(log.topics.len())?;
+            for topic in log.topics.iter() {
+                self.fixed(topic.as_slice());
+            }
+            self.bytes(&log.data)?;
+        }
+        Ok(())
+    }
+
+    fn receipt_proofs(&mut self, receipt_proofs: &[ReceiptProof]) -> Result<()> {
+        self.len(receipt_proofs.len())?;
+        for receipt_proof in receipt_proofs.iter() {
+            self.receipt_proof(receipt_proof)?;
+        }
+        Ok(())
+    }
+
+    fn historical_block<S: ConsensusSpec>(
+        &mut self,
+        historical_block: &HistoricalBlockProof<S>,
+    ) -> Result<()> {
+        let header = &historical_block.header;
+        self.u64(header.slot);
+        self.u64(header.proposer_index);
+        self.fixed(header.parent_root.as_slice());
+        self.fixed(header.state_root.as_slice());
+        self.fixed(header.body_root.as_slice());
+        match &historical_block.block_root_proof {
+            HistoricalBlockRootProof::BlockRoots { branch } => {
+                self.u8(0);
+                self.branch(branch)?;
+            }
+            HistoricalBlockRootProof::HistoricalSummaries {
+                block_summary_root,
+                block_roots_branch,
+                historical_summary_branch,
+            } => {
+                self.u8(1);
+                self.fixed(block_summary_root.as_slice());
+                self.branch(block_roots_branch)?;
+                self.branch(historical_summary_branch)?;
+            }
+        }
+        // Execution payload headers are helios superstruct types, which only support serde
+        self.bytes(
+            &serde_cbor::to_vec(&historical_block.execution)
+                .context("Failed to encode execution payload header")?,
+        )?;
+        self.branch(&historical_block.execution_branch)?;
+        self.contract_storages(&historical_block.contract_storages)?;
+        self.receipt_proofs(&historical_block.receipt_proofs)
+    }
+}
+
+/// Reads the flat layout field by field, copying the values out of the input buffer.
+struct FlatReader<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> FlatReader<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        FlatReader { bytes, offset: 0 }
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        let end = self
+            .offset
+            .checked_add(len)
+            .filter(|end| *end <= self.bytes.len())
+            .ok_or_else(|| {
+                anyhow!(
+                    "Guest input truncated: {} bytes requested at offset {} of {}",
+                    len,
+                    self.offset,
+                    self.bytes.len()
+                )
+            })?;
+        let slice = &self.bytes[self.offset..end];
+        self.offset = end;
+        Ok(slice)
+    }
+
+    fn fixed<const N: usize>(&mut self) -> Result<[u8; N]> {
+        Ok(self.take(N)?.try_into().unwrap())
+    }
+
+    fn u8(&mut self) -> Result<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u32(&mut self) -> Result<u32> {
+        Ok(u32::from_le_bytes(self.fixed::<4>()?))
+    }
+
+    fn u64(&mut self) -> Result<u64> {
+        Ok(u64::from_le_bytes(self.fixed::<8>()?))
+    }
+
+    fn len(&mut self) -> Result<usize> {
+        Ok(self.u32()? as usize)
+    }
+
+    fn address(&mut self) -> Result<Address> {
+        Ok(Address::from(self.fixed::<20>()?))
+    }
+
+    fn b256(&mut self) -> Result<B256> {
+        Ok(B256::from(self.fixed::<32>()?))
+    }
+
+    fn u256(&mut self) -> Result<U256> {
+        Ok(U256::from_be_bytes(self.fixed::<32>()?))
+    }
+
+    fn bytes(&mut self) -> Result<&'a [u8]> {
+        let len = self.len()?;
+        self.take(len)
+    }
+
+    fn nodes(&mut self) -> Result<Vec<Bytes>> {
+        let n_nodes = self.len()?;
+        let mut nodes = Vec::with_capacity(n_nodes);
+        for _ in 0..n_nodes {
+            nodes.push(Bytes::copy_from_slice(self.bytes()?));
+        }
+        Ok(nodes)
+    }
+
+    fn branch(&mut self) -> Result<Vec<B256>> {
+        let n_nodes = self.len()?;
+        let mut branch = Vec::with_capacity(n_nodes);
+        for _ in 0..n_nodes {
+            branch.push(self.b256()?);
+        }
+        Ok(branch)
+    }
+
+    fn sync_committee<S: ConsensusSpec>(
+        &mut self,
+        committee_size: usize,
+    ) -> Result<SyncCommittee<S>> {
+        let mut sync_committee = SyncCommittee::<S>::default();
+        if sync_committee.pubkeys.len() != committee_size {
+            bail!(
+                "Sync committee of {} members, the consensus spec has {}",
+                committee_size,
+                sync_committee.pubkeys.len()
+            );
+        }
+        for pubkey in sync_committee.pubkeys.iter_mut() {
+            *pubkey = Decode::from_ssz_bytes(self.take(PUBKEY_SIZE)?)
+                .map_err(|e| anyhow!("Invalid sync committee pubkey: {:?}", e))?;
+        }
+        sync_committee.aggregate_pubkey = Decode::from_ssz_bytes(self.take(PUBKEY_SIZE)?)
+            .map_err(|e| anyhow!("Invalid sync committee aggregate pubkey: {:?}", e))?;
+        Ok(sync_committee)
+    }
+
+    fn storage_key_kind(&mut self) -> Result<StorageKeyKind> {
+        match self.u8()? {
+            0 => Ok(StorageKeyKind::Address),
+            1 => Ok(StorageKeyKind::Uint256),
+            2 => Ok(StorageKeyKind::Bytes32),
+            tag => bail!("Invalid storage key kind tag {}", tag),
+        }
+    }
+
+    fn layout(&mut self) -> Result<StorageLayout> {
+        let base = match self.u8()? {
+            0 => StorageBase::Slot(self.u256()?),
+            1 => StorageBase::Erc7201(
+                String::from_utf8(self.bytes()?.to_vec())
+                    .map_err(|e| anyhow!("Invalid ERC-7201 namespace: {}", e))?,
+            ),
+            tag => bail!("Invalid storage base tag {}", tag),
+        };
+        let n_steps = self.len()?;
+        let mut path = Vec::with_capacity(n_steps);
+        for _ in 0..n_steps {
+            path.push(match self.u8()? {
+                0 => StoragePathStep::Mapping(self.storage_key_kind()?),
+                1 => StoragePathStep::ArrayElement {
+                    element_slots: self.u64()?,
+                },
+                2 => StoragePathStep::Offset(self.u64()?),
+                tag => bail!("Invalid storage path step tag {}", tag),
+            });
+        }
+        Ok(StorageLayout { base, path })
+    }
+
+    fn storage_key(&mut self) -> Result<StorageKey> {
+        match self.u8()? {
+            0 => Ok(StorageKey::Address(self.address()?)),
+            1 => Ok(StorageKey::Uint256(self.u256()?)),
+            2 => Ok(StorageKey::Bytes32(self.b256()?)),
+            tag => bail!("Invalid storage key tag {}", tag),
+        }
+    }
+
+    fn contract_storages(&mut self) -> Result<Vec<ContractStorage>> {
+        let n_contract_storages = self.len()?;
+        let mut contract_storages = Vec::with_capacity(n_contract_storages);
+        for _ in 0..n_contract_storages {
+            let address = self.address()?;
+            let expected_value = match self.u8()? {
+                0 => None,
+                1 => Some(TrieAccount {
+                    nonce: self.u64()?,
+                    balance: self.u256()?,
+                    storage_root: self.b256()?,
+                    code_hash: self.b256()?,
+                }),
+                flag => bail!("Invalid account flag {}", flag),
+            };
+            let mpt_proof = self.nodes()?;
+            let layout = self.layout()?;
+            let storage_proof_nodes = self.nodes()?;
+
+            let n_slots = self.len()?;
+            let mut storage_slots = Vec::with_capacity(n_slots);
+            for _ in 0..n_slots {
+                let key = self.b256()?;
+                let slot_key_address = self.address()?;
+                let n_layout_keys = self.len()?;
+                let mut layout_keys = Vec::with_capacity(n_layout_keys);
+                for _ in 0..n_layout_keys {
+                    layout_keys.push(self.storage_key()?);
+                }
+                let expected_value = self.u256()?;
+                let path_len = self.len()?;
+                let mut mpt_proof_path = Vec::with_capacity(path_len);
+                for _ in 0..path_len {
+                    mpt_proof_path.push(self.u32()?);
+                }
+                storage_slots.push(StorageSlot {
+                    key,
+                    slot_key_address,
+                    layout_keys,
+                    expected_value,
+                    mpt_proof_path,
+                });
+            }
+
+            contract_storages.push(ContractStorage {
+                address,
+                expected_value,
+                mpt_proof,
+                layout,
+                storage_proof_nodes,
+                storage_slots,
+            });
+        }
+        Ok(contract_storages)
+    }
+
+    fn receipt_proof(&mut self) -> Result<ReceiptProof> {
+        let tx_index = self.u64()?;
+        let receipt = Bytes::copy_from_slice(self.bytes()?);
+        let mpt_proof = self.nodes()?;
+        let n_logs = self.len()?;
+        let mut logs = Vec::with_capacity(n_logs);
+        for _ in 0..n_logs {
+            let log_index = self.u32()?;
+            let emitter = self.address()?;
+            let n_topics = self.len()?;
+            let mut topics = Vec::with_capacity(n_topics);
+            for _ in 0..n_topics {
+                topics.push(self.b256()?);
+            }
+            let data = Bytes::copy_from_slice(self.bytes()?);
+            logs.push(ReceiptLog {
+                log_index,
+                emitter,
+                topics,
+                data,
+            });
+        }
+        Ok(ReceiptProof {
+            tx_index,
+            receipt,
+            mpt_proof,
+            logs,
+        })
+    }
+
+    fn receipt_proofs(&mut self) -> Result<Vec<ReceiptProof>> {
+        let n_receipt_proofs = self.len()?;
+        let mut receipt_proofs = Vec::with_capacity(n_receipt_proofs);
+        for _ in 0..n_receipt_proofs {
+            receipt_proofs.push(self.receipt_proof()?);
+        }
+        Ok(receipt_proofs)
+    }
+
+    fn historical_block<S: ConsensusSpec>(&mut self) -> Result<HistoricalBlockProof<S>> {
+        let header = BeaconBlockHeader {
+            slot: self.u64()?,
+            proposer_index: self.u64()?,
+            parent_root: self.b256()?,
+            state_root: self.b256()?,
+            body_root: self.b256()?,
+        };
+        let block_root_proof = match self.u8()? {
+            0 => HistoricalBlockRootProof::BlockRoots {
+                branch: self.branch()?,
+            },
+            1 => HistoricalBlockRootProof::HistoricalSummaries {
+                block_summary_root: self.b256()?,
+                block_roots_branch: self.branch()?,
+                historical_summary_branch: self.branch()?,
+            },
+            tag => bail!("Invalid historical block root proof tag {}", tag),
+        };
+        let execution = serde_cbor::from_slice(self.bytes()?)
+            .context("Failed to decode execution payload header")?;
+        Ok(HistoricalBlockProof {
+            header,
+            block_root_proof,
+            execution,
+            execution_branch: self.branch()?,
+            contract_storages: self.contract_storages()?,
+            receipt_proofs: self.receipt_proofs()?,
+        })
+    }
+
+    fn finish(&self) -> Result<()> {
+        if self.offset != self.bytes.len() {
+            bail!(
+                "Guest input has {} trailing bytes",
+                self.bytes.len() - self.offset
+            );
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::{address, b256};
+    use helios_consensus_core::consensus_spec::MainnetConsensusSpec;
+
+    fn contract_storage(
+        layout: StorageLayout,
+        expected_value: Option<TrieAccount>,
+    ) -> ContractStorage {
+        ContractStorage {
+            address: address!("00000000000000000000000000000000000000aa"),
+            expected_value,
+            mpt_proof: vec![Bytes::from(vec![0xf8, 0x51]), Bytes::from(vec![0xe2; 40])],
+            layout,
+            storage_proof_nodes: vec![Bytes::from(vec![0xc0])],
+            storage_slots: vec![StorageSlot {
+                key: b256!("00000000000000000000000000000000000000000000000000000000000000ff"),
+                slot_key_address: address!("00000000000000000000000000000000000000bb"),
+                layout_keys: vec![
+                    StorageKey::Address(address!("00000000000000000000000000000000000000bb")),
+                    StorageKey::Uint256(U256::from(3)),
+                    StorageKey::Bytes32(B256::repeat_byte(0x11)),
+                ],
+                expected_value: U256::MAX,
+                mpt_proof_path: vec![0, 7, 2],
+            }],
+        }
+    }
+
+    // Flat encodings carry no field names, compare them through the serde encoding
+    fn assert_same<T: Serialize>(actual: &T, expected: &T) {
+        assert_eq!(
+            serde_cbor::to_vec(actual).unwrap(),
+            serde_cbor::to_vec(expected).unwrap()
+        );
+    }
+
+    #[test]
+    fn contract_storages_round_trip() {
+        let contract_storages = vec![
+            contract_storage(
+                StorageLayout::locked_tokens(),
+                Some(TrieAccount {
+                    nonce: 1,
+                    balance: U256::from(10).pow(U256::from(18)),
+                    storage_root: B256::repeat_byte(0x22),
+                    code_hash: B256::repeat_byte(0x33),
+                }),
+            ),
+            contract_storage(
+                StorageLayout {
+                    base: StorageBase::Erc7201("golem.storage.Deposits".to_string()),
+                    path: vec![
+                        StoragePathStep::Mapping(StorageKeyKind::Bytes32),
+                        StoragePathStep::ArrayElement { element_slots: 2 },
+                        StoragePathStep::Offset(1),
+                    ],
+                },
+                None,
+            ),
+        ];
+
+        let mut writer = FlatWriter::default();
+        writer.contract_storages(&contract_storages).unwrap();
+        let mut reader = FlatReader::new(&writer.0);
+        let decoded = reader.contract_storages().unwrap();
+        reader.finish().unwrap();
+
+        assert_same(&decoded, &contract_storages);
+        assert_eq!(decoded[1].layout, contract_storages[1].layout);
+    }
+
+    #[test]
+    fn receipt_proofs_round_trip() {
+        let receipt_proofs = vec![ReceiptProof {
+            tx_index: 5,
+            receipt: Bytes::from(vec![0x02, 0xf9, 0x01]),
+            mpt_proof: vec![Bytes::from(vec![0xf9; 70])],
+            logs: vec![ReceiptLog {
+                log_index: 1,
+                emitter: address!("00000000000000000000000000000000000000cc"),
+                topics: vec![B256::repeat_byte(0x44), B256::repeat_byte(0x55)],
+                data: Bytes::from(vec![0x66; 96]),
+            }],
+        }];
+
+        let mut writer = FlatWriter::default();
+        writer.receipt_proofs(&receipt_proofs).unwrap();
+        let mut reader = FlatReader::new(&writer.0);
+        let decoded = reader.receipt_proofs().unwrap();
+        reader.finish().unwrap();
+
+        assert_same(&decoded, &receipt_proofs);
+    }
+
+    #[test]
+    fn rejects_truncated_and_trailing_bytes() {
+        let mut writer = FlatWriter::default();
+        writer
+            .contract_storages(&[contract_storage(StorageLayout::locked_tokens(), None)])
+            .unwrap();
+
+        let truncated = &writer.0[..writer.0.len() - 1];
+        assert!(FlatReader::new(truncated).contract_storages().is_err());
+
+        let mut trailing = writer.0.clone();
+        trailing.push(0);
+        let mut reader = FlatReader::new(&trailing);
+        reader.contract_storages().unwrap();
+        assert!(reader.finish().is_err());
+    }
+
+    #[test]
+    fn rejects_inputs_without_the_marker() {
+        let cbor_inputs = serde_cbor::to_vec(&vec![0u8; 8]).unwrap();
+        assert!(decode_guest_inputs::<MainnetConsensusSpec>(&cbor_inputs).is_err());
+    }
+}
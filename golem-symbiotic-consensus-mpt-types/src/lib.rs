@@ -1 +1,5 @@
 pub mod types;
+pub mod storage_layout;
+pub mod public_values;
+pub mod network;
+pub mod guest_input;
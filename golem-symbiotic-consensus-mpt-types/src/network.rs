@@ -0,0 +1,67 @@
+use alloy_primitives::{keccak256, B256};
+use helios_consensus_core::consensus_spec::{
+    ConsensusSpec, MainnetConsensusSpec, MinimalConsensusSpec,
+};
+use helios_consensus_core::types::{Fork, Forks};
+
+/// Name of a consensus spec, committed as part of the network identity, and the preset constants which helios'
+/// `ConsensusSpec` does not expose.
+pub trait ConsensusSpecName: ConsensusSpec {
+    fn spec_name() -> &'static str;
+    /// `SLOTS_PER_HISTORICAL_ROOT`, the length of the beacon state's `block_roots` (a power of two).
+    fn slots_per_historical_root() -> u64;
+}
+
+impl ConsensusSpecName for MainnetConsensusSpec {
+    fn spec_name() -> &'static str {
+        "mainnet"
+    }
+
+    fn slots_per_historical_root() -> u64 {
+        8192
+    }
+}
+
+impl ConsensusSpecName for MinimalConsensusSpec {
+    fn spec_name() -> &'static str {
+        "minimal"
+    }
+
+    fn slots_per_historical_root() -> u64 {
+        64
+    }
+}
+
+/// Default minimum number of sync committee members that must sign a finality update: a 2/3 supermajority.
+pub fn sync_committee_supermajority<S: ConsensusSpec>() -> u64 {
+    (2 * S::sync_committee_size()).div_ceil(3)
+}
+
+/// Computes the network identity digest a proof is bound to.
+///
+/// `keccak256` over:
+/// - `genesis_root`
+/// - each fork of the schedule, from genesis to electra: `epoch as u64 ‖ fork_version`
+/// - `len(spec_name) as u32 ‖ spec_name`
+pub fn network_identity_digest<S: ConsensusSpecName>(genesis_root: &B256, forks: &Forks) -> B256 {
+    let schedule: [&Fork; 6] = [
+        &forks.genesis,
+        &forks.altair,
+        &forks.bellatrix,
+        &forks.capella,
+        &forks.deneb,
+        &forks.electra,
+    ];
+    let spec_name = S::spec_name();
+
+    let mut encoded = Vec::with_capacity(32 + schedule.len() * (8 + 4) + 4 + spec_name.len());
+    encoded.extend_from_slice(genesis_root.as_slice());
+    for fork in schedule.iter() {
+        encoded.extend_from_slice(&fork.epoch.to_be_bytes());
+        encoded.extend_from_slice(fork.fork_version.as_slice());
+    }
+    encoded.extend_from_slice(&(spec_name.len() as u32).to_be_bytes());
+    encoded.extend_from_slice(spec_name.as_bytes());
+
+    keccak256(encoded)
+}
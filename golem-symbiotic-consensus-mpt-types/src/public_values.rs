@@ -0,0 +1,68 @@
+use alloy_primitives::Bytes;
+use alloy_sol_types::{sol, SolType, SolValue};
+use anyhow::{anyhow, bail, Result};
+
+// Public values structs, generated from the solidity definitions consumed on-chain.
+sol!("../src/layerzero/zk/ZkEthStatePublicValues.sol");
+
+pub use ZkEthStatePublicValues::{
+    AggregationPublicValues, BootstrapPublicValues, ChainHead, ConsensusPublicValues,
+    PublicValuesEnvelope, RotationPublicValues, TransitionPublicValues,
+};
+
+/// Version of `TransitionPublicValues` committed by the consensus MPT transition program.
+pub const TRANSITION_PUBLIC_VALUES_VERSION: u16 = 1;
+/// Version of `ConsensusPublicValues` committed by the consensus only program.
+pub const CONSENSUS_PUBLIC_VALUES_VERSION: u16 = 1;
+/// Version of `BootstrapPublicValues` committed by the bootstrap program.
+pub const BOOTSTRAP_PUBLIC_VALUES_VERSION: u16 = 1;
+/// Version of `RotationPublicValues` committed by the sync committee rotation program.
+pub const ROTATION_PUBLIC_VALUES_VERSION: u16 = 1;
+/// Version of `AggregationPublicValues` committed by the aggregation program.
+pub const AGGREGATION_PUBLIC_VALUES_VERSION: u16 = 1;
+
+/// Encodes `payload` as versioned public values: `abi.encode(PublicValuesEnvelope(version, abi.encode(payload)))`.
+pub fn encode_public_values<T: SolValue>(version: u16, payload: &T) -> Vec<u8> {
+    PublicValuesEnvelope {
+        version,
+        payload: Bytes::from(payload.abi_encode()),
+    }
+    .abi_encode()
+}
+
+/// Decodes versioned public values, rejecting any version other than `expected_version`.
+pub fn decode_public_values<T: SolValue + From<<T::SolType as SolType>::RustType>>(
+    bytes: &[u8],
+    expected_version: u16,
+) -> Result<T> {
+    let envelope = <PublicValuesEnvelope as SolType>::abi_decode(bytes, true)
+        .map_err(|e| anyhow!("Invalid public values envelope: {}", e))?;
+    if envelope.version != expected_version {
+        bail!(
+            "Unsupported public values version {}, expected {}",
+            envelope.version,
+            expected_version
+        );
+    }
+    T::abi_decode(&envelope.payload, true)
+        .map_err(|e| anyhow!("Invalid public values payload (version {}): {}", envelope.version, e))
+}
+
+/// Decodes the `ChainHead` of transition, rotation or aggregation public values of any version, returning it with
+/// the version. Only the leading fields are read, which are frozen across versions.
+pub fn decode_chain_head(bytes: &[u8]) -> Result<(u16, ChainHead)> {
+    let envelope = <PublicValuesEnvelope as SolType>::abi_decode(bytes, true)
+        .map_err(|e| anyhow!("Invalid public values envelope: {}", e))?;
+    let head_size = <ChainHead as SolType>::ENCODED_SIZE
+        .ok_or_else(|| anyhow!("Chain head is not statically sized"))?;
+    if envelope.payload.len() < head_size {
+        bail!(
+            "Public values payload of {} bytes is too short for a chain head (version {})",
+            envelope.payload.len(),
+            envelope.version
+        );
+    }
+    let head = <ChainHead as SolType>::abi_decode(&envelope.payload[..head_size], true)
+        .map_err(|e| anyhow!("Invalid chain head (version {}): {}", envelope.version, e))?;
+    Ok((envelope.version, head))
+}
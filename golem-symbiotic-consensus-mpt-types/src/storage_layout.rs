@@ -0,0 +1,306 @@
+use alloy_primitives::{keccak256, Address, B256, U256};
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::types::SOURCE_CONTRACT_LOCKED_TOKENS_STORAGE_INDEX;
+
+/// The solidity type of a mapping key.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StorageKeyKind {
+    Address,
+    Uint256,
+    Bytes32,
+}
+
+/// A concrete key consumed by a `StoragePathStep` when deriving a storage location.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StorageKey {
+    Address(Address),
+    Uint256(U256),
+    Bytes32(B256),
+}
+
+impl StorageKey {
+    pub fn kind(&self) -> StorageKeyKind {
+        match self {
+            StorageKey::Address(_) => StorageKeyKind::Address,
+            StorageKey::Uint256(_) => StorageKeyKind::Uint256,
+            StorageKey::Bytes32(_) => StorageKeyKind::Bytes32,
+        }
+    }
+
+    /// The key left padded to 32 bytes, as it is laid out by `abi.encode`.
+    pub fn to_word(&self) -> B256 {
+        match self {
+            StorageKey::Address(address) => address.into_word(),
+            StorageKey::Uint256(value) => B256::from(value.to_be_bytes::<32>()),
+            StorageKey::Bytes32(value) => *value,
+        }
+    }
+}
+
+/// Where the root of a storage variable lives.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub enum StorageBase {
+    /// A slot assigned by the solidity compiler (declaration order).
+    Slot(U256),
+    /// An ERC-7201 namespaced storage root, `@custom:storage-location erc7201:<namespace>`.
+    Erc7201(String),
+}
+
+/// One step from a storage location towards the storage slot being proven.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub enum StoragePathStep {
+    /// `mapping(kind => ...)`, consumes one key of `kind`: `keccak256(pad32(key) ‖ slot)`.
+    Mapping(StorageKeyKind),
+    /// Element of a dynamic array `T[]` where each element spans `element_slots` slots, consumes one
+    /// `Uint256` key (the index): `keccak256(slot) + index * element_slots`.
+    ArrayElement { element_slots: u64 },
+    /// Member of a struct or a statically sized value: `slot + offset`.
+    Offset(u64),
+}
+
+/// Layout descriptor describing how a storage slot key is derived for a contract variable.
+///
+/// Examples:
+/// - `uint256 public totalLocked` (slot 0): `{ base: Slot(0), path: [] }`
+/// - `mapping(address => uint256) lockedTokens` (slot 1): `{ base: Slot(1), path: [Mapping(Address)] }`
+/// - `mapping(address => mapping(address => uint256)) allowance` (slot 2):
+///   `{ base: Slot(2), path: [Mapping(Address), Mapping(Address)] }`
+/// - `Deposit[] deposits` (slot 3, `Deposit` is 2 slots wide), amount at member 1:
+///   `{ base: Slot(3), path: [ArrayElement { element_slots: 2 }, Offset(1)] }`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct StorageLayout {
+    pub base: StorageBase,
+    pub path: Vec<StoragePathStep>,
+}
+
+impl StorageLayout {
+    /// `AppSource.lockedTokens`, a `mapping(address => uint256)` at index 1.
+    pub fn locked_tokens() -> Self {
+        StorageLayout {
+            base: StorageBase::Slot(U256::from(SOURCE_CONTRACT_LOCKED_TOKENS_STORAGE_INDEX)),
+            path: vec![StoragePathStep::Mapping(StorageKeyKind::Address)],
+        }
+    }
+
+    /// Resolves the root slot of the variable.
+    pub fn base_slot(&self) -> U256 {
+        match &self.base {
+            StorageBase::Slot(slot) => *slot,
+            StorageBase::Erc7201(namespace) => get_erc7201_base_slot(namespace),
+        }
+    }
+
+    /// Derives the storage slot key from the layout and the ordered `keys` consumed by its path.
+    ///
+    /// Fails if the number of keys or their kinds do not match the path.
+    pub fn storage_location(&self, keys: &[StorageKey]) -> Result<B256> {
+        let mut slot = self.base_slot();
+        let mut keys_iter = keys.iter();
+
+        for step in self.path.iter() {
+            slot = match step {
+                StoragePathStep::Mapping(kind) => {
+                    let Some(key) = keys_iter.next() else {
+                        bail!("Missing {:?} key for mapping step", kind);
+                    };
+                    if key.kind() != *kind {
+                        bail!("Expected {:?} mapping key, got {:?}", kind, key.kind());
+                    }
+                    let mut encoded = [0u8; 64];
+                    encoded[0..32].copy_from_slice(key.to_word().as_slice());
+                    encoded[32..64].copy_from_slice(&slot.to_be_bytes::<32>());
+                    U256::from_be_bytes(keccak256(encoded).0)
+                }
+                StoragePathStep::ArrayElement { element_slots } => {
+                    let index = match keys_iter.next() {
+                        Some(StorageKey::Uint256(index)) => *index,
+                        Some(key) => bail!("Expected Uint256 array index, got {:?}", key.kind()),
+                        None => bail!("Missing Uint256 key for array element step"),
+                    };
+                    let data_slot = U256::from_be_bytes(keccak256(slot.to_be_bytes::<32>()).0);
+                    data_slot.wrapping_add(index.wrapping_mul(U256::from(*element_slots)))
+                }
+                StoragePathStep::Offset(offset) => slot.wrapping_add(U256::from(*offset)),
+            };
+        }
+
+        if keys_iter.next().is_some() {
+            bail!(
+                "Too many keys for storage layout, path consumes {} key(s) but {} were given",
+                self.n_keys(),
+                keys.len()
+            );
+        }
+
+        Ok(B256::from(slot.to_be_bytes::<32>()))
+    }
+
+    /// Number of keys consumed by the path.
+    pub fn n_keys(&self) -> usize {
+        self.path
+            .iter()
+            .filter(|step| !matches!(step, StoragePathStep::Offset(_)))
+            .count()
+    }
+
+    /// The address a verified slot is attributed to in the verified storage slots merkle tree:
+    /// the first `Address` key of the path, or the zero address if there is none.
+    pub fn leaf_address(keys: &[StorageKey]) -> Address {
+        keys.iter()
+            .find_map(|key| match key {
+                StorageKey::Address(address) => Some(*address),
+                _ => None,
+            })
+            .unwrap_or(Address::ZERO)
+    }
+
+    /// Commitment to the layout descriptor.
+    ///
+    /// `keccak256` over a tagged encoding:
+    /// - base: `0x00 ‖ slot` or `0x01 ‖ len(namespace) as u32 ‖ namespace`
+    /// - each step: `0x10 + kind` for mappings (`Address` = 0, `Uint256` = 1, `Bytes32` = 2),
+    ///   `0x20 ‖ element_slots as u64` for array elements, `0x30 ‖ offset as u64` for offsets
+    pub fn hash(&self) -> B256 {
+        let mut encoded = Vec::new();
+        match &self.base {
+            StorageBase::Slot(slot) => {
+                encoded.push(0x00);
+                encoded.extend_from_slice(&slot.to_be_bytes::<32>());
+            }
+            StorageBase::Erc7201(namespace) => {
+                encoded.push(0x01);
+                encoded.extend_from_slice(&(namespace.len() as u32).to_be_bytes());
+                encoded.extend_from_slice(namespace.as_bytes());
+            }
+        }
+        for step in self.path.iter() {
+            match step {
+                StoragePathStep::Mapping(kind) => encoded.push(
+                    0x10 + match kind {
+                        StorageKeyKind::Address => 0,
+                        StorageKeyKind::Uint256 => 1,
+                        StorageKeyKind::Bytes32 => 2,
+                    },
+                ),
+                StoragePathStep::ArrayElement { element_slots } => {
+                    encoded.push(0x20);
+                    encoded.extend_from_slice(&element_slots.to_be_bytes());
+                }
+                StoragePathStep::Offset(offset) => {
+                    encoded.push(0x30);
+                    encoded.extend_from_slice(&offset.to_be_bytes());
+                }
+            }
+        }
+        keccak256(encoded)
+    }
+}
+
+/// Returns the ERC-7201 namespaced storage root for `namespace`.
+///
+/// Formula: keccak256(abi.encode(uint256(keccak256(namespace)) - 1)) & ~bytes32(uint256(0xff))
+pub fn get_erc7201_base_slot(namespace: &str) -> U256 {
+    let namespace_hash = U256::from_be_bytes(keccak256(namespace.as_bytes()).0);
+    let location = keccak256(namespace_hash.wrapping_sub(U256::from(1)).to_be_bytes::<32>());
+    U256::from_be_bytes(location.0) & !U256::from(0xff)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::get_storage_location_for_key;
+    use alloy_primitives::{address, b256};
+
+    const OWNER: Address = address!("00000000000000000000000000000000000000aa");
+    const SPENDER: Address = address!("00000000000000000000000000000000000000bb");
+
+    fn mapping_slot(key: B256, slot: B256) -> B256 {
+        let mut encoded = [0u8; 64];
+        encoded[0..32].copy_from_slice(key.as_slice());
+        encoded[32..64].copy_from_slice(slot.as_slice());
+        keccak256(encoded)
+    }
+
+    #[test]
+    fn locked_tokens_matches_the_legacy_mapping_location() {
+        let slot = StorageLayout::locked_tokens()
+            .storage_location(&[StorageKey::Address(OWNER)])
+            .unwrap();
+        assert_eq!(
+            slot,
+            get_storage_location_for_key(OWNER, SOURCE_CONTRACT_LOCKED_TOKENS_STORAGE_INDEX)
+        );
+    }
+
+    #[test]
+    fn plain_slot_without_path() {
+        let layout = StorageLayout {
+            base: StorageBase::Slot(U256::from(7)),
+            path: vec![],
+        };
+        assert_eq!(
+            layout.storage_location(&[]).unwrap(),
+            B256::from(U256::from(7).to_be_bytes::<32>())
+        );
+    }
+
+    #[test]
+    fn nested_mapping_hashes_each_key_in_order() {
+        let layout = StorageLayout {
+            base: StorageBase::Slot(U256::from(2)),
+            path: vec![
+                StoragePathStep::Mapping(StorageKeyKind::Address),
+                StoragePathStep::Mapping(StorageKeyKind::Address),
+            ],
+        };
+        let outer = mapping_slot(OWNER.into_word(), B256::from(U256::from(2).to_be_bytes::<32>()));
+        let expected = mapping_slot(SPENDER.into_word(), outer);
+        assert_eq!(
+            layout
+                .storage_location(&[StorageKey::Address(OWNER), StorageKey::Address(SPENDER)])
+                .unwrap(),
+            expected
+        );
+    }
+
+    #[test]
+    fn array_element_member_offsets_from_the_data_slot() {
+        let layout = StorageLayout {
+            base: StorageBase::Slot(U256::from(3)),
+            path: vec![
+                StoragePathStep::ArrayElement { element_slots: 2 },
+                StoragePathStep::Offset(1),
+            ],
+        };
+        let data_slot = U256::from_be_bytes(keccak256(U256::from(3).to_be_bytes::<32>()).0);
+        let expected = data_slot + U256::from(5 * 2 + 1);
+        assert_eq!(
+            layout
+                .storage_location(&[StorageKey::Uint256(U256::from(5))])
+                .unwrap(),
+            B256::from(expected.to_be_bytes::<32>())
+        );
+    }
+
+    #[test]
+    fn erc7201_base_matches_the_eip_example() {
+        assert_eq!(
+            B256::from(get_erc7201_base_slot("example.main").to_be_bytes::<32>()),
+            b256!("183a6125c38840424c4a85fa12bab2ab606c4b6d0e7cc73c0c06ba5300eab500")
+        );
+    }
+
+    #[test]
+    fn rejects_keys_not_matching_the_path() {
+        let layout = StorageLayout::locked_tokens();
+        assert!(layout.storage_location(&[]).is_err());
+        assert!(layout
+            .storage_location(&[StorageKey::Uint256(U256::from(1))])
+            .is_err());
+        assert!(layout
+            .storage_location(&[StorageKey::Address(OWNER), StorageKey::Address(SPENDER)])
+            .is_err());
+    }
+}
@@ -3,37 +3,99 @@ use alloy_trie::TrieAccount;
 use anyhow::{bail, Context, Result};
 use helios_consensus_core::consensus_spec::ConsensusSpec;
 use helios_consensus_core::types::Forks;
-use helios_consensus_core::types::{FinalityUpdate, LightClientStore, Update};
+use helios_consensus_core::types::{
+    BeaconBlockHeader, Bootstrap, ExecutionPayloadHeader, FinalityUpdate, LightClientStore, Update,
+};
 use serde::{Deserialize, Serialize};
 
+use crate::public_values::{
+    decode_public_values, encode_public_values, AggregationPublicValues, BootstrapPublicValues,
+    ConsensusPublicValues, RotationPublicValues, TransitionPublicValues,
+    AGGREGATION_PUBLIC_VALUES_VERSION, BOOTSTRAP_PUBLIC_VALUES_VERSION,
+    CONSENSUS_PUBLIC_VALUES_VERSION, ROTATION_PUBLIC_VALUES_VERSION, TRANSITION_PUBLIC_VALUES_VERSION,
+};
+use crate::storage_layout::{StorageKey, StorageLayout};
+
 pub const SOURCE_CONTRACT_LOCKED_TOKENS_STORAGE_INDEX: u8 = 1u8;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct StorageSlot {
     pub key: B256, // raw 32 byte storage slot key e.g. for slot 0: 0x000...00
     pub slot_key_address: Address, // address associated with the slot key
-    pub expected_value: U256, // raw `keccak256(abi.encode(target, data));`
-    pub mpt_proof: Vec<Bytes>, // contract-specific MPT proof
+    pub layout_keys: Vec<StorageKey>, // keys consumed by the contract storage layout path to derive the slot key
+    pub expected_value: U256, // raw `keccak256(abi.encode(target, data));`, zero when the slot is absent (exclusion proof)
+    pub mpt_proof_path: Vec<u32>, // indices into the contract's `storage_proof_nodes`, from the storage root to the slot
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct ContractStorage {
     pub address: Address,
-    pub expected_value: TrieAccount,
+    pub expected_value: Option<TrieAccount>, // None when the account is absent from the state trie (exclusion proof)
     pub mpt_proof: Vec<Bytes>, // global MPT proof
+    pub layout: StorageLayout, // how the storage slot keys are derived
+    pub storage_proof_nodes: Vec<Bytes>, // deduplicated storage trie nodes shared by the storage slot proofs (multiproof)
     pub storage_slots: Vec<StorageSlot>,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ReceiptLog {
+    pub log_index: u32, // index of the log within its receipt
+    pub emitter: Address, // address of the contract which emitted the log
+    pub topics: Vec<B256>, // raw log topics, topics[0] being the event signature
+    pub data: Bytes, // raw abi encoded non-indexed log data
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ReceiptProof {
+    pub tx_index: u64, // index of the transaction within the block (the receipt trie key is rlp(tx_index))
+    pub receipt: Bytes, // EIP-2718 encoded receipt, exactly as stored in the receipts trie
+    pub mpt_proof: Vec<Bytes>, // receipts trie MPT proof
+    pub logs: Vec<ReceiptLog>, // logs of this receipt which should be proven
+}
+
+/// How a historical beacon block root is linked to the finalized beacon state.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum HistoricalBlockRootProof {
+    /// The block is within the last `SLOTS_PER_HISTORICAL_ROOT` slots of the finalized state:
+    /// SSZ branch from `state.block_roots[slot % SLOTS_PER_HISTORICAL_ROOT]` to the finalized state root.
+    BlockRoots { branch: Vec<B256> },
+    /// The block is in an older, completed period (Capella onwards):
+    /// SSZ branch from the block root to the period's `block_summary_root` (the `block_roots` vector root) and
+    /// from that `block_summary_root` within `state.historical_summaries` to the finalized state root.
+    HistoricalSummaries {
+        block_summary_root: B256,
+        block_roots_branch: Vec<B256>,
+        historical_summary_branch: Vec<B256>,
+    },
+}
+
+/// Proof of an older beacon block and its execution payload against the finalized beacon state, with the
+/// storage and receipt proofs which target that block.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct HistoricalBlockProof<S: ConsensusSpec> {
+    pub header: BeaconBlockHeader, // the historical beacon block header
+    pub block_root_proof: HistoricalBlockRootProof, // links the header's root to the finalized state
+    pub execution: ExecutionPayloadHeader<S>, // the historical block's execution payload header
+    pub execution_branch: Vec<B256>, // SSZ branch from the execution payload header to the header's body root
+    pub contract_storages: Vec<ContractStorage>, // proven against the historical execution state root
+    pub receipt_proofs: Vec<ReceiptProof>, // proven against the historical execution receipts root
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct ProofInputs<S: ConsensusSpec> {
     pub updates: Vec<Update<S>>,
-    pub finality_update: FinalityUpdate<S>,
+    pub finality_update: Option<FinalityUpdate<S>>, // None exactly when force_update is given
+    pub force_update: Option<Update<S>>, // applied instead of the finality update once finality stalled for a sync committee period
     pub expected_current_slot: u64,
     pub store: LightClientStore<S>,
     pub genesis_root: B256,
     pub forks: Forks,
     pub store_hash: B256,
-    pub contract_storage: ContractStorage,
+    pub min_sync_committee_participants: u64, // enforced on the finality update's sync aggregate and committed
+    pub contract_storages: Vec<ContractStorage>,
+    pub input_contract_storages: Option<Vec<ContractStorage>>, // same contracts and slots proven at the input block, for deltas
+    pub receipt_proofs: Vec<ReceiptProof>,
+    pub historical_blocks: Vec<HistoricalBlockProof<S>>, // older blocks linked to the finalized state
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -55,12 +117,38 @@ pub struct DualProofInputsWithWindow<S: ConsensusSpec> {
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct ConsensusProofInputs<S: ConsensusSpec> {
     pub updates: Vec<Update<S>>,
-    pub finality_update: FinalityUpdate<S>,
+    pub finality_update: Option<FinalityUpdate<S>>, // None exactly when force_update is given
+    pub force_update: Option<Update<S>>, // applied instead of the finality update once finality stalled for a sync committee period
     pub expected_current_slot: u64,
     pub store: LightClientStore<S>,
     pub genesis_root: B256,
     pub forks: Forks,
     pub store_hash: B256,
+    pub min_sync_committee_participants: u64,
+}
+
+/// Inputs of the sync committee rotation program: sync committee updates only, without a finality update, to keep
+/// the committee chain alive while finality is stalled.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RotationProofInputs<S: ConsensusSpec> {
+    pub updates: Vec<Update<S>>, // at least one
+    pub expected_current_slot: u64,
+    pub store: LightClientStore<S>,
+    pub genesis_root: B256,
+    pub forks: Forks,
+    pub store_hash: B256,
+}
+
+/// Inputs of the bootstrap program: a light client bootstrap for a weak subjectivity checkpoint, and the first sync
+/// committee update of its period which brings the store its next sync committee (as the cold start does).
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BootstrapProofInputs<S: ConsensusSpec> {
+    pub checkpoint: B256, // weak subjectivity checkpoint (beacon block root), committed for verifiers to pin
+    pub bootstrap: Bootstrap<S>,
+    pub first_update: Update<S>,
+    pub expected_current_slot: u64,
+    pub genesis_root: B256,
+    pub forks: Forks,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -72,141 +160,412 @@ pub struct ExecutionStateProof {
     pub gindex: String,
 }
 
-// TODO do we need the contract address here.
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct VerifiedContractStorageSlot {
+    pub contract_address: Address,
     pub slot_key_address: Address,
+    pub slot_key: B256, // derived storage slot key, committed in the slot leaf
     pub value: U256,
+    pub absent: bool, // slot (or its contract account) was proven absent, committed as an absent leaf
+}
+
+/// The verified storage slots of one contract, in the order they are committed to the
+/// verified contract storage slots root.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct VerifiedContractStorage {
+    pub contract_address: Address,
+    pub account_absent: bool, // account was proven absent from the state trie
+    pub storage_slots: Vec<VerifiedContractStorageSlot>,
+}
+
+impl From<&ContractStorage> for VerifiedContractStorage {
+    fn from(contract_storage: &ContractStorage) -> Self {
+        let account_absent = contract_storage.expected_value.is_none();
+        VerifiedContractStorage {
+            contract_address: contract_storage.address,
+            account_absent,
+            storage_slots: contract_storage
+                .storage_slots
+                .iter()
+                .map(|slot| VerifiedContractStorageSlot {
+                    contract_address: contract_storage.address,
+                    slot_key_address: slot.slot_key_address,
+                    slot_key: slot.key,
+                    value: slot.expected_value,
+                    absent: account_absent || slot.expected_value.is_zero(),
+                })
+                .collect(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct VerifiedReceiptLog {
+    pub tx_index: u64,
+    pub log_index: u32,
+    pub emitter: Address,
+    pub topics: Vec<B256>,
+    pub data: Bytes,
 }
 
+/// Outputs of the consensus MPT transition program, committed as `TransitionPublicValues`.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProofOutputs {
-    pub input_slot: u64,                            // [  0..  8] u64
-    pub input_store_hash: B256,                     // [  8.. 40] bytes32
-    pub output_slot: u64,                           // [ 40.. 48] u64
-    pub output_store_hash: B256,                    // [ 48.. 80] bytes32
-    pub execution_state_root: B256,                 // [ 80..112] bytes32
-    pub verified_contract_storage_slots_root: B256, // [112..144] bytes32
-    pub next_sync_committee_hash: B256,             // [144..176] bytes32
+    pub network_identity_digest: B256,
+    pub input_slot: u64,
+    pub input_store_hash: B256,
+    pub output_slot: u64,
+    pub output_store_hash: B256,
+    pub beacon_block_root: B256,
+    pub execution_block_hash: B256,
+    pub execution_block_number: u64,
+    pub execution_timestamp: u64,
+    pub execution_state_root: B256,
+    pub receipts_root: B256,
+    pub verified_contract_storage_slots_root: B256, // contracts by address, each contract's slots by derived slot key (not slot_key_address)
+    pub contract_storage_layouts_hash: B256,
+    pub verified_receipt_logs_root: B256,
+    pub verified_contract_storage_deltas_root: B256,
+    pub verified_historical_blocks_root: B256,
+    pub next_sync_committee_hash: B256,
+    pub sync_committee_participants: u64,
+    pub min_sync_committee_participants: u64,
+    pub forced_update: bool, // store advanced by a force update, without finality
+    pub program_version: u32,
 }
 
 impl ProofOutputs {
-    pub const SIZE: usize = 176;
+    /// Encodes the outputs as versioned `TransitionPublicValues`.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        encode_public_values(
+            TRANSITION_PUBLIC_VALUES_VERSION,
+            &TransitionPublicValues {
+                networkIdentityDigest: self.network_identity_digest,
+                inputSlot: self.input_slot,
+                inputStoreHash: self.input_store_hash,
+                outputSlot: self.output_slot,
+                outputStoreHash: self.output_store_hash,
+                beaconBlockRoot: self.beacon_block_root,
+                executionBlockHash: self.execution_block_hash,
+                executionBlockNumber: self.execution_block_number,
+                executionTimestamp: self.execution_timestamp,
+                executionStateRoot: self.execution_state_root,
+                receiptsRoot: self.receipts_root,
+                verifiedContractStorageSlotsRoot: self.verified_contract_storage_slots_root,
+                contractStorageLayoutsHash: self.contract_storage_layouts_hash,
+                verifiedReceiptLogsRoot: self.verified_receipt_logs_root,
+                verifiedContractStorageDeltasRoot: self.verified_contract_storage_deltas_root,
+                verifiedHistoricalBlocksRoot: self.verified_historical_blocks_root,
+                nextSyncCommitteeHash: self.next_sync_committee_hash,
+                syncCommitteeParticipants: self.sync_committee_participants,
+                minSyncCommitteeParticipants: self.min_sync_committee_participants,
+                forcedUpdate: self.forced_update,
+                programVersion: self.program_version,
+            },
+        )
+    }
 
-    pub fn to_bytes(&self) -> [u8; Self::SIZE] {
-        let mut buf = [0u8; Self::SIZE];
+    /// Refuses outputs proven against a network other than `expected_network_identity_digest`.
+    pub fn ensure_network_identity(&self, expected_network_identity_digest: &B256) -> Result<()> {
+        if self.network_identity_digest != *expected_network_identity_digest {
+            bail!(
+                "Proof is bound to network identity {:?}, expected {:?}",
+                self.network_identity_digest,
+                expected_network_identity_digest
+            );
+        }
+        Ok(())
+    }
 
-        buf[0..8].copy_from_slice(&self.input_slot.to_be_bytes());
-        buf[8..40].copy_from_slice(&self.input_store_hash.0);
-        buf[40..48].copy_from_slice(&self.output_slot.to_be_bytes());
-        buf[48..80].copy_from_slice(&self.output_store_hash.0);
-        buf[80..112].copy_from_slice(&self.execution_state_root.0);
-        buf[112..144].copy_from_slice(&self.verified_contract_storage_slots_root.0);
-        buf[144..176].copy_from_slice(&self.next_sync_committee_hash.0);
+    /// Decodes versioned `TransitionPublicValues`, rejecting unknown versions.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let public_values: TransitionPublicValues =
+            decode_public_values(bytes, TRANSITION_PUBLIC_VALUES_VERSION)
+                .context("Failed to decode ProofOutputs")?;
 
-        buf
+        Ok(Self {
+            network_identity_digest: public_values.networkIdentityDigest,
+            input_slot: public_values.inputSlot,
+            input_store_hash: public_values.inputStoreHash,
+            output_slot: public_values.outputSlot,
+            output_store_hash: public_values.outputStoreHash,
+            beacon_block_root: public_values.beaconBlockRoot,
+            execution_block_hash: public_values.executionBlockHash,
+            execution_block_number: public_values.executionBlockNumber,
+            execution_timestamp: public_values.executionTimestamp,
+            execution_state_root: public_values.executionStateRoot,
+            receipts_root: public_values.receiptsRoot,
+            verified_contract_storage_slots_root: public_values.verifiedContractStorageSlotsRoot,
+            contract_storage_layouts_hash: public_values.contractStorageLayoutsHash,
+            verified_receipt_logs_root: public_values.verifiedReceiptLogsRoot,
+            verified_contract_storage_deltas_root: public_values.verifiedContractStorageDeltasRoot,
+            verified_historical_blocks_root: public_values.verifiedHistoricalBlocksRoot,
+            next_sync_committee_hash: public_values.nextSyncCommitteeHash,
+            sync_committee_participants: public_values.syncCommitteeParticipants,
+            min_sync_committee_participants: public_values.minSyncCommitteeParticipants,
+            forced_update: public_values.forcedUpdate,
+            program_version: public_values.programVersion,
+        })
     }
+}
 
-    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
-        if bytes.len() != Self::SIZE {
-            bail!(
-                "Invalid input length for ProofOutputs: expected {} bytes, got {}",
-                Self::SIZE,
-                bytes.len()
-            );
+/// Inputs of the aggregation program: consecutive transition and sync committee rotation proofs, given by their public
+/// values, which are verified recursively against the verification key of the program that produced them.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AggregationProofInputs {
+    pub transition_vkey: [u32; 8], // verification key hash of the transition program
+    pub rotation_vkey: [u32; 8], // verification key hash of the sync committee rotation program
+    pub transition_public_values: Vec<AggregatedPublicValues>, // public values of each proof, in slot order
+    pub migration: Option<AggregationMigration>, // set to continue the store hash chain of a previous program version
+}
+
+/// Public values of a proof in the store hash chain of an aggregation, tagged with the program that produced them.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum AggregatedPublicValues {
+    Transition(Vec<u8>), // `ProofOutputs::to_bytes` of a transition
+    Rotation(Vec<u8>), // `RotationProofOutputs::to_bytes` of a sync committee rotation
+}
+
+impl AggregatedPublicValues {
+    /// The public values bytes, whichever program produced them.
+    pub fn bytes(&self) -> &[u8] {
+        match self {
+            AggregatedPublicValues::Transition(bytes) | AggregatedPublicValues::Rotation(bytes) => bytes,
         }
+    }
+}
 
-        let input_slot_bytes: [u8; 8] = bytes[0..8]
-            .try_into()
-            .context("Failed to parse input_slot bytes")?;
-        let input_slot = u64::from_be_bytes(input_slot_bytes);
+/// An aggregation proof of the previous program version, verified recursively by a migrating aggregation: the first
+/// transition must continue from its output store hash and slot.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AggregationMigration {
+    pub previous_aggregation_vkey: [u32; 8], // verification key hash of the previous aggregation program
+    pub previous_public_values: Vec<u8>, // public values of the previous aggregation proof, of any version
+}
 
-        let input_store_hash = B256::from_slice(&bytes[8..40]);
-        // Assuming B256::from_slice does not fail; if it can fail, handle accordingly
+/// Outputs of the aggregation program, committed as `AggregationPublicValues`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AggregationProofOutputs {
+    pub network_identity_digest: B256,
+    pub input_slot: u64,
+    pub input_store_hash: B256,
+    pub output_slot: u64,
+    pub output_store_hash: B256,
+    pub transition_vkey: B256,
+    pub rotation_vkey: B256,
+    pub execution_state_roots_hash: B256,
+    pub transition_outputs_hash: B256,
+    pub n_transitions: u64, // transitions and rotations
+    pub sync_committee_participants: u64, // lowest participation of the aggregated transitions
+    pub min_sync_committee_participants: u64,
+    pub forced_update: bool, // any of the aggregated transitions is forced
+    pub program_version: u32, // shared by every aggregated transition
+    pub previous_aggregation_vkey: B256, // zero unless migrating from a previous program version
+}
 
-        let output_slot_bytes: [u8; 8] = bytes[40..48]
-            .try_into()
-            .context("Failed to parse output_slot bytes")?;
-        let output_slot = u64::from_be_bytes(output_slot_bytes);
+impl AggregationProofOutputs {
+    /// Encodes the outputs as versioned `AggregationPublicValues`.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        encode_public_values(
+            AGGREGATION_PUBLIC_VALUES_VERSION,
+            &AggregationPublicValues {
+                networkIdentityDigest: self.network_identity_digest,
+                inputSlot: self.input_slot,
+                inputStoreHash: self.input_store_hash,
+                outputSlot: self.output_slot,
+                outputStoreHash: self.output_store_hash,
+                transitionVkey: self.transition_vkey,
+                rotationVkey: self.rotation_vkey,
+                executionStateRootsHash: self.execution_state_roots_hash,
+                transitionOutputsHash: self.transition_outputs_hash,
+                nTransitions: self.n_transitions,
+                syncCommitteeParticipants: self.sync_committee_participants,
+                minSyncCommitteeParticipants: self.min_sync_committee_participants,
+                forcedUpdate: self.forced_update,
+                programVersion: self.program_version,
+                previousAggregationVkey: self.previous_aggregation_vkey,
+            },
+        )
+    }
 
-        let output_store_hash = B256::from_slice(&bytes[48..80]);
-        let execution_state_root = B256::from_slice(&bytes[80..112]);
-        let verified_contract_storage_slots_root = B256::from_slice(&bytes[112..144]);
-        let next_sync_committee_hash = B256::from_slice(&bytes[144..176]);
+    /// Decodes versioned `AggregationPublicValues`, rejecting unknown versions.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let public_values: AggregationPublicValues =
+            decode_public_values(bytes, AGGREGATION_PUBLIC_VALUES_VERSION)
+                .context("Failed to decode AggregationProofOutputs")?;
 
         Ok(Self {
-            input_slot,
-            input_store_hash,
-            output_slot,
-            output_store_hash,
-            execution_state_root,
-            verified_contract_storage_slots_root,
-            next_sync_committee_hash,
+            network_identity_digest: public_values.networkIdentityDigest,
+            input_slot: public_values.inputSlot,
+            input_store_hash: public_values.inputStoreHash,
+            output_slot: public_values.outputSlot,
+            output_store_hash: public_values.outputStoreHash,
+            transition_vkey: public_values.transitionVkey,
+            rotation_vkey: public_values.rotationVkey,
+            execution_state_roots_hash: public_values.executionStateRootsHash,
+            transition_outputs_hash: public_values.transitionOutputsHash,
+            n_transitions: public_values.nTransitions,
+            sync_committee_participants: public_values.syncCommitteeParticipants,
+            min_sync_committee_participants: public_values.minSyncCommitteeParticipants,
+            forced_update: public_values.forcedUpdate,
+            program_version: public_values.programVersion,
+            previous_aggregation_vkey: public_values.previousAggregationVkey,
         })
     }
 }
 
+/// Outputs of the consensus only program, committed as `ConsensusPublicValues`.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ConsensusProofOutputs {
-    pub input_slot: u64,                // [  0..  8] u64
-    pub input_store_hash: B256,         // [  8.. 40] bytes32
-    pub output_slot: u64,               // [ 40.. 48] u64
-    pub output_store_hash: B256,        // [ 48.. 80] bytes32
-    pub execution_state_root: B256,     // [ 80..112] bytes32
-    pub next_sync_committee_hash: B256, // [112..144] bytes32
+    pub network_identity_digest: B256,
+    pub input_slot: u64,
+    pub input_store_hash: B256,
+    pub output_slot: u64,
+    pub output_store_hash: B256,
+    pub execution_state_root: B256,
+    pub next_sync_committee_hash: B256,
+    pub forced_update: bool, // store advanced by a force update, without finality
+    pub program_version: u32,
 }
 
 impl ConsensusProofOutputs {
-    pub const SIZE: usize = 144;
+    /// Encodes the outputs as versioned `ConsensusPublicValues`.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        encode_public_values(
+            CONSENSUS_PUBLIC_VALUES_VERSION,
+            &ConsensusPublicValues {
+                networkIdentityDigest: self.network_identity_digest,
+                inputSlot: self.input_slot,
+                inputStoreHash: self.input_store_hash,
+                outputSlot: self.output_slot,
+                outputStoreHash: self.output_store_hash,
+                executionStateRoot: self.execution_state_root,
+                nextSyncCommitteeHash: self.next_sync_committee_hash,
+                forcedUpdate: self.forced_update,
+                programVersion: self.program_version,
+            },
+        )
+    }
 
-    pub fn to_bytes(&self) -> [u8; Self::SIZE] {
-        let mut buf = [0u8; Self::SIZE];
+    /// Decodes versioned `ConsensusPublicValues`, rejecting unknown versions.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let public_values: ConsensusPublicValues =
+            decode_public_values(bytes, CONSENSUS_PUBLIC_VALUES_VERSION)
+                .context("Failed to decode ConsensusProofOutputs")?;
 
-        buf[0..8].copy_from_slice(&self.input_slot.to_be_bytes());
-        buf[8..40].copy_from_slice(&self.input_store_hash.0);
-        buf[40..48].copy_from_slice(&self.output_slot.to_be_bytes());
-        buf[48..80].copy_from_slice(&self.output_store_hash.0);
-        buf[80..112].copy_from_slice(&self.execution_state_root.0);
-        buf[112..144].copy_from_slice(&self.next_sync_committee_hash.0);
+        Ok(Self {
+            network_identity_digest: public_values.networkIdentityDigest,
+            input_slot: public_values.inputSlot,
+            input_store_hash: public_values.inputStoreHash,
+            output_slot: public_values.outputSlot,
+            output_store_hash: public_values.outputStoreHash,
+            execution_state_root: public_values.executionStateRoot,
+            next_sync_committee_hash: public_values.nextSyncCommitteeHash,
+            forced_update: public_values.forcedUpdate,
+            program_version: public_values.programVersion,
+        })
+    }
+}
+/// Outputs of the bootstrap program, committed as `BootstrapPublicValues`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BootstrapProofOutputs {
+    pub network_identity_digest: B256,
+    pub checkpoint: B256,
+    pub output_slot: u64, // finalized slot of the bootstrapped store, the `input_slot` of the first transition
+    pub output_store_hash: B256, // the `input_store_hash` of the first transition
+    pub current_sync_committee_hash: B256,
+    pub next_sync_committee_hash: B256,
+    pub program_version: u32,
+}
 
-        buf
+impl BootstrapProofOutputs {
+    /// Encodes the outputs as versioned `BootstrapPublicValues`.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        encode_public_values(
+            BOOTSTRAP_PUBLIC_VALUES_VERSION,
+            &BootstrapPublicValues {
+                networkIdentityDigest: self.network_identity_digest,
+                checkpoint: self.checkpoint,
+                outputSlot: self.output_slot,
+                outputStoreHash: self.output_store_hash,
+                currentSyncCommitteeHash: self.current_sync_committee_hash,
+                nextSyncCommitteeHash: self.next_sync_committee_hash,
+                programVersion: self.program_version,
+            },
+        )
     }
 
+    /// Decodes versioned `BootstrapPublicValues`, rejecting unknown versions.
     pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
-        if bytes.len() != Self::SIZE {
-            anyhow::bail!(
-                "Invalid input length for ConsensusProofOutputs: expected {} bytes, got {}",
-                Self::SIZE,
-                bytes.len()
-            );
-        }
+        let public_values: BootstrapPublicValues =
+            decode_public_values(bytes, BOOTSTRAP_PUBLIC_VALUES_VERSION)
+                .context("Failed to decode BootstrapProofOutputs")?;
 
-        let input_slot_bytes: [u8; 8] = bytes[0..8]
-            .try_into()
-            .context("Failed to parse input_slot bytes")?;
-        let input_slot = u64::from_be_bytes(input_slot_bytes);
+        Ok(Self {
+            network_identity_digest: public_values.networkIdentityDigest,
+            checkpoint: public_values.checkpoint,
+            output_slot: public_values.outputSlot,
+            output_store_hash: public_values.outputStoreHash,
+            current_sync_committee_hash: public_values.currentSyncCommitteeHash,
+            next_sync_committee_hash: public_values.nextSyncCommitteeHash,
+            program_version: public_values.programVersion,
+        })
+    }
+}
 
-        let input_store_hash = B256::from_slice(&bytes[8..40]);
+/// Outputs of the sync committee rotation program, committed as `RotationPublicValues`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RotationProofOutputs {
+    pub network_identity_digest: B256,
+    pub input_slot: u64,
+    pub input_store_hash: B256,
+    pub output_slot: u64, // finalized slot of the store, unchanged unless an update carried a newer finalized header
+    pub output_store_hash: B256,
+    pub current_sync_committee_hash: B256,
+    pub next_sync_committee_hash: B256, // zero when the store has no next sync committee
+    pub n_updates: u64,
+    pub program_version: u32,
+}
 
-        let output_slot_bytes: [u8; 8] = bytes[40..48]
-            .try_into()
-            .context("Failed to parse output_slot bytes")?;
-        let output_slot = u64::from_be_bytes(output_slot_bytes);
+impl RotationProofOutputs {
+    /// Encodes the outputs as versioned `RotationPublicValues`.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        encode_public_values(
+            ROTATION_PUBLIC_VALUES_VERSION,
+            &RotationPublicValues {
+                networkIdentityDigest: self.network_identity_digest,
+                inputSlot: self.input_slot,
+                inputStoreHash: self.input_store_hash,
+                outputSlot: self.output_slot,
+                outputStoreHash: self.output_store_hash,
+                currentSyncCommitteeHash: self.current_sync_committee_hash,
+                nextSyncCommitteeHash: self.next_sync_committee_hash,
+                nUpdates: self.n_updates,
+                programVersion: self.program_version,
+            },
+        )
+    }
 
-        let output_store_hash = B256::from_slice(&bytes[48..80]);
-        let execution_state_root = B256::from_slice(&bytes[80..112]);
-        let next_sync_committee_hash = B256::from_slice(&bytes[144..176]);
+    /// Decodes versioned `RotationPublicValues`, rejecting unknown versions.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let public_values: RotationPublicValues =
+            decode_public_values(bytes, ROTATION_PUBLIC_VALUES_VERSION)
+                .context("Failed to decode RotationProofOutputs")?;
 
         Ok(Self {
-            input_slot,
-            input_store_hash,
-            output_slot,
-            output_store_hash,
-            execution_state_root,
-            next_sync_committee_hash,
+            network_identity_digest: public_values.networkIdentityDigest,
+            input_slot: public_values.inputSlot,
+            input_store_hash: public_values.inputStoreHash,
+            output_slot: public_values.outputSlot,
+            output_store_hash: public_values.outputStoreHash,
+            current_sync_committee_hash: public_values.currentSyncCommitteeHash,
+            next_sync_committee_hash: public_values.nextSyncCommitteeHash,
+            n_updates: public_values.nUpdates,
+            program_version: public_values.programVersion,
         })
     }
 }
+
 /// Returns the storage slot for a given address in a mapping(address => value)
 /// at the specified mapping index (slot position in contract storage).
 /// 
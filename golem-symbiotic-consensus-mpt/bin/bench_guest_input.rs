@@ -0,0 +1,79 @@
+use anyhow::{anyhow, Context, Result};
+use golem_symbiotic_consensus_mpt_types::guest_input::encode_guest_inputs;
+use golem_symbiotic_consensus_mpt_types::types::ProofInputs;
+use helios_consensus_core::consensus_spec::MainnetConsensusSpec;
+use nori::api::sp1_prover::{DECODE_BENCH_ELF, ELF};
+use sp1_sdk::{ProverClient, SP1Stdin};
+use std::{env, fs};
+
+/// Executes programs (without proving) on recorded windows and reports the cycles of the `decode_inputs` span per
+/// window, for both input encodings of the same build:
+/// - `cbor`: the decode bench program decoding CBOR `ProofInputs`,
+/// - `guest`: the program decoding the sectioned guest input layout.
+///
+/// Usage: `bench_guest_input <proof_inputs.cbor>...`, each file a CBOR encoded mainnet `ProofInputs` of one window, as
+/// recorded by the prover when `PROOF_INPUTS_DUMP_DIR` is set. Both ELFs are loaded from `PROGRAM_ELF_ROOT`, as built
+/// by `rebuild-zk.sh`. No results are checked in, cycle counts depend on the windows measured (number of depositors,
+/// sync committee updates, historical blocks).
+fn main() -> Result<()> {
+    dotenv::dotenv().ok();
+
+    let paths: Vec<String> = env::args().skip(1).collect();
+    if paths.is_empty() {
+        return Err(anyhow!("Usage: bench_guest_input <proof_inputs.cbor>..."));
+    }
+
+    let decode_bench_elf = DECODE_BENCH_ELF.elf()?;
+    let elf = ELF.elf()?;
+    let prover_client = ProverClient::from_env();
+
+    println!("window\tinput\tbytes\tdecode_inputs cycles");
+    let mut total_saved: i128 = 0;
+    for path in paths.iter() {
+        let cbor_inputs =
+            fs::read(path).with_context(|| format!("Failed to read proof inputs '{}'", path))?;
+        let inputs: ProofInputs<MainnetConsensusSpec> = serde_cbor::from_slice(&cbor_inputs)
+            .with_context(|| format!("Invalid proof inputs '{}'", path))?;
+        let guest_inputs = encode_guest_inputs(&inputs)?;
+
+        let mut decode_cycles = [0u64; 2];
+        for (i, (name, program, encoded)) in [
+            ("cbor", decode_bench_elf, &cbor_inputs),
+            ("guest", elf, &guest_inputs),
+        ]
+        .iter()
+        .enumerate()
+        {
+            let mut stdin = SP1Stdin::new();
+            stdin.write_slice(encoded);
+            let (_, report) = prover_client
+                .execute(program, &stdin)
+                .run()
+                .map_err(|e| anyhow!("Failed to execute '{}' with {} inputs: {}", path, name, e))?;
+            decode_cycles[i] = *report.cycle_tracker.get("decode_inputs").ok_or_else(|| {
+                anyhow!(
+                    "The {} program reported no decode_inputs span for '{}'",
+                    name,
+                    path
+                )
+            })?;
+            println!(
+                "{}\t{}\t{}\t{}",
+                path,
+                name,
+                encoded.len(),
+                decode_cycles[i]
+            );
+        }
+
+        let saved = decode_cycles[0] as i128 - decode_cycles[1] as i128;
+        total_saved += saved;
+        println!("{}\tsaved\t\t{}", path, saved);
+    }
+    println!(
+        "Average decode_inputs cycles saved per window: {}",
+        total_saved / paths.len() as i128
+    );
+
+    Ok(())
+}
@@ -0,0 +1,34 @@
+use anyhow::{anyhow, Context, Result};
+use golem_symbiotic_consensus_mpt_types::types::ProofInputs;
+use helios_consensus_core::consensus_spec::MainnetConsensusSpec;
+use nori::api::sp1_prover::profile_finality_update;
+use std::{env, fs};
+
+/// Executes the program (without proving) on recorded windows and prints one JSON `CycleReport` per window, with the
+/// cycles spent in each phase (store hashing, sync committee updates, finality update, account and storage slot
+/// proofs, Merkle folds...).
+///
+/// Usage: `profile_window <proof_inputs.cbor>...`, each file a CBOR encoded mainnet `ProofInputs` of one window, as
+/// recorded by the prover when `PROOF_INPUTS_DUMP_DIR` is set.
+#[tokio::main]
+async fn main() -> Result<()> {
+    dotenv::dotenv().ok();
+
+    let paths: Vec<String> = env::args().skip(1).collect();
+    if paths.is_empty() {
+        return Err(anyhow!("Usage: profile_window <proof_inputs.cbor>..."));
+    }
+
+    for path in paths.iter() {
+        let encoded_inputs =
+            fs::read(path).with_context(|| format!("Failed to read proof inputs '{}'", path))?;
+        let inputs: ProofInputs<MainnetConsensusSpec> = serde_cbor::from_slice(&encoded_inputs)
+            .with_context(|| format!("Invalid proof inputs '{}'", path))?;
+        let input_head = inputs.store.finalized_header.beacon().slot;
+
+        let report = profile_finality_update(input_head, &inputs).await?;
+        println!("{}", serde_json::to_string(&report)?);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,350 @@
+use crate::api::finality_change_detector::FinalityChangeDetectorUpdate;
+use alloy_primitives::FixedBytes;
+use anyhow::{anyhow, bail, Context, Result};
+use golem_symbiotic_consensus_mpt_program::consensus::{
+    apply_force_update, track_best_valid_update,
+};
+use golem_symbiotic_consensus_mpt_types::types::{ProofInputs, ProofInputsWithWindow};
+use helios_consensus_core::{
+    apply_finality_update, apply_update, consensus_spec::ConsensusSpec, types::LightClientStore,
+};
+use log::warn;
+use nori_hash::sha256_hash::sha256_hash_helios_store;
+use serde::{Deserialize, Serialize};
+use std::{
+    env, fs,
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+/// Version of the bridge head checkpoint file format.
+pub const BRIDGE_HEAD_CHECKPOINT_VERSION: u16 = 1;
+const BRIDGE_HEAD_CHECKPOINT_PATH_ENV_VAR: &str = "BRIDGE_HEAD_CHECKPOINT_PATH";
+const DEFAULT_BRIDGE_HEAD_CHECKPOINT_PATH: &str = "bridge_head_checkpoint.cbor";
+
+/// A window whose transition proof was staged (in flight) when the checkpoint was written. Its proof inputs are kept
+/// so that the job can be re-staged after a restart.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct StagedWindow<S: ConsensusSpec> {
+    pub job_id: u64,
+    pub staged_at: i64, // unix timestamp (seconds)
+    pub inputs_with_window: ProofInputsWithWindow<S>,
+}
+
+/// Durable state of the bridge head, written atomically (CBOR) after every change so that a restart resumes the same
+/// store hash chain instead of cold starting a new one.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BridgeHeadCheckpoint<S: ConsensusSpec> {
+    pub version: u16,
+    pub network_identity_digest: FixedBytes<32>,
+    pub slot: u64, // last proven slot (the bridge head)
+    pub store_hash: FixedBytes<32>,
+    pub store: Option<LightClientStore<S>>, // store at `slot` hashing to `store_hash`, unknown after a cold start
+    pub staged_windows: Vec<StagedWindow<S>>, // in staging order
+    pub last_job_id: u64,
+}
+
+impl<S: ConsensusSpec> BridgeHeadCheckpoint<S> {
+    /// Checkpoint of a cold started bridge head, with no staged windows.
+    pub fn new(network_identity_digest: FixedBytes<32>, slot: u64, store_hash: FixedBytes<32>) -> Self {
+        Self {
+            version: BRIDGE_HEAD_CHECKPOINT_VERSION,
+            network_identity_digest,
+            slot,
+            store_hash,
+            store: None,
+            staged_windows: Vec::new(),
+            last_job_id: 0,
+        }
+    }
+
+    /// Path of the checkpoint file, from `BRIDGE_HEAD_CHECKPOINT_PATH` (defaults to `bridge_head_checkpoint.cbor`).
+    pub fn path() -> PathBuf {
+        PathBuf::from(
+            env::var(BRIDGE_HEAD_CHECKPOINT_PATH_ENV_VAR)
+                .unwrap_or_else(|_| DEFAULT_BRIDGE_HEAD_CHECKPOINT_PATH.to_string()),
+        )
+    }
+
+    /// Loads the checkpoint at `path`, `None` when there is none yet. Rejects unknown versions and a store which does
+    /// not hash to the checkpoint's store hash.
+    pub fn load(path: &Path) -> Result<Option<Self>> {
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let bytes = fs::read(path).with_context(|| format!("Failed to read checkpoint {:?}", path))?;
+        let checkpoint: Self = serde_cbor::from_slice(&bytes)
+            .with_context(|| format!("Failed to decode checkpoint {:?}", path))?;
+        if checkpoint.version != BRIDGE_HEAD_CHECKPOINT_VERSION {
+            bail!(
+                "Unsupported checkpoint version {}, expected {}",
+                checkpoint.version,
+                BRIDGE_HEAD_CHECKPOINT_VERSION
+            );
+        }
+        if let Some(store) = &checkpoint.store {
+            let store_hash = sha256_hash_helios_store(store)?;
+            if store_hash != checkpoint.store_hash {
+                bail!(
+                    "Checkpoint store hashes to {}, expected {}",
+                    store_hash,
+                    checkpoint.store_hash
+                );
+            }
+        }
+
+        Ok(Some(checkpoint))
+    }
+
+    /// Writes the checkpoint to `path` atomically: to a temporary file in the same directory, synced, then renamed
+    /// over `path`, so a crash leaves either the previous or the new checkpoint.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let bytes = serde_cbor::to_vec(self).context("Failed to encode checkpoint")?;
+
+        let tmp_path = path.with_extension("tmp");
+        {
+            let mut file = fs::File::create(&tmp_path)
+                .with_context(|| format!("Failed to create {:?}", tmp_path))?;
+            file.write_all(&bytes)?;
+            file.sync_all()?;
+        }
+        fs::rename(&tmp_path, path)
+            .with_context(|| format!("Failed to move {:?} to {:?}", tmp_path, path))?;
+
+        // Sync the directory so the rename itself is durable
+        let dir = match path.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir,
+            _ => Path::new("."),
+        };
+        fs::File::open(dir)?.sync_all()?;
+
+        Ok(())
+    }
+
+    /// Refuses a checkpoint written for a network other than `expected_network_identity_digest`.
+    pub fn ensure_network_identity(&self, expected_network_identity_digest: &FixedBytes<32>) -> Result<()> {
+        if self.network_identity_digest != *expected_network_identity_digest {
+            bail!(
+                "Checkpoint is bound to network identity {:?}, expected {:?}",
+                self.network_identity_digest,
+                expected_network_identity_digest
+            );
+        }
+        Ok(())
+    }
+
+    /// Records a staged window, with the job id it was staged as.
+    pub fn stage(&mut self, job_id: u64, inputs_with_window: ProofInputsWithWindow<S>) {
+        self.last_job_id = self.last_job_id.max(job_id);
+        self.staged_windows.push(StagedWindow {
+            job_id,
+            staged_at: chrono::Utc::now().timestamp(),
+            inputs_with_window,
+        });
+    }
+
+    /// Forgets a staged window, e.g. after its job failed.
+    pub fn unstage(&mut self, job_id: u64) -> Option<StagedWindow<S>> {
+        let index = self
+            .staged_windows
+            .iter()
+            .position(|window| window.job_id == job_id)?;
+        Some(self.staged_windows.remove(index))
+    }
+
+    /// Advances the head to the output of the proven window of `job_id`. The store at the new head is rebuilt by
+    /// replaying the window's updates on its input store, and kept only if it hashes to `output_store_hash`.
+    pub fn advance(&mut self, job_id: u64, output_slot: u64, output_store_hash: FixedBytes<32>) -> Result<()> {
+        let store = match self.unstage(job_id) {
+            Some(window) => {
+                replayed_output_store(&window.inputs_with_window.proof_inputs, output_store_hash)?
+            }
+            None => None,
+        };
+        if store.is_none() {
+            warn!(
+                "No store of job {} hashes to the proven output store hash {}, not keeping it.",
+                job_id, output_store_hash
+            );
+        }
+
+        self.slot = output_slot;
+        self.store_hash = output_store_hash;
+        self.store = store;
+
+        Ok(())
+    }
+
+    /// Keeps the staged windows which continue the head's store hash chain (each starting where the previous one is
+    /// expected to end) and returns them in order, to be re-staged. Other windows can no longer complete.
+    pub fn take_resumable_staged_windows(&mut self) -> Vec<StagedWindow<S>> {
+        let mut resumable: Vec<StagedWindow<S>> = Vec::new();
+        let (mut slot, mut store_hash) = (self.slot, self.store_hash);
+        for window in self.staged_windows.drain(..) {
+            let inputs_with_window = &window.inputs_with_window;
+            if inputs_with_window.input_slot != slot
+                || inputs_with_window.proof_inputs.store_hash != store_hash
+            {
+                warn!(
+                    "Dropping staged window of job {} from slot {}, it does not continue slot {}.",
+                    window.job_id, inputs_with_window.input_slot, slot
+                );
+                continue;
+            }
+            slot = inputs_with_window.expected_output_slot;
+            store_hash = inputs_with_window.expected_output_store_hash;
+            resumable.push(window);
+        }
+        self.staged_windows = resumable.clone();
+        resumable
+    }
+
+    /// Start of the window following the last staged window, for the finality change detector to prepare in parallel.
+    pub fn next_expected_output(&self) -> Option<FinalityChangeDetectorUpdate<S>> {
+        self.staged_windows
+            .last()
+            .map(|window| expected_output(&window.inputs_with_window))
+    }
+}
+
+/// Start of the window following a staged window: its expected output slot and store hash, with the store its proof
+/// commits to when it can be replayed.
+pub fn expected_output<S: ConsensusSpec>(
+    inputs_with_window: &ProofInputsWithWindow<S>,
+) -> FinalityChangeDetectorUpdate<S> {
+    let store = replayed_output_store(
+        &inputs_with_window.proof_inputs,
+        inputs_with_window.expected_output_store_hash,
+    )
+    .unwrap_or_else(|err| {
+        warn!("Failed to replay the window from slot {}: {:?}", inputs_with_window.input_slot, err);
+        None
+    });
+    FinalityChangeDetectorUpdate {
+        slot: inputs_with_window.expected_output_slot,
+        store_hash: inputs_with_window.expected_output_store_hash,
+        store,
+    }
+}
+
+/// Replays a window (see `output_store`), giving its output store only if it hashes to `output_store_hash`.
+fn replayed_output_store<S: ConsensusSpec>(
+    inputs: &ProofInputs<S>,
+    output_store_hash: FixedBytes<32>,
+) -> Result<Option<LightClientStore<S>>> {
+    let store = output_store(inputs)?;
+    if sha256_hash_helios_store(&store)? == output_store_hash {
+        Ok(Some(store))
+    } else {
+        Ok(None)
+    }
+}
+
+/// Replays the updates of a window onto its input store, giving the store its proof commits to.
+fn output_store<S: ConsensusSpec>(inputs: &ProofInputs<S>) -> Result<LightClientStore<S>> {
+    let mut store = inputs.store.clone();
+    let mut best_valid_update = None;
+    for update in inputs.updates.iter() {
+        let finalized_slot = store.finalized_header.beacon().slot;
+        apply_update(&mut store, update);
+        best_valid_update =
+            track_best_valid_update(best_valid_update, update, finalized_slot, &store);
+    }
+    match &inputs.force_update {
+        Some(force_update) => apply_force_update(
+            &mut store,
+            force_update,
+            best_valid_update,
+            inputs.expected_current_slot,
+            inputs.genesis_root,
+            &inputs.forks,
+        )
+        .map_err(|e| anyhow!("Failed to replay force update: {}", e))?,
+        None => match &inputs.finality_update {
+            Some(finality_update) => apply_finality_update(&mut store, finality_update),
+            None => bail!("Window has neither a finality update nor a force update"),
+        },
+    }
+    Ok(store)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use helios_consensus_core::consensus_spec::MainnetConsensusSpec;
+
+    fn checkpoint_path(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!(
+            "bridge_head_checkpoint_{}_{}",
+            name,
+            std::process::id()
+        ));
+        fs::create_dir_all(&dir).unwrap();
+        dir.join("checkpoint.cbor")
+    }
+
+    fn checkpoint() -> BridgeHeadCheckpoint<MainnetConsensusSpec> {
+        let mut checkpoint = BridgeHeadCheckpoint::new(
+            FixedBytes::repeat_byte(0x11),
+            8192,
+            FixedBytes::repeat_byte(0x22),
+        );
+        checkpoint.last_job_id = 3;
+        checkpoint
+    }
+
+    #[test]
+    fn save_then_load_round_trips() {
+        let path = checkpoint_path("round_trip");
+        checkpoint().save(&path).unwrap();
+
+        let loaded = BridgeHeadCheckpoint::<MainnetConsensusSpec>::load(&path).unwrap().unwrap();
+        assert_eq!(loaded.version, BRIDGE_HEAD_CHECKPOINT_VERSION);
+        assert_eq!(loaded.network_identity_digest, FixedBytes::repeat_byte(0x11));
+        assert_eq!(loaded.slot, 8192);
+        assert_eq!(loaded.store_hash, FixedBytes::repeat_byte(0x22));
+        assert!(loaded.store.is_none());
+        assert!(loaded.staged_windows.is_empty());
+        assert_eq!(loaded.last_job_id, 3);
+        assert!(!path.with_extension("tmp").exists());
+
+        // Saving again replaces the previous checkpoint
+        let mut advanced = loaded;
+        advanced.slot = 8224;
+        advanced.save(&path).unwrap();
+        let reloaded = BridgeHeadCheckpoint::<MainnetConsensusSpec>::load(&path).unwrap().unwrap();
+        assert_eq!(reloaded.slot, 8224);
+
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn load_without_checkpoint_is_none() {
+        let path = checkpoint_path("missing");
+        assert!(BridgeHeadCheckpoint::<MainnetConsensusSpec>::load(&path).unwrap().is_none());
+
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn load_rejects_unknown_versions_and_corrupt_files() {
+        let path = checkpoint_path("rejects");
+        let mut unknown_version = checkpoint();
+        unknown_version.version = BRIDGE_HEAD_CHECKPOINT_VERSION + 1;
+        unknown_version.save(&path).unwrap();
+        assert!(BridgeHeadCheckpoint::<MainnetConsensusSpec>::load(&path).is_err());
+
+        fs::write(&path, b"not a checkpoint").unwrap();
+        assert!(BridgeHeadCheckpoint::<MainnetConsensusSpec>::load(&path).is_err());
+
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn ensure_network_identity_refuses_other_networks() {
+        let checkpoint = checkpoint();
+        assert!(checkpoint.ensure_network_identity(&FixedBytes::repeat_byte(0x11)).is_ok());
+        assert!(checkpoint.ensure_network_identity(&FixedBytes::repeat_byte(0x33)).is_err());
+    }
+}
@@ -1,21 +1,25 @@
+use crate::api::checkpoint::{expected_output, BridgeHeadCheckpoint};
 use crate::api::finality_change_detector::FinalityChangeDetectorUpdate;
 use crate::api::sp1_prover::{finality_update_job, ProverJobOutput};
 use crate::{
     api::finality_change_detector::start_validated_consensus_finality_change_detector,
-    rpcs::consensus::ConsensusHttpProxy,
+    merkle::{StorageSlotClaim, VerifiedContractStorageTree},
+    rpcs::consensus::{get_source_network_identity_digest, ConsensusHttpProxy, SourceConsensusSpec},
 };
 use alloy_primitives::FixedBytes;
-use anyhow::{Error, Result};
+use anyhow::{anyhow, Error, Result};
+use golem_symbiotic_consensus_mpt_types::network::ConsensusSpecName;
 use golem_symbiotic_consensus_mpt_types::types::{
-    DualProofInputsWithWindow, ProofInputsWithWindow, ProofOutputs, VerifiedContractStorageSlot,
+    DualProofInputsWithWindow, ProofInputsWithWindow, ProofOutputs, VerifiedContractStorage, VerifiedContractStorageSlot,
 };
-use helios_consensus_core::consensus_spec::MainnetConsensusSpec;
+use helios_consensus_core::consensus_spec::{MainnetConsensusSpec, MinimalConsensusSpec};
 use helios_ethereum::rpc::http_rpc::HttpRpc;
-use log::{debug, error, info};
+use log::{debug, error, info, warn};
 use serde::{Deserialize, Serialize};
 use sp1_sdk::SP1ProofWithPublicValues;
 use std::collections::HashMap;
 use std::error::Error as StdError;
+use std::path::PathBuf;
 use std::{fmt, process};
 use tokio::sync::broadcast;
 use tokio::sync::mpsc;
@@ -34,11 +38,12 @@ pub struct ProofMessage {
     pub proof: SP1ProofWithPublicValues,
     pub execution_state_root: FixedBytes<32>,
     pub contract_storage_slots: Vec<VerifiedContractStorageSlot>,
+    pub storage_slot_claims: Vec<StorageSlotClaim>,
     pub elapsed_sec: f64,
 }
 
-struct ProverJob {
-    inputs_with_window: ProofInputsWithWindow<MainnetConsensusSpec>,
+struct ProverJob<S: ConsensusSpecName> {
+    inputs_with_window: ProofInputsWithWindow<S>,
     start_instant: Instant,
 }
 
@@ -72,29 +77,65 @@ impl StdError for ProverJobError {
     }
 }
 
-pub struct EventLoop {
+pub struct EventLoop<S: ConsensusSpecName> {
     /// Current finalized slot head
     current_slot: u64,
 
-    finality_output_rx: Option<mpsc::Receiver<DualProofInputsWithWindow<MainnetConsensusSpec>>>,
-    finality_advance_input_tx: Option<mpsc::Sender<FinalityChangeDetectorUpdate>>,
-    finality_stage_input_tx: Option<mpsc::Sender<FinalityChangeDetectorUpdate>>,
+    finality_output_rx: Option<mpsc::Receiver<DualProofInputsWithWindow<S>>>,
+    finality_advance_input_tx: Option<mpsc::Sender<FinalityChangeDetectorUpdate<S>>>,
+    finality_stage_input_tx: Option<mpsc::Sender<FinalityChangeDetectorUpdate<S>>>,
     job_id: u64,
-    prover_jobs: HashMap<u64, ProverJob>,
+    prover_jobs: HashMap<u64, ProverJob<S>>,
     job_rx: Option<mpsc::UnboundedReceiver<Result<ProverJobOutput, ProverJobError>>>,
     job_tx: mpsc::UnboundedSender<Result<ProverJobOutput, ProverJobError>>,
     /// FixedBytes representing the store hash
     store_hash: FixedBytes<32>,
+    /// Network identity digest proofs must be bound to (derived from SOURCE_CHAIN_ID)
+    network_identity_digest: FixedBytes<32>,
+    /// Durable bridge head state, saved to `checkpoint_path` after every change
+    checkpoint: BridgeHeadCheckpoint<S>,
+    checkpoint_path: PathBuf,
 }
 
-impl EventLoop {
-    pub async fn new() -> (EventLoop) {
-        // Setup polling client for finality change detection
-        let (current_slot, store_hash) =
-            ConsensusHttpProxy::<MainnetConsensusSpec, HttpRpc>::try_from_env()
-                .get_latest_finality_slot_and_store_hash()
-                .await
-                .unwrap();
+impl<S: ConsensusSpecName + Send + 'static> EventLoop<S> {
+    pub async fn new() -> (EventLoop<S>) {
+        // Derive the network identity proofs must be bound to
+        let network_identity_digest =
+            get_source_network_identity_digest::<S>().unwrap();
+        info!("Expecting network identity digest {}.", network_identity_digest);
+
+        // Resume the bridge head from the checkpoint file, or cold start from the latest finality checkpoint
+        let checkpoint_path = BridgeHeadCheckpoint::<S>::path();
+        let mut checkpoint = match BridgeHeadCheckpoint::<S>::load(&checkpoint_path).unwrap() {
+            Some(checkpoint) => {
+                checkpoint
+                    .ensure_network_identity(&network_identity_digest)
+                    .unwrap();
+                info!(
+                    "Resuming bridge head at slot {} with store hash {} from {:?}.",
+                    checkpoint.slot, checkpoint.store_hash, checkpoint_path
+                );
+                checkpoint
+            }
+            None => {
+                info!("No checkpoint at {:?}, cold starting.", checkpoint_path);
+                let (slot, store_hash) = ConsensusHttpProxy::<S, HttpRpc>::try_from_env()
+                    .get_latest_finality_slot_and_store_hash()
+                    .await
+                    .unwrap();
+                BridgeHeadCheckpoint::new(network_identity_digest, slot, store_hash)
+            }
+        };
+        let resumed_windows = checkpoint.take_resumable_staged_windows();
+        checkpoint.save(&checkpoint_path).unwrap();
+        let current_slot = checkpoint.slot;
+        let store_hash = checkpoint.store_hash;
+        if checkpoint.store.is_none() {
+            warn!(
+                "No store at slot {}, proof inputs will be prepared by bootstrapping from it.",
+                current_slot
+            );
+        }
 
         info!("Starting helios polling client.");
         let (
@@ -102,26 +143,51 @@ impl EventLoop {
             finality_output_rx,
             finality_advance_input_tx,
             finality_stage_input_tx,
-        ) = start_validated_consensus_finality_change_detector::<MainnetConsensusSpec, HttpRpc>(
+        ) = start_validated_consensus_finality_change_detector::<S, HttpRpc>(
             current_slot,
             store_hash,
-            None, // FIXME this needs to come from persistant state aka from the checkpoint file
+            checkpoint.store.clone(),
+            checkpoint.next_expected_output(),
         )
         .await;
 
         // Create job mpsc
         let (job_tx, job_rx) = mpsc::unbounded_channel();
 
-        EventLoop {
+        let mut event_loop = EventLoop {
             current_slot,
             finality_output_rx: Some(finality_output_rx),
             finality_advance_input_tx: Some(finality_advance_input_tx),
             finality_stage_input_tx: Some(finality_stage_input_tx),
-            job_id: 0,
+            job_id: checkpoint.last_job_id,
             prover_jobs: HashMap::new(),
             job_rx: Some(job_rx),
             job_tx,
             store_hash,
+            network_identity_digest,
+            checkpoint,
+            checkpoint_path,
+        };
+
+        // Re-stage the windows which were in flight when the checkpoint was written
+        for window in resumed_windows {
+            info!(
+                "Re-staging job {} from slot {} to slot {}.",
+                window.job_id,
+                window.inputs_with_window.input_slot,
+                window.inputs_with_window.expected_output_slot
+            );
+            event_loop.spawn_prover_job(window.job_id, window.inputs_with_window);
+        }
+
+        event_loop
+    }
+
+    // Save the checkpoint, exiting if it cannot be persisted (a stale checkpoint would fork the hash chain on restart)
+    fn save_checkpoint(&self) {
+        if let Err(err) = self.checkpoint.save(&self.checkpoint_path) {
+            error!("Failed to save checkpoint {:?}: {:?}", self.checkpoint_path, err);
+            process::exit(1);
         }
     }
 
@@ -154,6 +220,8 @@ impl EventLoop {
         let public_values_bytes = public_values.as_slice(); // Raw bytes
 
         let proof_outputs = ProofOutputs::from_bytes(public_values_bytes)?;
+        // Refuse proofs bound to another network
+        proof_outputs.ensure_network_identity(&self.network_identity_digest)?;
         let input_slot = proof_outputs.input_slot;
         let input_store_hash = proof_outputs.input_store_hash;
         let output_slot = proof_outputs.output_slot;
@@ -172,17 +240,48 @@ impl EventLoop {
         info!("-----------------------------------------------------------------------------------------");
         info!("-----------------------------------------------------------------------------------------");
 
-        // Build a vector of VerifiedContractStorageSlot
-        let contract_storage_slots: Vec<VerifiedContractStorageSlot> = inputs_with_window
+        // Build a vector of VerifiedContractStorage
+        let contract_storages: Vec<VerifiedContractStorage> = inputs_with_window
             .proof_inputs
-            .contract_storage
-            .storage_slots
+            .contract_storages
             .iter()
-            .map(|slot| VerifiedContractStorageSlot {
-                slot_key_address: slot.slot_key_address,
-                value: slot.expected_value,
-            })
+            .map(VerifiedContractStorage::from)
             .collect();
+        let contract_storage_slots: Vec<VerifiedContractStorageSlot> = contract_storages
+            .iter()
+            .flat_map(|contract_storage| contract_storage.storage_slots.clone())
+            .collect();
+
+        // Build the per slot inclusion claims against the verified contract storage slots root
+        let storage_slot_tree = VerifiedContractStorageTree::new(contract_storages)?;
+        if storage_slot_tree.root() != proof_outputs.verified_contract_storage_slots_root {
+            return Err(anyhow!(
+                "Rebuilt verified contract storage slots root {:?} does not match the proof output {:?}.",
+                storage_slot_tree.root(),
+                proof_outputs.verified_contract_storage_slots_root
+            ));
+        }
+        let storage_slot_claims = storage_slot_tree.claims()?;
+        debug!(
+            "Built {} storage slot claims for {} slots.",
+            storage_slot_claims.len(),
+            contract_storage_slots.len()
+        );
+
+        // Advance and persist the bridge head, only along its store hash chain
+        if input_slot != self.current_slot || input_store_hash != self.store_hash {
+            warn!(
+                "Job '{}' proved slot {} to {}, which does not continue the bridge head at slot {}, not advancing.",
+                job_id, input_slot, output_slot, self.current_slot
+            );
+            self.checkpoint.unstage(job_id);
+        } else {
+            self.checkpoint.advance(job_id, output_slot, output_store_hash)?;
+            self.current_slot = output_slot;
+            self.store_hash = output_store_hash;
+            info!("Bridge head advanced to slot {}.", output_slot);
+        }
+        self.save_checkpoint();
 
         Ok(())
     }
@@ -206,11 +305,15 @@ impl EventLoop {
         // Build job failure error message
         let message = format!("Job '{}' failed with error: {}", err.job_id, err);
         error!("{}", message);
+
+        // Forget the failed window
+        self.checkpoint.unstage(err.job_id);
+        self.save_checkpoint();
     }
 
     async fn stage_transition_proof(
         &mut self,
-        proof_inputs_with_window: ProofInputsWithWindow<MainnetConsensusSpec>,
+        proof_inputs_with_window: ProofInputsWithWindow<S>,
     ) {
         // Get job id
         self.job_id += 1;
@@ -222,6 +325,23 @@ impl EventLoop {
             job_id
         );
 
+        // Persist the staged window before proving it
+        let next_expected_output = expected_output(&proof_inputs_with_window);
+        self.checkpoint.stage(job_id, proof_inputs_with_window.clone());
+        self.save_checkpoint();
+
+        self.spawn_prover_job(job_id, proof_inputs_with_window);
+
+        // Here we should tell the finality_change_detector that we have a job inflight and its expected_output_slot
+        // So it can begin preparing proof inputs from this input slot as well..
+        // Borrow the transmitter
+        if let Some(finality_stage_input_tx) = &self.finality_stage_input_tx {
+            let _ = finality_stage_input_tx.send(next_expected_output).await;
+        }
+    }
+
+    // Spawn a prover job for a staged window
+    fn spawn_prover_job(&mut self, job_id: u64, proof_inputs_with_window: ProofInputsWithWindow<S>) {
         // Insert job details into map
         self.prover_jobs.insert(
             job_id,
@@ -235,16 +355,13 @@ impl EventLoop {
         let tx = self.job_tx.clone();
 
         // Clone job arguments
-        let current_slot = self.current_slot;
-        let store_hash = self.store_hash;
+        let input_slot = proof_inputs_with_window.input_slot;
         let inputs = proof_inputs_with_window.proof_inputs;
-        let expected_output_slot = proof_inputs_with_window.expected_output_slot;
-        let expected_output_store_hash = proof_inputs_with_window.expected_output_store_hash;
 
         // Spawn proof job in worker thread (check for blocking)
         tokio::spawn(async move {
             // Execute job
-            let proof_result = finality_update_job(job_id, current_slot, inputs).await;
+            let proof_result = finality_update_job::<S>(job_id, input_slot, inputs).await;
 
             // Send appropriate tx Ok or Err
             match proof_result {
@@ -257,18 +374,6 @@ impl EventLoop {
                 }
             }
         });
-
-        // Here we should tell the finality_change_detector that we have a job inflight and its expected_output_slot
-        // So it can begin preparing proof inputs from this input slot as well..
-        // Borrow the transmitter
-        if let Some(finality_stage_input_tx) = &self.finality_stage_input_tx {
-            let _ = finality_stage_input_tx
-                .send(FinalityChangeDetectorUpdate {
-                    slot: expected_output_slot,
-                    store_hash: expected_output_store_hash,
-                })
-                .await;
-        }
     }
 
     pub async fn run(mut self) {
@@ -292,8 +397,15 @@ impl EventLoop {
                                 result_data.proof(),
                             ).await;
                             if let Err(err) = handle_prover_success_result {
-                                error!("Error handlng prover success: {:?}", err);
-                                process::exit(1);
+                                // The proof cannot be accepted (e.g. it is bound to another network), drop its window
+                                // so it is not re-staged on every restart
+                                error!(
+                                    "Error handlng prover success, dropping job '{}': {:?}",
+                                    result_data.job_id(),
+                                    err
+                                );
+                                self.checkpoint.unstage(result_data.job_id());
+                                self.save_checkpoint();
                             }
                         }
                         Err(err) => {
@@ -306,3 +418,19 @@ impl EventLoop {
         }
     }
 }
+
+/// Runs the event loop with the consensus spec configured by `SOURCE_CONSENSUS_SPEC`, proving with the matching
+/// program ELF.
+pub async fn run_event_loop() -> Result<()> {
+    match SourceConsensusSpec::from_env()? {
+        SourceConsensusSpec::Mainnet => {
+            info!("Running with the mainnet consensus spec.");
+            EventLoop::<MainnetConsensusSpec>::new().await.run().await
+        }
+        SourceConsensusSpec::Minimal => {
+            info!("Running with the minimal consensus spec.");
+            EventLoop::<MinimalConsensusSpec>::new().await.run().await
+        }
+    }
+    Ok(())
+}
@@ -1,7 +1,8 @@
 use crate::rpcs::consensus::ConsensusHttpProxy;
 use alloy_primitives::FixedBytes;
-use helios_consensus_core::consensus_spec::{ConsensusSpec, MainnetConsensusSpec};
-use helios_ethereum::rpc::{http_rpc::HttpRpc, ConsensusRpc};
+use golem_symbiotic_consensus_mpt_types::network::ConsensusSpecName;
+use helios_consensus_core::{consensus_spec::ConsensusSpec, types::LightClientStore};
+use helios_ethereum::rpc::ConsensusRpc;
 use log::{debug, error, info};
 use golem_symbiotic_consensus_mpt_types::types::DualProofInputsWithWindow;
 use std::{process, time::Duration};
@@ -47,10 +48,14 @@ use tokio::{sync::mpsc, time::interval};
 ///   The consensus store hash at `slot`:
 ///   - `ProofStaging`: hash at the staged proof’s expected output slot.
 ///   - `BridgeAdvancement`: hash at the bridge head slot.
+/// - `store`  
+///   The consensus store at `slot` hashing to `store_hash`, when known. Proof inputs are prepared from it rather than
+///   by bootstrapping from `slot`, which is impossible from the non-checkpoint head of a forced update.
 #[derive(Clone)]
-pub struct FinalityChangeDetectorUpdate {
+pub struct FinalityChangeDetectorUpdate<S: ConsensusSpec> {
     pub slot: u64,
     pub store_hash: FixedBytes<32>,
+    pub store: Option<LightClientStore<S>>,
 }
 
 /// FinalityChangeDetector job input struct describing the window starts the detector worker must
@@ -75,12 +80,14 @@ pub struct FinalityChangeDetectorUpdate {
 /// ## Fields
 /// - `slot` – start slot of the **current window**.
 /// - `store_hash` – store hash at the **current window** slot.
-/// - `next_expected_output` – optional `FinalityChangeDetectorUpdate` containing the start slot
-///   and store hash for the **next window**, used in dual mode.
-pub struct FinalityChangeDetectorJobInput {
+/// - `store` – store at the **current window** slot, when known.
+/// - `next_expected_output` – optional `FinalityChangeDetectorUpdate` containing the start slot,
+///   store hash and store for the **next window**, used in dual mode.
+pub struct FinalityChangeDetectorJobInput<S: ConsensusSpec> {
     pub slot: u64,
     pub store_hash: FixedBytes<32>,
-    pub next_expected_output: Option<FinalityChangeDetectorUpdate>,
+    pub store: Option<LightClientStore<S>>,
+    pub next_expected_output: Option<FinalityChangeDetectorUpdate<S>>,
 }
 
 /// Spawns an asynchronous actor responsible for generating and validating consensus proof inputs
@@ -114,14 +121,14 @@ pub struct FinalityChangeDetectorJobInput {
 /// - `mpsc::Sender<FinalityChangeDetectorJobInput>`: channel to submit validation jobs.
 /// - `mpsc::Receiver<Result<DualProofInputsWithWindow<S>, anyhow::Error>>`: channel to receive validation results.
 pub fn validate_and_prepare_proof_inputs_actor<S, R>() -> (
-    mpsc::Sender<FinalityChangeDetectorJobInput>,
+    mpsc::Sender<FinalityChangeDetectorJobInput<S>>,
     mpsc::Receiver<Result<DualProofInputsWithWindow<S>, anyhow::Error>>,
 )
 where
-    S: ConsensusSpec + Send + 'static,
+    S: ConsensusSpecName + Send + 'static,
     R: ConsensusRpc<S> + std::fmt::Debug + Send + 'static,
 {
-    let (job_tx, mut job_rx) = mpsc::channel::<FinalityChangeDetectorJobInput>(1);
+    let (job_tx, mut job_rx) = mpsc::channel::<FinalityChangeDetectorJobInput<S>>(1);
     let (result_tx, result_rx) =
         mpsc::channel::<Result<DualProofInputsWithWindow<S>, anyhow::Error>>(1);
 
@@ -143,6 +150,7 @@ where
                 let current_res = consensus_http_proxy.prepare_consensus_mpt_proof_inputs(
                     job.slot,
                     job.store_hash,
+                    job.store,
                     true,
                 );
 
@@ -150,6 +158,7 @@ where
                 let next_res = consensus_http_proxy.prepare_consensus_mpt_proof_inputs(
                     next_expected_output.slot,
                     next_expected_output.store_hash,
+                    next_expected_output.store,
                     true,
                 );
 
@@ -185,7 +194,7 @@ where
                 }
             } else {
                 let current_res = ConsensusHttpProxy::<S, R>::try_from_env()
-                    .prepare_consensus_mpt_proof_inputs(job.slot, job.store_hash, true)
+                    .prepare_consensus_mpt_proof_inputs(job.slot, job.store_hash, job.store, true)
                     .await;
 
                 let current_res = match current_res {
@@ -229,18 +238,20 @@ where
 /// * `validation_job_tx` - Channel sender to submit validation jobs to the validation actor.
 /// * `slot` - The input slot corresponding to the start of the current window.
 /// * `store_hash` - The hash of the store at the input slot for the current window.
-/// * `next_expected_output` - Optional slot and store hash for computing proof inputs for the next window,
+/// * `store` - The store at the input slot for the current window, when known.
+/// * `next_expected_output` - Optional slot, store hash and store for computing proof inputs for the next window,
 ///   following a currently staged proof's window.
 /// * `in_flight` - Mutable reference indicating whether a validation job is currently outstanding in the validation actor.
 ///
 /// # Returns
 /// * `Ok(())` if the job was successfully submitted and marked in-flight.
 /// * `Err(())` if the job channel is closed and the actor cannot process further jobs.
-async fn try_start_validation_job(
-    validation_job_tx: &mpsc::Sender<FinalityChangeDetectorJobInput>,
+async fn try_start_validation_job<S: ConsensusSpec>(
+    validation_job_tx: &mpsc::Sender<FinalityChangeDetectorJobInput<S>>,
     slot: u64,
     store_hash: FixedBytes<32>,
-    next_expected_output: &Option<FinalityChangeDetectorUpdate>,
+    store: &Option<LightClientStore<S>>,
+    next_expected_output: &Option<FinalityChangeDetectorUpdate<S>>,
     in_flight: &mut bool,
 ) -> Result<(), ()> {
     // This may be unnecessary as start_validated_consensus_finality_change_detector sets next_expected_output
@@ -261,6 +272,7 @@ async fn try_start_validation_job(
     let job = FinalityChangeDetectorJobInput {
         slot,
         store_hash,
+        store: store.clone(),
         next_expected_output: filtered_next,
     };
 
@@ -299,8 +311,10 @@ async fn try_start_validation_job(
 /// Arguments:
 /// - `slot`: The initial input slot for the **current window**, from which finality change detection begins (anchored to the bridge's slot header).
 /// - `store_hash`: The hash of the store at the input slot for the **current window**.
+/// - `store`: The store at the input slot for the **current window**, when known (e.g. from the bridge head
+///   checkpoint). Without it the store is restored by bootstrapping from `slot`, which must then be a checkpoint slot.
 /// - `pipeline_inflight_next_expected_output`: Optional in-flight proof job representing the
-///   expected output slot and store hash of a staged proof currently being processed, i.e. the **next window's** input slot.  
+///   expected output slot, store hash and store of a staged proof currently being processed, i.e. the **next window's** input slot.  
 ///
 /// Purpose:
 ///   The detector computes the **current window’s** proof inputs from the bridge head’s finality slot
@@ -336,23 +350,24 @@ async fn try_start_validation_job(
 /// - Runs indefinitely until the process exits or a critical error occurs
 ///
 /// Type Parameters:
-/// - `S`: The consensus specification type implementing `ConsensusSpec`
+/// - `S`: The consensus specification type implementing `ConsensusSpecName`
 /// - `R`: The RPC interface type implementing `ConsensusRpc<S>` and `Debug`
 pub async fn start_validated_consensus_finality_change_detector<S, R>(
     mut slot: u64,
     mut store_hash: FixedBytes<32>,
+    mut store: Option<LightClientStore<S>>,
     // need an pipeline_inflight_output_slot to represent the end of the window slot of a proof that is currently being processed
     // by the pipeline if it exists such that we can compute windowed proof inputs from this as an input slot in case that the inflight
     // job succeeds
-    pipeline_inflight_next_expected_output: Option<FinalityChangeDetectorUpdate>,
+    pipeline_inflight_next_expected_output: Option<FinalityChangeDetectorUpdate<S>>,
 ) -> (
     u64,
     mpsc::Receiver<DualProofInputsWithWindow<S>>,
-    mpsc::Sender<FinalityChangeDetectorUpdate>,
-    mpsc::Sender<FinalityChangeDetectorUpdate>,
+    mpsc::Sender<FinalityChangeDetectorUpdate<S>>,
+    mpsc::Sender<FinalityChangeDetectorUpdate<S>>,
 )
 where
-    S: ConsensusSpec + Send + 'static,
+    S: ConsensusSpecName + Send + 'static,
     R: ConsensusRpc<S> + std::fmt::Debug + Send + 'static,
 {
     dotenv::dotenv().ok();
@@ -364,7 +379,7 @@ where
 
     info!("Fetching helios latest checkpoint.");
     let init_latest_beacon_slot =
-        ConsensusHttpProxy::<MainnetConsensusSpec, HttpRpc>::try_from_env()
+        ConsensusHttpProxy::<S, R>::try_from_env()
             .get_latest_finality_slot()
             .await
             .unwrap();
@@ -372,9 +387,9 @@ where
     // Channels for finality detector output and input updates
     let (finality_output_tx, finality_output_rx) = mpsc::channel(1);
     let (finality_advance_input_tx, mut finality_advance_input_rx) =
-        mpsc::channel::<FinalityChangeDetectorUpdate>(1);
+        mpsc::channel::<FinalityChangeDetectorUpdate<S>>(1);
     let (finality_stage_input_tx, mut finality_stage_input_rx) =
-        mpsc::channel::<FinalityChangeDetectorUpdate>(1);
+        mpsc::channel::<FinalityChangeDetectorUpdate<S>>(1);
 
     // Channels for validation actor (job requests and results)
     let (validation_job_tx, mut validation_result_rx) =
@@ -395,7 +410,7 @@ where
         let mut tick_interval = interval(Duration::from_secs_f64(polling_interval_sec));
         // Option for if we have a currently staged job in the pipeline, it represents the window start which is the
         // output slot of the currently staged job, it can be used to calculate input for if the staged job succeeds.
-        let mut next_expected_output: Option<FinalityChangeDetectorUpdate> =
+        let mut next_expected_output: Option<FinalityChangeDetectorUpdate<S>> =
             pipeline_inflight_next_expected_output;
 
         loop {
@@ -421,6 +436,7 @@ where
                     // Cache the update
                     slot = update.slot;
                     store_hash = update.store_hash;
+                    store = update.store;
                     // we should probably just override the slot here FIXME
                     // our last computed proof input was from a different input slot and thus is not really valid
                     // when the observer calls advance -> api advance gets called this is with the output slot of that proof
@@ -462,7 +478,7 @@ where
                                 // Drop this result
                                 stale = false;
                                 // Immediately start an new proof validation job validation_job_tx.send(job)
-                                if try_start_validation_job(&validation_job_tx, slot, store_hash, &next_expected_output, &mut in_flight).await.is_err() {
+                                if try_start_validation_job(&validation_job_tx, slot, store_hash, &store, &next_expected_output, &mut in_flight).await.is_err() {
                                     break;
                                 }
                                 continue;
@@ -523,7 +539,7 @@ where
 
                 // Tick event - try to start validation if none in-flight
                 _ = tick_interval.tick() => {
-                    if !in_flight && try_start_validation_job(&validation_job_tx, slot, store_hash, &next_expected_output, &mut in_flight).await.is_err() {
+                    if !in_flight && try_start_validation_job(&validation_job_tx, slot, store_hash, &store, &next_expected_output, &mut in_flight).await.is_err() {
                         break;
                     }
                 }
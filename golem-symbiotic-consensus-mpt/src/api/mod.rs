@@ -1,3 +1,4 @@
+pub mod checkpoint;
 pub mod finality_change_detector;
 pub mod event_loop;
 pub mod sp1_prover;
@@ -1,23 +1,161 @@
-use anyhow::Result;
-use helios_consensus_core::consensus_spec::MainnetConsensusSpec;
-use log::info;
-use golem_symbiotic_consensus_mpt_types::types::ProofInputs;
-use sp1_sdk::{ProverClient, SP1ProofWithPublicValues, SP1ProvingKey, SP1Stdin};
+use anyhow::{anyhow, Context, Result};
+use golem_symbiotic_consensus_mpt_aggregation_program::aggregation_program;
+use golem_symbiotic_consensus_mpt_program::consensus::{bootstrap_program, rotation_program};
+use log::{debug, info};
+use golem_symbiotic_consensus_mpt_types::guest_input::encode_guest_inputs;
+use golem_symbiotic_consensus_mpt_types::network::ConsensusSpecName;
+use golem_symbiotic_consensus_mpt_types::types::{
+    AggregatedPublicValues, AggregationMigration, AggregationProofInputs, AggregationProofOutputs,
+    BootstrapProofInputs, BootstrapProofOutputs, ProofInputs, RotationProofInputs, RotationProofOutputs,
+};
+use serde::{Deserialize, Serialize};
+use sp1_sdk::{
+    ExecutionReport, HashableKey, ProverClient, SP1Proof, SP1ProofWithPublicValues,
+    SP1ProvingKey, SP1Stdin, SP1VerifyingKey,
+};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
 use std::sync::OnceLock;
+use std::{env, fs};
 
-// Import nori sp1 helios program
-pub const ELF: &[u8] = include_bytes!("../../../golem-symbiotic-consensus-mpt-program-elf/golem-symbiotic-consensus-mpt-program");
+/// A program ELF built by `golem-symbiotic-consensus-mpt-program-builder` (see `rebuild-zk.sh`), loaded from disk on
+/// first use along with its proving key.
+pub struct ProgramElf {
+    dir: &'static str, // ELF directory, relative to the ELF root
+    name: &'static str,
+    elf: OnceLock<Vec<u8>>,
+    proving_key: OnceLock<SP1ProvingKey>,
+}
 
-// Cache the proving key globally (initialized once)
-static PROVING_KEY: OnceLock<SP1ProvingKey> = OnceLock::new();
+impl ProgramElf {
+    const fn new(dir: &'static str, name: &'static str) -> Self {
+        Self {
+            dir,
+            name,
+            elf: OnceLock::new(),
+            proving_key: OnceLock::new(),
+        }
+    }
 
-pub async fn get_proving_key() -> &'static SP1ProvingKey {
-    PROVING_KEY.get_or_init(|| {
-        // Initialize fresh client just for setup
-        let client = ProverClient::from_env();
-        let (pk, _) = client.setup(ELF);
-        pk
-    })
+    /// Path of the ELF, under `PROGRAM_ELF_ROOT`. It has no default, the build machine's workspace does not exist
+    /// where the binary is deployed.
+    pub fn path(&self) -> Result<PathBuf> {
+        let root = env::var(PROGRAM_ELF_ROOT_ENV_VAR).map_err(|_| {
+            anyhow!(
+                "{} is not set, set it to the directory holding {}/{} (as built by golem-symbiotic-consensus-mpt-program/rebuild-zk.sh)",
+                PROGRAM_ELF_ROOT_ENV_VAR,
+                self.dir,
+                self.name
+            )
+        })?;
+        Ok(PathBuf::from(root).join(self.dir).join(self.name))
+    }
+
+    /// The ELF bytes, an error when `PROGRAM_ELF_ROOT` is not set or the program has not been built.
+    pub fn elf(&self) -> Result<&[u8]> {
+        if let Some(elf) = self.elf.get() {
+            return Ok(elf);
+        }
+        let path = self.path()?;
+        let elf = fs::read(&path).with_context(|| {
+            format!(
+                "Missing program ELF {:?}, build it with golem-symbiotic-consensus-mpt-program/rebuild-zk.sh",
+                path
+            )
+        })?;
+        Ok(self.elf.get_or_init(|| elf))
+    }
+
+    pub fn proving_key(&self) -> Result<&SP1ProvingKey> {
+        let elf = self.elf()?;
+        Ok(self.proving_key.get_or_init(|| {
+            // Initialize fresh client just for setup
+            let client = ProverClient::from_env();
+            let (pk, _) = client.setup(elf);
+            pk
+        }))
+    }
+}
+
+/// Directory holding the program ELF directories, required to load any program.
+const PROGRAM_ELF_ROOT_ENV_VAR: &str = "PROGRAM_ELF_ROOT";
+const PROGRAM_ELF_DIR: &str = "golem-symbiotic-consensus-mpt-program-elf";
+const AGGREGATION_PROGRAM_ELF_DIR: &str = "golem-symbiotic-consensus-mpt-aggregation-program-elf";
+
+// nori sp1 helios program, for both consensus presets (minimal for local devnets)
+pub static ELF: ProgramElf = ProgramElf::new(PROGRAM_ELF_DIR, "golem-symbiotic-consensus-mpt-program");
+pub static MINIMAL_ELF: ProgramElf =
+    ProgramElf::new(PROGRAM_ELF_DIR, "golem-symbiotic-consensus-mpt-program-minimal");
+
+// Sync committee rotation program, for both consensus presets
+pub static ROTATION_ELF: ProgramElf =
+    ProgramElf::new(PROGRAM_ELF_DIR, "golem-symbiotic-consensus-mpt-rotation-program");
+pub static MINIMAL_ROTATION_ELF: ProgramElf =
+    ProgramElf::new(PROGRAM_ELF_DIR, "golem-symbiotic-consensus-mpt-rotation-program-minimal");
+
+// Bootstrap program, for both consensus presets
+pub static BOOTSTRAP_ELF: ProgramElf =
+    ProgramElf::new(PROGRAM_ELF_DIR, "golem-symbiotic-consensus-mpt-bootstrap-program");
+pub static MINIMAL_BOOTSTRAP_ELF: ProgramElf =
+    ProgramElf::new(PROGRAM_ELF_DIR, "golem-symbiotic-consensus-mpt-bootstrap-program-minimal");
+
+// CBOR decoding bench program, mainnet only (see bench_guest_input)
+pub static DECODE_BENCH_ELF: ProgramElf =
+    ProgramElf::new(PROGRAM_ELF_DIR, "golem-symbiotic-consensus-mpt-decode-bench-program");
+
+// Aggregation program
+pub static AGGREGATION_ELF: ProgramElf = ProgramElf::new(
+    AGGREGATION_PROGRAM_ELF_DIR,
+    "golem-symbiotic-consensus-mpt-aggregation-program",
+);
+
+/// The transition program ELF built for the consensus spec `S`.
+pub fn get_elf<S: ConsensusSpecName>() -> Result<&'static ProgramElf> {
+    match S::spec_name() {
+        "mainnet" => Ok(&ELF),
+        "minimal" => Ok(&MINIMAL_ELF),
+        spec_name => Err(anyhow!("No program ELF built for consensus spec '{}'.", spec_name)),
+    }
+}
+
+pub async fn get_proving_key<S: ConsensusSpecName>() -> Result<&'static SP1ProvingKey> {
+    get_elf::<S>()?.proving_key()
+}
+
+/// The sync committee rotation program ELF built for the consensus spec `S`.
+fn get_rotation_elf<S: ConsensusSpecName>() -> Result<&'static ProgramElf> {
+    match S::spec_name() {
+        "mainnet" => Ok(&ROTATION_ELF),
+        "minimal" => Ok(&MINIMAL_ROTATION_ELF),
+        spec_name => Err(anyhow!(
+            "No rotation program ELF built for consensus spec '{}'.",
+            spec_name
+        )),
+    }
+}
+
+pub async fn get_rotation_proving_key<S: ConsensusSpecName>() -> Result<&'static SP1ProvingKey> {
+    get_rotation_elf::<S>()?.proving_key()
+}
+
+/// The bootstrap program ELF built for the consensus spec `S`.
+fn get_bootstrap_elf<S: ConsensusSpecName>() -> Result<&'static ProgramElf> {
+    match S::spec_name() {
+        "mainnet" => Ok(&BOOTSTRAP_ELF),
+        "minimal" => Ok(&MINIMAL_BOOTSTRAP_ELF),
+        spec_name => Err(anyhow!(
+            "No bootstrap program ELF built for consensus spec '{}'.",
+            spec_name
+        )),
+    }
+}
+
+pub async fn get_bootstrap_proving_key<S: ConsensusSpecName>() -> Result<&'static SP1ProvingKey> {
+    get_bootstrap_elf::<S>()?.proving_key()
+}
+
+pub async fn get_aggregation_proving_key() -> Result<&'static SP1ProvingKey> {
+    AGGREGATION_ELF.proving_key()
 }
 
 // Struct for ProverJobOutput
@@ -41,34 +179,164 @@ impl ProverJobOutput {
     }
 }
 
+/// A compressed proof in the store hash chain of an aggregation, by the program that produced it (see
+/// `compressed_finality_update_job` and `compressed_rotation_job`).
+pub enum AggregatedProof {
+    Transition(SP1ProofWithPublicValues),
+    Rotation(SP1ProofWithPublicValues),
+}
+
+impl AggregatedProof {
+    fn proof(&self) -> &SP1ProofWithPublicValues {
+        match self {
+            AggregatedProof::Transition(proof) | AggregatedProof::Rotation(proof) => proof,
+        }
+    }
+
+    fn public_values(&self) -> AggregatedPublicValues {
+        match self {
+            AggregatedProof::Transition(proof) => {
+                AggregatedPublicValues::Transition(proof.public_values.to_vec())
+            }
+            AggregatedProof::Rotation(proof) => AggregatedPublicValues::Rotation(proof.public_values.to_vec()),
+        }
+    }
+}
+
+/// A compressed aggregation proof of the previous program version (see `compressed_aggregation_job`), which a
+/// migrating aggregation continues the store hash chain of.
+pub struct AggregationMigrationProof {
+    pub previous_aggregation_vk: SP1VerifyingKey,
+    pub proof: SP1ProofWithPublicValues,
+}
+
+// Struct for AggregationJobOutput
+pub struct AggregationJobOutput {
+    job_id: u64,
+    outputs: AggregationProofOutputs,
+    proof: SP1ProofWithPublicValues,
+}
+
+impl AggregationJobOutput {
+    pub fn outputs(&self) -> &AggregationProofOutputs {
+        &self.outputs
+    }
+
+    pub fn proof(&self) -> SP1ProofWithPublicValues {
+        self.proof.clone()
+    }
+
+    pub fn job_id(&self) -> u64 {
+        self.job_id
+    }
+}
+
+// Struct for BootstrapJobOutput
+pub struct BootstrapJobOutput {
+    job_id: u64,
+    outputs: BootstrapProofOutputs,
+    proof: SP1ProofWithPublicValues,
+}
+
+impl BootstrapJobOutput {
+    pub fn outputs(&self) -> &BootstrapProofOutputs {
+        &self.outputs
+    }
+
+    pub fn proof(&self) -> SP1ProofWithPublicValues {
+        self.proof.clone()
+    }
+
+    pub fn job_id(&self) -> u64 {
+        self.job_id
+    }
+}
+
+// Struct for RotationJobOutput
+pub struct RotationJobOutput {
+    job_id: u64,
+    outputs: RotationProofOutputs,
+    proof: SP1ProofWithPublicValues,
+}
+
+impl RotationJobOutput {
+    pub fn outputs(&self) -> &RotationProofOutputs {
+        &self.outputs
+    }
+
+    pub fn proof(&self) -> SP1ProofWithPublicValues {
+        self.proof.clone()
+    }
+
+    pub fn job_id(&self) -> u64 {
+        self.job_id
+    }
+}
+
 /// Generates a ZK proof for a finality update at the given slot
 ///
 /// # Arguments
 /// * `job_id` - The identifier for this job
 /// * `input_head` - Target slot number to prove from up until current finality head
 /// * `store_hash` - The previous hash of the helio client store state at the `input_head` slot
-pub async fn finality_update_job(
+pub async fn finality_update_job<S: ConsensusSpecName>(
     job_id: u64,
     input_head: u64,
-    inputs: ProofInputs<MainnetConsensusSpec>,
+    inputs: ProofInputs<S>,
     //store_hash: FixedBytes<32>,
     //finality_update: FinalityUpdate<MainnetConsensusSpec>,
 ) -> Result<ProverJobOutput> {
-    info!(
-        "Contract storage {:?}",
-        serde_json::to_string(&inputs.contract_storage)
+    prove_finality_update(job_id, input_head, inputs, false).await
+}
+
+/// Generates a compressed ZK proof for a finality update at the given slot, which can be recursively verified
+/// by `aggregation_job` instead of being verified on its own.
+///
+/// # Arguments
+/// * `job_id` - The identifier for this job
+/// * `input_head` - Target slot number to prove from up until current finality head
+pub async fn compressed_finality_update_job<S: ConsensusSpecName>(
+    job_id: u64,
+    input_head: u64,
+    inputs: ProofInputs<S>,
+) -> Result<ProverJobOutput> {
+    prove_finality_update(job_id, input_head, inputs, true).await
+}
+
+async fn prove_finality_update<S: ConsensusSpecName>(
+    job_id: u64,
+    input_head: u64,
+    inputs: ProofInputs<S>,
+    compressed: bool,
+) -> Result<ProverJobOutput> {
+    debug!(
+        "Proving {} contract storages with {} slots.",
+        inputs.contract_storages.len(),
+        inputs
+            .contract_storages
+            .iter()
+            .map(|contract_storage| contract_storage.storage_slots.len())
+            .sum::<usize>()
     );
 
     // Encode proof inputs
     info!("Encoding sp1 proof inputs.");
-    let encoded_proof_inputs = serde_cbor::to_vec(&inputs)?;
-    info!("Encoded sp1 proof inputs.");
+    let encoded_proof_inputs = encode_guest_inputs(&inputs)?;
+    info!("Encoded sp1 proof inputs ({} bytes).", encoded_proof_inputs.len());
+
+    // Record the window's inputs for bench_guest_input
+    if let Ok(dump_dir) = std::env::var("PROOF_INPUTS_DUMP_DIR") {
+        let path =
+            std::path::Path::new(&dump_dir).join(format!("proof_inputs_{}.cbor", input_head));
+        std::fs::write(&path, serde_cbor::to_vec(&inputs)?)?;
+        info!("Dumped sp1 proof inputs to {:?}.", path);
+    }
 
     /*let encoded_proof_inputs =
     prepare_zk_program_input(input_head, store_hash, finality_update).await?;*/
 
-    // Get proving key
-    let pk = get_proving_key().await;
+    // Get proving key of the program built for this consensus spec
+    let pk = get_proving_key::<S>().await?;
 
     let proof: SP1ProofWithPublicValues =
         tokio::task::spawn_blocking(move || -> Result<SP1ProofWithPublicValues> {
@@ -81,7 +349,11 @@ pub async fn finality_update_job(
 
             // Generate proof.
             info!("Running sp1 proof.");
-            let proof = prover_client.prove(pk, &stdin).plonk().run();
+            let proof = if compressed {
+                prover_client.prove(pk, &stdin).compressed().run()
+            } else {
+                prover_client.prove(pk, &stdin).plonk().run()
+            };
             info!("Finished sp1 proof.");
 
             proof
@@ -94,3 +366,329 @@ pub async fn finality_update_job(
         job_id,
     })
 }
+
+/// Cycles spent in one cycle-tracker span of the transition program, accumulated over its invocations.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PhaseCycles {
+    pub cycles: u64,
+    pub invocations: u64,
+}
+
+/// Per-phase cycle counts of an execute-only run of the transition program over one window.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CycleReport {
+    pub input_head: u64,
+    pub contract_storages: usize,
+    pub storage_slots: usize, // over all contracts, the depositor count
+    pub receipt_proofs: usize,
+    pub total_instructions: u64,
+    pub phases: BTreeMap<String, PhaseCycles>, // by span name, see golem_symbiotic_consensus_mpt_program::profiling
+}
+
+/// Executes (without proving) the transition program built for `S` over the window inputs and reports the cycles
+/// spent in each of its phases.
+///
+/// # Arguments
+/// * `input_head` - Target slot number to prove from up until current finality head
+/// * `inputs` - The window's proof inputs, as given to `finality_update_job`
+pub async fn profile_finality_update<S: ConsensusSpecName>(
+    input_head: u64,
+    inputs: &ProofInputs<S>,
+) -> Result<CycleReport> {
+    let encoded_proof_inputs = encode_guest_inputs(inputs)?;
+    let elf = get_elf::<S>()?.elf()?;
+
+    let report = tokio::task::spawn_blocking(move || -> Result<ExecutionReport> {
+        let mut stdin = SP1Stdin::new();
+        stdin.write_slice(&encoded_proof_inputs);
+        let prover_client = ProverClient::from_env();
+
+        info!("Executing sp1 program.");
+        let (_, report) = prover_client
+            .execute(elf, &stdin)
+            .run()
+            .map_err(|e| anyhow!("Failed to execute sp1 program: {}", e))?;
+        info!("Finished executing sp1 program.");
+
+        Ok(report)
+    })
+    .await??;
+
+    let phases = report
+        .cycle_tracker
+        .iter()
+        .map(|(name, cycles)| {
+            let invocations = report.invocation_tracker.get(name).copied().unwrap_or(1);
+            (
+                name.clone(),
+                PhaseCycles {
+                    cycles: *cycles,
+                    invocations,
+                },
+            )
+        })
+        .collect();
+
+    Ok(CycleReport {
+        input_head,
+        contract_storages: inputs.contract_storages.len(),
+        storage_slots: inputs
+            .contract_storages
+            .iter()
+            .map(|contract_storage| contract_storage.storage_slots.len())
+            .sum(),
+        receipt_proofs: inputs.receipt_proofs.len(),
+        total_instructions: report.total_instruction_count(),
+        phases,
+    })
+}
+
+/// Generates a ZK proof of the store bootstrapped from a weak subjectivity checkpoint (see `bootstrap_program`),
+/// proving the `input_store_hash` of the first transition of the proof chain.
+///
+/// # Arguments
+/// * `job_id` - The identifier for this job
+/// * `inputs` - The bootstrap and first update for the checkpoint, from `Client::prepare_bootstrap_proof_inputs`
+pub async fn bootstrap_job<S: ConsensusSpecName>(
+    job_id: u64,
+    inputs: BootstrapProofInputs<S>,
+) -> Result<BootstrapJobOutput> {
+    // Dry run the bootstrap to fail early on a bootstrap which does not verify against the checkpoint
+    info!("Checking bootstrap for checkpoint {}.", inputs.checkpoint);
+    let outputs = bootstrap_program(inputs.clone(), false)?;
+    info!(
+        "Bootstrapping store at slot {} with hash {}.",
+        outputs.output_slot, outputs.output_store_hash
+    );
+
+    // Encode bootstrap inputs
+    info!("Encoding sp1 bootstrap inputs.");
+    let encoded_inputs = serde_cbor::to_vec(&inputs)?;
+    info!("Encoded sp1 bootstrap inputs.");
+
+    // Get proving key of the bootstrap program built for this consensus spec
+    let pk = get_bootstrap_proving_key::<S>().await?;
+
+    let proof: SP1ProofWithPublicValues =
+        tokio::task::spawn_blocking(move || -> Result<SP1ProofWithPublicValues> {
+            // Setup prover client
+            info!("Setting up prover client");
+            let mut stdin = SP1Stdin::new();
+            stdin.write_slice(&encoded_inputs);
+            let prover_client = ProverClient::from_env();
+            info!("Prover client setup complete.");
+
+            // Generate proof.
+            info!("Running sp1 bootstrap proof.");
+            let proof = prover_client.prove(pk, &stdin).plonk().run();
+            info!("Finished sp1 bootstrap proof.");
+
+            proof
+        })
+        .await??; // Await the blocking task and propagate errors properly
+
+    Ok(BootstrapJobOutput {
+        job_id,
+        outputs,
+        proof,
+    })
+}
+
+/// Generates a ZK proof applying sync committee updates only (see `rotation_program`), keeping the sync committee
+/// chain alive while finality is stalled and no finality update can be proven.
+///
+/// # Arguments
+/// * `job_id` - The identifier for this job
+/// * `inputs` - The sync committee updates and the store they apply to, from `Client::prepare_rotation_proof_inputs`
+pub async fn rotation_job<S: ConsensusSpecName>(
+    job_id: u64,
+    inputs: RotationProofInputs<S>,
+) -> Result<RotationJobOutput> {
+    prove_rotation(job_id, inputs, false).await
+}
+
+/// Generates a compressed sync committee rotation proof, which can be recursively verified by `aggregation_job`
+/// between transition proofs instead of being verified on its own.
+///
+/// # Arguments
+/// * `job_id` - The identifier for this job
+/// * `inputs` - The sync committee updates and the store they apply to, from `Client::prepare_rotation_proof_inputs`
+pub async fn compressed_rotation_job<S: ConsensusSpecName>(
+    job_id: u64,
+    inputs: RotationProofInputs<S>,
+) -> Result<RotationJobOutput> {
+    prove_rotation(job_id, inputs, true).await
+}
+
+async fn prove_rotation<S: ConsensusSpecName>(
+    job_id: u64,
+    inputs: RotationProofInputs<S>,
+    compressed: bool,
+) -> Result<RotationJobOutput> {
+    // Dry run the rotation to fail early on updates that do not rotate the committees
+    info!("Checking {} sync committee updates for rotation.", inputs.updates.len());
+    let outputs = rotation_program(inputs.clone(), false)?;
+    info!(
+        "Rotating sync committees from slot {} to slot {}.",
+        outputs.input_slot, outputs.output_slot
+    );
+
+    // Encode rotation inputs
+    info!("Encoding sp1 rotation inputs.");
+    let encoded_inputs = serde_cbor::to_vec(&inputs)?;
+    info!("Encoded sp1 rotation inputs.");
+
+    // Get proving key of the rotation program built for this consensus spec
+    let pk = get_rotation_proving_key::<S>().await?;
+
+    let proof: SP1ProofWithPublicValues =
+        tokio::task::spawn_blocking(move || -> Result<SP1ProofWithPublicValues> {
+            // Setup prover client
+            info!("Setting up prover client");
+            let mut stdin = SP1Stdin::new();
+            stdin.write_slice(&encoded_inputs);
+            let prover_client = ProverClient::from_env();
+            info!("Prover client setup complete.");
+
+            // Generate proof.
+            info!("Running sp1 rotation proof.");
+            let proof = if compressed {
+                prover_client.prove(pk, &stdin).compressed().run()
+            } else {
+                prover_client.prove(pk, &stdin).plonk().run()
+            };
+            info!("Finished sp1 rotation proof.");
+
+            proof
+        })
+        .await??; // Await the blocking task and propagate errors properly
+
+    Ok(RotationJobOutput {
+        job_id,
+        outputs,
+        proof,
+    })
+}
+
+/// Generates a single ZK proof covering N consecutive transitions and sync committee rotations, by recursively
+/// verifying their compressed proofs (see `compressed_finality_update_job` and `compressed_rotation_job`) and
+/// checking that each `output_store_hash` equals the next `input_store_hash`.
+///
+/// # Arguments
+/// * `job_id` - The identifier for this job
+/// * `transition_proofs` - Compressed transition and rotation proofs, in slot order, proven with the programs built
+///   for `S`
+/// * `migration` - Aggregation proof of the previous program version the first transition continues from, after an
+///   upgrade
+pub async fn aggregation_job<S: ConsensusSpecName>(
+    job_id: u64,
+    transition_proofs: Vec<AggregatedProof>,
+    migration: Option<AggregationMigrationProof>,
+) -> Result<AggregationJobOutput> {
+    prove_aggregation::<S>(job_id, transition_proofs, migration, false).await
+}
+
+/// Generates a compressed aggregation proof, which the next program version can recursively verify to migrate the
+/// store hash chain (see `AggregationMigrationProof`).
+///
+/// # Arguments
+/// * `job_id` - The identifier for this job
+/// * `transition_proofs` - Compressed transition and rotation proofs, in slot order, proven with the programs built
+///   for `S`
+/// * `migration` - Aggregation proof of the previous program version the first transition continues from
+pub async fn compressed_aggregation_job<S: ConsensusSpecName>(
+    job_id: u64,
+    transition_proofs: Vec<AggregatedProof>,
+    migration: Option<AggregationMigrationProof>,
+) -> Result<AggregationJobOutput> {
+    prove_aggregation::<S>(job_id, transition_proofs, migration, true).await
+}
+
+async fn prove_aggregation<S: ConsensusSpecName>(
+    job_id: u64,
+    transition_proofs: Vec<AggregatedProof>,
+    migration: Option<AggregationMigrationProof>,
+    compressed: bool,
+) -> Result<AggregationJobOutput> {
+    // Get the transition and rotation program verification keys
+    let transition_vk = get_proving_key::<S>().await?.vk.clone();
+    let rotation_vk = get_rotation_proving_key::<S>().await?.vk.clone();
+
+    let inputs = AggregationProofInputs {
+        transition_vkey: transition_vk.hash_u32(),
+        rotation_vkey: rotation_vk.hash_u32(),
+        transition_public_values: transition_proofs
+            .iter()
+            .map(AggregatedProof::public_values)
+            .collect(),
+        migration: migration.as_ref().map(|migration| AggregationMigration {
+            previous_aggregation_vkey: migration.previous_aggregation_vk.hash_u32(),
+            previous_public_values: migration.proof.public_values.to_vec(),
+        }),
+    };
+
+    // Dry run the aggregation to fail early on a broken chain
+    info!("Checking {} transitions and rotations for aggregation.", transition_proofs.len());
+    let outputs = aggregation_program(inputs.clone())?;
+    info!(
+        "Aggregating transitions from slot {} to slot {}.",
+        outputs.input_slot, outputs.output_slot
+    );
+
+    // Encode aggregation inputs
+    info!("Encoding sp1 aggregation inputs.");
+    let encoded_inputs = serde_cbor::to_vec(&inputs)?;
+    info!("Encoded sp1 aggregation inputs.");
+
+    // Get proving key
+    let pk = get_aggregation_proving_key().await?;
+
+    let proof: SP1ProofWithPublicValues =
+        tokio::task::spawn_blocking(move || -> Result<SP1ProofWithPublicValues> {
+            // Setup prover client
+            info!("Setting up prover client");
+            let mut stdin = SP1Stdin::new();
+            stdin.write_slice(&encoded_inputs);
+            for (index, transition_proof) in transition_proofs.into_iter().enumerate() {
+                let vk = match &transition_proof {
+                    AggregatedProof::Transition(_) => &transition_vk,
+                    AggregatedProof::Rotation(_) => &rotation_vk,
+                };
+                let SP1Proof::Compressed(proof) = transition_proof.proof().proof.clone() else {
+                    return Err(anyhow!(
+                        "Proof {} is not a compressed proof and cannot be aggregated.",
+                        index
+                    ));
+                };
+                stdin.write_proof(*proof, vk.vk.clone());
+            }
+            if let Some(migration) = migration {
+                let SP1Proof::Compressed(proof) = migration.proof.proof else {
+                    return Err(anyhow!(
+                        "Previous aggregation proof is not a compressed proof and cannot be migrated from."
+                    ));
+                };
+                stdin.write_proof(*proof, migration.previous_aggregation_vk.vk);
+            }
+            let prover_client = ProverClient::from_env();
+            info!("Prover client setup complete.");
+
+            // Generate proof.
+            info!("Running sp1 aggregation proof.");
+            let proof = if compressed {
+                prover_client.prove(pk, &stdin).compressed().run()
+            } else {
+                prover_client.prove(pk, &stdin).plonk().run()
+            };
+            info!("Finished sp1 aggregation proof.");
+
+            proof
+        })
+        .await??; // Await the blocking task and propagate errors properly
+
+    Ok(AggregationJobOutput {
+        job_id,
+        outputs,
+        proof,
+    })
+}
@@ -1,2 +1,3 @@
 pub mod rpcs;
-pub mod api;
\ No newline at end of file
+pub mod api;
+pub mod merkle;
\ No newline at end of file
@@ -0,0 +1,435 @@
+use alloy_primitives::{Address, FixedBytes, B256, U256};
+use anyhow::{anyhow, bail, Result};
+use golem_symbiotic_consensus_mpt_program::mpt::{
+    fp_to_fixed_bytes, hash_contract_storage_root, hash_storage_slot, poseidon_hash,
+};
+use golem_symbiotic_consensus_mpt_types::types::VerifiedContractStorage;
+use mina_curves::pasta::Fp;
+use nori_hash::merkle_poseidon_fixed::{
+    compute_merkle_tree_depth_and_size, fold_merkle_left, get_merkle_zeros, MAX_TREE_DEPTH,
+};
+use o1_utils::FieldHelpers;
+use serde::{Deserialize, Serialize};
+
+/// A claim that a verified storage slot is committed to a proof's `verified_contract_storage_slots_root`.
+///
+/// The path has two levels, mirroring how the program commits slots:
+/// - `slot_siblings`: from the slot leaf (`hash_storage_slot`) to the contract's storage slots root
+/// - `contract_siblings`: from the contract leaf (`hash_contract_storage_root`) to the root-of-roots
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct StorageSlotClaim {
+    pub contract_address: Address,
+    pub account_absent: bool,
+    pub slot_key_address: Address,
+    pub slot_key: B256,
+    pub value: U256,
+    pub absent: bool, // zero valued slots are committed as absent leaves
+    pub slot_index: u64,
+    pub slot_siblings: Vec<FixedBytes<32>>,
+    pub contract_index: u64,
+    pub contract_siblings: Vec<FixedBytes<32>>,
+    pub root: FixedBytes<32>,
+}
+
+/// Off-chain rebuild of the padded Poseidon trees committed by the program as `verified_contract_storage_slots_root`.
+pub struct VerifiedContractStorageTree {
+    contract_storages: Vec<VerifiedContractStorage>,
+    slot_levels: Vec<Vec<Vec<Fp>>>, // per contract, the levels of its storage slots tree
+    contract_levels: Vec<Vec<Fp>>,  // the levels of the root-of-roots tree
+}
+
+/// Builds every level of the padded tree over `leaves`, from the leaves (level 0) up to the root.
+///
+/// The root is cross checked against `fold_merkle_left`, which is what the program uses.
+fn build_merkle_levels(leaves: Vec<Fp>) -> Result<Vec<Vec<Fp>>> {
+    let n_leaves = leaves.len();
+    let (depth, padded_size) = compute_merkle_tree_depth_and_size(n_leaves);
+    if depth > MAX_TREE_DEPTH {
+        bail!(
+            "Merkle tree depth {} (derived from {} leaves) exceeds the maximum allowed depth of {}",
+            depth,
+            n_leaves,
+            MAX_TREE_DEPTH
+        );
+    }
+
+    let zeros = get_merkle_zeros();
+    let mut level = leaves.clone();
+    level.resize(padded_size, zeros[0]);
+    let mut levels = vec![level];
+    for _ in 0..depth {
+        let next_level = levels
+            .last()
+            .unwrap()
+            .chunks(2)
+            .map(|pair| poseidon_hash(&[pair[0], pair[1]]))
+            .collect();
+        levels.push(next_level);
+    }
+
+    let mut merkle_nodes = Vec::with_capacity(padded_size);
+    merkle_nodes.extend(leaves);
+    let root = fold_merkle_left(&mut merkle_nodes, padded_size, depth, &zeros);
+    if levels[depth][0] != root {
+        bail!("Rebuilt merkle tree does not match the folded merkle root.");
+    }
+
+    Ok(levels)
+}
+
+/// Sibling of the node at `index` on each level below the root.
+fn merkle_siblings(levels: &[Vec<Fp>], mut index: usize) -> Vec<FixedBytes<32>> {
+    let mut siblings = Vec::with_capacity(levels.len() - 1);
+    for level in levels[..levels.len() - 1].iter() {
+        siblings.push(fp_to_fixed_bytes(&level[index ^ 1]));
+        index >>= 1;
+    }
+    siblings
+}
+
+/// Folds `leaf` up through `siblings`, taking the side of each node from the bits of `index`.
+fn fold_merkle_path(leaf: Fp, siblings: &[FixedBytes<32>], index: u64) -> Result<Fp> {
+    if siblings.len() < 64 && index >> siblings.len() != 0 {
+        bail!("Leaf index {} is out of range for a path of {} siblings", index, siblings.len());
+    }
+    let mut node = leaf;
+    for (level, sibling) in siblings.iter().enumerate() {
+        let sibling = Fp::from_bytes(sibling.as_slice())?;
+        node = if (index >> level) & 1 == 1 {
+            poseidon_hash(&[sibling, node])
+        } else {
+            poseidon_hash(&[node, sibling])
+        };
+    }
+    Ok(node)
+}
+
+impl VerifiedContractStorageTree {
+    /// Rebuilds the trees from the verified contract storages, in the order the program committed them.
+    /// The program only commits contracts strictly increasing by address, each with its slots strictly increasing by
+    /// derived slot key, so storages taken from a proof's inputs are already in that order. Do not sort slots by
+    /// `slot_key_address`: nested mappings repeat it, and the rebuilt root would not match.
+    pub fn new(contract_storages: Vec<VerifiedContractStorage>) -> Result<Self> {
+        let mut slot_levels = Vec::with_capacity(contract_storages.len());
+        let mut contract_leaves = Vec::with_capacity(contract_storages.len());
+
+        for contract_storage in contract_storages.iter() {
+            let slot_leaves = contract_storage
+                .storage_slots
+                .iter()
+                .map(|slot| hash_storage_slot(&slot.slot_key_address, &slot.slot_key, &slot.value))
+                .collect::<Result<Vec<Fp>>>()?;
+            let levels = build_merkle_levels(slot_leaves)?;
+
+            let storage_slots_root = levels.last().unwrap()[0];
+            contract_leaves.push(hash_contract_storage_root(
+                &contract_storage.contract_address,
+                &storage_slots_root,
+                contract_storage.account_absent,
+            )?);
+            slot_levels.push(levels);
+        }
+
+        let contract_levels = build_merkle_levels(contract_leaves)?;
+
+        Ok(VerifiedContractStorageTree {
+            contract_storages,
+            slot_levels,
+            contract_levels,
+        })
+    }
+
+    /// The rebuilt `verified_contract_storage_slots_root`.
+    pub fn root(&self) -> FixedBytes<32> {
+        fp_to_fixed_bytes(&self.contract_levels.last().unwrap()[0])
+    }
+
+    /// The inclusion claim of slot `slot_index` of contract `contract_index`.
+    pub fn claim(&self, contract_index: usize, slot_index: usize) -> Result<StorageSlotClaim> {
+        let contract_storage = self
+            .contract_storages
+            .get(contract_index)
+            .ok_or_else(|| anyhow!("Contract index {} is out of range", contract_index))?;
+        let slot = contract_storage
+            .storage_slots
+            .get(slot_index)
+            .ok_or_else(|| {
+                anyhow!(
+                    "Slot index {} is out of range for contract {:?}",
+                    slot_index,
+                    contract_storage.contract_address
+                )
+            })?;
+
+        Ok(StorageSlotClaim {
+            contract_address: contract_storage.contract_address,
+            account_absent: contract_storage.account_absent,
+            slot_key_address: slot.slot_key_address,
+            slot_key: slot.slot_key,
+            value: slot.value,
+            absent: slot.value.is_zero(),
+            slot_index: slot_index as u64,
+            slot_siblings: merkle_siblings(&self.slot_levels[contract_index], slot_index),
+            contract_index: contract_index as u64,
+            contract_siblings: merkle_siblings(&self.contract_levels, contract_index),
+            root: self.root(),
+        })
+    }
+
+    /// The inclusion claims of every verified slot.
+    pub fn claims(&self) -> Result<Vec<StorageSlotClaim>> {
+        let mut claims = Vec::new();
+        for (contract_index, contract_storage) in self.contract_storages.iter().enumerate() {
+            for slot_index in 0..contract_storage.storage_slots.len() {
+                claims.push(self.claim(contract_index, slot_index)?);
+            }
+        }
+        Ok(claims)
+    }
+}
+
+/// Verifies that `claim` is included under `root` (a proof's `verified_contract_storage_slots_root`).
+pub fn verify_storage_slot_claim(claim: &StorageSlotClaim, root: &FixedBytes<32>) -> Result<bool> {
+    let slot_leaf = hash_storage_slot(&claim.slot_key_address, &claim.slot_key, &claim.value)?;
+    let storage_slots_root = fold_merkle_path(slot_leaf, &claim.slot_siblings, claim.slot_index)?;
+
+    let contract_leaf = hash_contract_storage_root(
+        &claim.contract_address,
+        &storage_slots_root,
+        claim.account_absent,
+    )?;
+    let computed_root =
+        fold_merkle_path(contract_leaf, &claim.contract_siblings, claim.contract_index)?;
+
+    Ok(fp_to_fixed_bytes(&computed_root) == *root)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::{address, keccak256, Bytes};
+    use alloy_trie::{
+        proof::{ProofNodes, ProofRetainer},
+        HashBuilder, Nibbles, TrieAccount, KECCAK_EMPTY,
+    };
+    use golem_symbiotic_consensus_mpt_program::mpt::{
+        verify_contracts_storage_slot_proofs, OUTPUT_BLOCK_SPANS,
+    };
+    use golem_symbiotic_consensus_mpt_types::{
+        storage_layout::{StorageKey, StorageLayout},
+        types::{ContractStorage, StorageSlot},
+    };
+
+    const CONTRACT_A: Address = address!("00000000000000000000000000000000000000a1");
+    const CONTRACT_B: Address = address!("00000000000000000000000000000000000000b2");
+    const CONTRACT_C: Address = address!("00000000000000000000000000000000000000c3");
+
+    // A trie over `leaves` (hashed key, rlp value) retaining the proof nodes of `targets`
+    fn trie(leaves: Vec<(Nibbles, Vec<u8>)>, targets: Vec<Nibbles>) -> (B256, ProofNodes) {
+        let mut leaves = leaves;
+        leaves.sort_by(|a, b| a.0.cmp(&b.0));
+        let mut hash_builder = HashBuilder::default().with_proof_retainer(ProofRetainer::new(targets));
+        for (key, value) in leaves.iter() {
+            hash_builder.add_leaf(key.clone(), value);
+        }
+        (hash_builder.root(), hash_builder.take_proof_nodes())
+    }
+
+    fn hashed(key: &[u8]) -> Nibbles {
+        Nibbles::unpack(keccak256(key))
+    }
+
+    // The `lockedTokens` slots of `(owner, value)`, in slot key order, proven against the contract's own storage trie
+    fn contract(address: Address, present: bool, balances: &[(u8, u64)]) -> (ContractStorage, Option<TrieAccount>) {
+        let layout = StorageLayout::locked_tokens();
+        let mut storage_slots: Vec<StorageSlot> = balances
+            .iter()
+            .map(|(owner, value)| {
+                let owner = Address::with_last_byte(*owner);
+                let layout_keys = vec![StorageKey::Address(owner)];
+                StorageSlot {
+                    key: layout.storage_location(&layout_keys).unwrap(),
+                    slot_key_address: owner,
+                    layout_keys,
+                    expected_value: if present { U256::from(*value) } else { U256::ZERO },
+                    mpt_proof_path: vec![],
+                }
+            })
+            .collect();
+        storage_slots.sort_by_key(|slot| slot.key);
+
+        let leaves = storage_slots
+            .iter()
+            .filter(|slot| !slot.expected_value.is_zero())
+            .map(|slot| (hashed(slot.key.as_slice()), alloy_rlp::encode(slot.expected_value)))
+            .collect();
+        let targets = storage_slots.iter().map(|slot| hashed(slot.key.as_slice())).collect();
+        let (storage_root, proof_nodes) = trie(leaves, targets);
+
+        // Pool the slot proofs as the host does, each node once
+        let mut storage_proof_nodes: Vec<Bytes> = Vec::new();
+        for slot in storage_slots.iter_mut() {
+            slot.mpt_proof_path = proof_nodes
+                .matching_nodes_sorted(&hashed(slot.key.as_slice()))
+                .into_iter()
+                .map(|(_, node)| match storage_proof_nodes.iter().position(|pooled| *pooled == node) {
+                    Some(index) => index as u32,
+                    None => {
+                        storage_proof_nodes.push(node);
+                        (storage_proof_nodes.len() - 1) as u32
+                    }
+                })
+                .collect();
+        }
+
+        let trie_account = present.then_some(TrieAccount {
+            nonce: 1,
+            balance: U256::ZERO,
+            storage_root,
+            code_hash: KECCAK_EMPTY,
+        });
+        let contract_storage = ContractStorage {
+            address,
+            expected_value: trie_account,
+            mpt_proof: vec![],
+            layout,
+            storage_proof_nodes,
+            storage_slots,
+        };
+        (contract_storage, trie_account)
+    }
+
+    // The contract storages of a window, with the state root they are proven against. The contract level and the
+    // slots of `CONTRACT_A` have an odd width, `CONTRACT_B` has a single slot and `CONTRACT_C` is absent.
+    fn fixture() -> (B256, Vec<ContractStorage>) {
+        let contracts = vec![
+            contract(CONTRACT_A, true, &[(1, 10), (2, 20), (3, 0)]),
+            contract(CONTRACT_B, true, &[(1, 30)]),
+            contract(CONTRACT_C, false, &[(1, 0), (2, 0)]),
+        ];
+
+        let leaves = contracts
+            .iter()
+            .filter_map(|(contract_storage, trie_account)| {
+                trie_account.map(|trie_account| {
+                    (hashed(contract_storage.address.as_slice()), alloy_rlp::encode(trie_account))
+                })
+            })
+            .collect();
+        let targets = contracts
+            .iter()
+            .map(|(contract_storage, _)| hashed(contract_storage.address.as_slice()))
+            .collect();
+        let (state_root, proof_nodes) = trie(leaves, targets);
+
+        let contract_storages = contracts
+            .into_iter()
+            .map(|(mut contract_storage, _)| {
+                contract_storage.mpt_proof = proof_nodes
+                    .matching_nodes_sorted(&hashed(contract_storage.address.as_slice()))
+                    .into_iter()
+                    .map(|(_, node)| node)
+                    .collect();
+                contract_storage
+            })
+            .collect();
+        (state_root, contract_storages)
+    }
+
+    fn committed_root(state_root: B256, contract_storages: &[ContractStorage]) -> FixedBytes<32> {
+        verify_contracts_storage_slot_proofs(state_root, contract_storages, &OUTPUT_BLOCK_SPANS)
+            .unwrap()
+            .0
+    }
+
+    fn tree(contract_storages: &[ContractStorage]) -> VerifiedContractStorageTree {
+        VerifiedContractStorageTree::new(contract_storages.iter().map(VerifiedContractStorage::from).collect())
+            .unwrap()
+    }
+
+    #[test]
+    fn rebuilds_the_root_committed_by_the_program() {
+        let (state_root, contract_storages) = fixture();
+        assert_eq!(tree(&contract_storages).root(), committed_root(state_root, &contract_storages));
+    }
+
+    #[test]
+    fn every_claim_verifies_under_the_committed_root() {
+        let (state_root, contract_storages) = fixture();
+        let root = committed_root(state_root, &contract_storages);
+        let claims = tree(&contract_storages).claims().unwrap();
+        assert_eq!(claims.len(), 6);
+
+        for claim in claims.iter() {
+            assert_eq!(claim.root, root);
+            assert_eq!(claim.contract_siblings.len(), 2);
+            assert!(verify_storage_slot_claim(claim, &root).unwrap());
+
+            let mut tampered = claim.clone();
+            tampered.value += U256::from(1);
+            assert!(!verify_storage_slot_claim(&tampered, &root).unwrap());
+
+            let mut moved = claim.clone();
+            moved.contract_index ^= 1;
+            assert!(!verify_storage_slot_claim(&moved, &root).unwrap());
+        }
+
+        // Zero valued slots and the slots of the absent contract are absent leaves
+        assert_eq!(claims.iter().filter(|claim| claim.absent).count(), 3);
+        assert!(claims.iter().filter(|claim| claim.account_absent).all(|claim| claim.absent));
+    }
+
+    #[test]
+    fn odd_width_levels_are_padded_with_zero_leaves() {
+        let (_, contract_storages) = fixture();
+        let tree = tree(&contract_storages);
+        let zero = fp_to_fixed_bytes(&get_merkle_zeros()[0]);
+
+        // The last slot of `CONTRACT_A` and the last contract are paired with the padding
+        let last_slot = tree.claim(0, 2).unwrap();
+        assert_eq!(last_slot.slot_siblings.len(), 2);
+        assert_eq!(last_slot.slot_siblings[0], zero);
+        let last_contract = tree.claim(2, 0).unwrap();
+        assert_eq!(last_contract.contract_siblings[0], zero);
+    }
+
+    #[test]
+    fn single_leaf_trees_have_no_siblings() {
+        let (_, contract_storages) = fixture();
+        let single = [contract_storages[1].clone()];
+        let tree = tree(&single);
+        let claim = tree.claim(0, 0).unwrap();
+        assert!(claim.slot_siblings.is_empty());
+        assert!(claim.contract_siblings.is_empty());
+
+        // The root is the contract leaf over the slot leaf
+        let slot = &single[0].storage_slots[0];
+        let slot_leaf = hash_storage_slot(&slot.slot_key_address, &slot.key, &slot.expected_value).unwrap();
+        let contract_leaf = hash_contract_storage_root(&CONTRACT_B, &slot_leaf, false).unwrap();
+        assert_eq!(tree.root(), fp_to_fixed_bytes(&contract_leaf));
+        assert!(verify_storage_slot_claim(&claim, &tree.root()).unwrap());
+    }
+
+    #[test]
+    fn contracts_without_slots_commit_an_empty_slots_root() {
+        let (state_root, contract_storages) = fixture();
+        let mut empty = contract_storages[1].clone();
+        empty.storage_proof_nodes.clear();
+        empty.storage_slots.clear();
+        let contract_storages = [empty];
+
+        let tree = tree(&contract_storages);
+        assert_eq!(tree.root(), committed_root(state_root, &contract_storages));
+        assert!(tree.claims().unwrap().is_empty());
+        assert!(tree.claim(0, 0).is_err());
+    }
+
+    #[test]
+    fn rejects_paths_too_short_for_their_index() {
+        let (_, contract_storages) = fixture();
+        let tree = tree(&contract_storages);
+        let mut claim = tree.claim(0, 1).unwrap();
+        claim.slot_index = 4;
+        assert!(verify_storage_slot_claim(&claim, &tree.root()).is_err());
+    }
+}
@@ -0,0 +1,317 @@
+use super::ssz::{
+    execution_payload_transactions, execution_payload_withdrawals, read_uint, BeaconBlockParts,
+    BeaconFork, BeaconPreset, BeaconStateRoots,
+};
+use alloy_primitives::{hex, B256, U256};
+use anyhow::{anyhow, bail, Error, Result};
+use golem_symbiotic_consensus_mpt_program::historical::verify_historical_block;
+use golem_symbiotic_consensus_mpt_types::network::ConsensusSpecName;
+use golem_symbiotic_consensus_mpt_types::types::{
+    ContractStorage, HistoricalBlockProof, HistoricalBlockRootProof, ReceiptProof,
+};
+use helios_consensus_core::{
+    consensus_spec::ConsensusSpec,
+    types::{BeaconBlockHeader, ExecutionPayloadHeader, Forks},
+};
+use log::debug;
+use reqwest::{header::ACCEPT, StatusCode, Url};
+use serde_json::json;
+use std::{collections::BTreeMap, sync::Arc};
+use tokio::time::Duration;
+use tree_hash::TreeHash;
+
+/// Timeout of a single SSZ download, mainnet beacon states are a few hundred megabytes.
+pub const BEACON_SSZ_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Fork of the beacon chain at `slot`, historical blocks can only be proven from Capella onwards.
+pub fn beacon_fork<S: ConsensusSpec>(slot: u64, forks: &Forks) -> Result<BeaconFork> {
+    let epoch = slot / S::slots_per_epoch();
+    if epoch >= forks.electra.epoch {
+        Ok(BeaconFork::Electra)
+    } else if epoch >= forks.deneb.epoch {
+        Ok(BeaconFork::Deneb)
+    } else if epoch >= forks.capella.epoch {
+        Ok(BeaconFork::Capella)
+    } else {
+        Err(anyhow!("Slot {} is before the Capella fork", slot))
+    }
+}
+
+/// Fetches an SSZ encoded beacon API resource, e.g. `eth/v2/beacon/blocks/{slot}`.
+async fn get_ssz(url: &Url, path: &str) -> Result<Vec<u8>> {
+    let url = format!("{}/{}", url.as_str().trim_end_matches('/'), path);
+    let response = reqwest::Client::new()
+        .get(&url)
+        .header(ACCEPT, "application/octet-stream")
+        .timeout(BEACON_SSZ_TIMEOUT)
+        .send()
+        .await
+        .map_err(|e| Error::msg(format!("Failed to fetch {}: {}", url, e)))?;
+    if response.status() == StatusCode::NOT_FOUND {
+        bail!("{} was not found (missed slot or pruned state)", url);
+    }
+    let bytes = response
+        .error_for_status()
+        .map_err(|e| Error::msg(format!("Failed to fetch {}: {}", url, e)))?
+        .bytes()
+        .await?;
+    debug!("Fetched {} bytes from {}", bytes.len(), url);
+    Ok(bytes.to_vec())
+}
+
+/// Rebuilds the execution payload header from the SSZ encoded fields of an execution payload, through the beacon API
+/// JSON encoding helios deserializes.
+fn execution_payload_header<S: ConsensusSpec>(
+    fields: &[Vec<u8>],
+    preset: &BeaconPreset,
+) -> Result<ExecutionPayloadHeader<S>> {
+    let bytes = |i: usize| hex::encode_prefixed(&fields[i]);
+    let uint = |i: usize| read_uint(&fields[i]).map(|value| value.to_string());
+
+    let mut header = json!({
+        "parent_hash": bytes(0),
+        "fee_recipient": bytes(1),
+        "state_root": bytes(2),
+        "receipts_root": bytes(3),
+        "logs_bloom": bytes(4),
+        "prev_randao": bytes(5),
+        "block_number": uint(6)?,
+        "gas_limit": uint(7)?,
+        "gas_used": uint(8)?,
+        "timestamp": uint(9)?,
+        "extra_data": bytes(10),
+        "base_fee_per_gas": U256::from_le_slice(&fields[11]).to_string(),
+        "block_hash": bytes(12),
+        "transactions_root": execution_payload_transactions().hash_tree_root(&fields[13])?,
+        "withdrawals_root": execution_payload_withdrawals(preset).hash_tree_root(&fields[14])?,
+    });
+    if fields.len() > 15 {
+        header["blob_gas_used"] = uint(15)?.into();
+        header["excess_blob_gas"] = uint(16)?.into();
+    }
+
+    serde_json::from_value(header)
+        .map_err(|e| Error::msg(format!("Failed to build execution payload header: {}", e)))
+}
+
+/// A block of the window to prove within the finalized beacon state, with the storage and receipt proofs which
+/// target it.
+#[derive(Debug, Clone)]
+pub struct HistoricalBlockTarget {
+    pub slot: u64,
+    pub contract_storages: Vec<ContractStorage>,
+    pub receipt_proofs: Vec<ReceiptProof>,
+}
+
+/// Period of the state closing the period of the block at `slot`, when the block is too old for the `block_roots` of
+/// the finalized state at `finalized_slot` and must be proven within the `historical_summaries`.
+pub fn summary_period<S: ConsensusSpec>(slot: u64, finalized_slot: u64) -> Option<u64> {
+    let slots_per_historical_root = S::slots_per_historical_root();
+    (finalized_slot > slot + slots_per_historical_root).then_some(slot / slots_per_historical_root)
+}
+
+/// Index of the historical summary of `period`, summaries starting at the Capella fork.
+fn summary_index<S: ConsensusSpec>(period: u64, forks: &Forks) -> Result<usize> {
+    let capella_period =
+        forks.capella.epoch * S::slots_per_epoch() / S::slots_per_historical_root();
+    period
+        .checked_sub(capella_period)
+        .map(|summary_index| summary_index as usize)
+        .ok_or_else(|| anyhow!("Period {} is before the first historical summary", period))
+}
+
+/// Fetches the SSZ encoded beacon state `state_id` at `state_slot` and merkleizes it.
+async fn fetch_beacon_state<S: ConsensusSpecName>(
+    url: &Url,
+    state_id: &str,
+    state_slot: u64,
+    forks: &Forks,
+) -> Result<BeaconStateRoots> {
+    let bytes = get_ssz(url, &format!("eth/v2/debug/beacon/states/{}", state_id)).await?;
+    let preset = BeaconPreset::from_spec_name(S::spec_name())?;
+    let fork = beacon_fork::<S>(state_slot, forks)?;
+    // Merkleizing beacon states is CPU heavy
+    tokio::task::spawn_blocking(move || BeaconStateRoots::from_ssz(&bytes, &preset, fork)).await?
+}
+
+/// Builds the proofs of the beacon blocks of `blocks` and their execution payloads against the finalized beacon state
+/// of `finalized_header`, each carrying the storage and receipt proofs which target its block.
+///
+/// - Blocks within the last `SLOTS_PER_HISTORICAL_ROOT` slots of the finalized state are proven within its
+///   `block_roots` (`HistoricalBlockRootProof::BlockRoots`).
+/// - Older blocks are proven within the `block_roots` of the state closing their period, whose root is proven
+///   within the finalized state's `historical_summaries` (`HistoricalBlockRootProof::HistoricalSummaries`). The
+///   provider must serve that state, which usually takes an archive node.
+///
+/// Beacon states are fetched SSZ encoded (`eth/v2/debug/beacon/states`) and merkleized locally, once for the
+/// finalized state and once per summary period whatever the number of blocks; the finalized state must hash to
+/// `finalized_header.state_root`. Each proof is verified as the program does before being returned, in the order of
+/// `blocks`.
+pub async fn prepare_historical_block_proofs<S: ConsensusSpecName>(
+    url: &Url,
+    finalized_header: &BeaconBlockHeader,
+    forks: &Forks,
+    blocks: Vec<HistoricalBlockTarget>,
+) -> Result<Vec<HistoricalBlockProof<S>>> {
+    if blocks.is_empty() {
+        return Ok(Vec::new());
+    }
+    let finalized_slot = finalized_header.slot;
+    if let Some(block) = blocks.iter().find(|block| block.slot >= finalized_slot) {
+        bail!(
+            "Historical block slot {} is not before the finalized slot {}",
+            block.slot,
+            finalized_slot
+        );
+    }
+    let slots_per_historical_root = S::slots_per_historical_root();
+
+    // Finalized state the blocks are proven against
+    let finalized_state = fetch_beacon_state::<S>(
+        url,
+        &finalized_header.state_root.to_string(),
+        finalized_slot,
+        forks,
+    )
+    .await?;
+    if finalized_state.state_root()? != finalized_header.state_root {
+        bail!(
+            "Fetched beacon state does not match the finalized state root {}",
+            finalized_header.state_root
+        );
+    }
+    let finalized_state = Arc::new(finalized_state);
+
+    // The states closing the periods of older blocks, their block_roots being the periods' block_summary_root
+    let mut summary_states = BTreeMap::new();
+    for period in blocks
+        .iter()
+        .filter_map(|block| summary_period::<S>(block.slot, finalized_slot))
+    {
+        if summary_states.contains_key(&period) {
+            continue;
+        }
+        let summary_state_slot = (period + 1) * slots_per_historical_root;
+        let summary_state = fetch_beacon_state::<S>(
+            url,
+            &summary_state_slot.to_string(),
+            summary_state_slot,
+            forks,
+        )
+        .await?;
+        let block_summary_root =
+            finalized_state.block_summary_root(summary_index::<S>(period, forks)?)?;
+        if summary_state.block_roots_root() != block_summary_root {
+            bail!(
+                "Fetched beacon state does not match the block summary root of period {}",
+                period
+            );
+        }
+        summary_states.insert(period, Arc::new(summary_state));
+    }
+
+    let mut historical_blocks = Vec::with_capacity(blocks.len());
+    for block in blocks {
+        let block_bytes = get_ssz(url, &format!("eth/v2/beacon/blocks/{}", block.slot)).await?;
+        let summary_state = summary_period::<S>(block.slot, finalized_slot)
+            .and_then(|period| summary_states.get(&period).cloned());
+        let finalized_header = finalized_header.clone();
+        let finalized_state = finalized_state.clone();
+        let forks = forks.clone();
+        historical_blocks.push(
+            tokio::task::spawn_blocking(move || {
+                build_historical_block_proof::<S>(
+                    block,
+                    &finalized_header,
+                    &forks,
+                    &block_bytes,
+                    &finalized_state,
+                    summary_state.as_deref(),
+                )
+            })
+            .await??,
+        );
+    }
+    Ok(historical_blocks)
+}
+
+/// Proves the block of `target` from its SSZ encoding against the merkleized finalized state, and the state closing
+/// its period when it is proven within the `historical_summaries`.
+fn build_historical_block_proof<S: ConsensusSpecName>(
+    target: HistoricalBlockTarget,
+    finalized_header: &BeaconBlockHeader,
+    forks: &Forks,
+    block_bytes: &[u8],
+    finalized_state: &BeaconStateRoots,
+    summary_state: Option<&BeaconStateRoots>,
+) -> Result<HistoricalBlockProof<S>> {
+    let slot = target.slot;
+    let preset = BeaconPreset::from_spec_name(S::spec_name())?;
+    let slots_per_historical_root = S::slots_per_historical_root();
+    let block_roots_index = (slot % slots_per_historical_root) as usize;
+
+    // Historical block and its execution payload
+    let block = BeaconBlockParts::from_signed_block_ssz(
+        block_bytes,
+        &preset,
+        beacon_fork::<S>(slot, forks)?,
+    )?;
+    if block.slot != slot {
+        bail!("Fetched the block at slot {} instead of slot {}", block.slot, slot);
+    }
+    let header = BeaconBlockHeader {
+        slot: block.slot,
+        proposer_index: block.proposer_index,
+        parent_root: block.parent_root,
+        state_root: block.state_root,
+        body_root: block.body_root,
+    };
+    let block_root = block.block_root()?;
+    if B256::from_slice(header.tree_hash_root().as_ref()) != block_root {
+        bail!("Block header root at slot {} does not match its SSZ root", slot);
+    }
+    let execution = execution_payload_header::<S>(&block.execution_payload_fields, &preset)?;
+    if B256::from_slice(execution.tree_hash_root().as_ref()) != block.execution_payload_root {
+        bail!(
+            "Execution payload header root at slot {} does not match the payload's SSZ root",
+            slot
+        );
+    }
+
+    let block_root_proof = match summary_state {
+        None => {
+            if finalized_state.block_roots()[block_roots_index] != block_root {
+                bail!("Block at slot {} is not an ancestor of the finalized block", slot);
+            }
+            HistoricalBlockRootProof::BlockRoots {
+                branch: finalized_state.block_roots_branch(slot)?,
+            }
+        }
+        Some(summary_state) => {
+            let summary_index = summary_index::<S>(slot / slots_per_historical_root, forks)?;
+            if summary_state.block_roots()[block_roots_index] != block_root {
+                bail!("Block at slot {} is not an ancestor of the finalized block", slot);
+            }
+            HistoricalBlockRootProof::HistoricalSummaries {
+                block_summary_root: finalized_state.block_summary_root(summary_index)?,
+                block_roots_branch: summary_state.block_roots_vector_branch(slot)?,
+                historical_summary_branch: finalized_state.historical_summary_branch(summary_index)?,
+            }
+        }
+    };
+
+    let historical_block = HistoricalBlockProof::<S> {
+        header,
+        block_root_proof,
+        execution,
+        execution_branch: block.execution_branch,
+        contract_storages: target.contract_storages,
+        receipt_proofs: target.receipt_proofs,
+    };
+
+    // Dry run the program's verification of this block
+    verify_historical_block::<S>(historical_block.clone(), finalized_header, forks)
+        .map_err(|e| Error::msg(format!("Historical block proof is invalid: {}", e)))?;
+
+    Ok(historical_block)
+}
@@ -1,59 +1,155 @@
-use super::{execution::http::ExecutionHttpProxy, multiplex, query_with_fallback};
+use super::{execution::http::ExecutionHttpProxy, multiplex, query_with_fallback, quorum};
 use alloy_primitives::{FixedBytes, B256};
 use anyhow::{anyhow, Error, Result};
 use futures::FutureExt;
 use helios_consensus_core::{
     apply_update, calc_sync_period,
     consensus_spec::ConsensusSpec,
-    types::{BeaconBlock, FinalityUpdate, Forks, LightClientHeader, LightClientStore, Update},
+    types::{
+        BeaconBlock, BeaconBlockHeader, FinalityUpdate, Forks, LightClientHeader, LightClientStore,
+        Update,
+    },
     verify_update,
 };
 use helios_ethereum::rpc::ConsensusRpc;
 use helios_ethereum::{
-    config::{checkpoints, networks::Network, Config},
+    config::Config,
     consensus::Inner,
 };
 use log::{debug, info, warn};
 use nori_hash::sha256_hash::sha256_hash_helios_store;
-use golem_symbiotic_consensus_mpt_types::types::{ConsensusProofInputs, ProofInputs, ProofInputsWithWindow};
-use golem_symbiotic_consensus_mpt_program::consensus::consensus_program;
+use golem_symbiotic_consensus_mpt_types::network::{sync_committee_supermajority, ConsensusSpecName};
+use golem_symbiotic_consensus_mpt_types::types::{
+    BootstrapProofInputs, ConsensusProofInputs, HistoricalBlockProof, ProofInputs, ProofInputsWithWindow,
+    RotationProofInputs,
+};
+use golem_symbiotic_consensus_mpt_program::consensus::{consensus_program, track_best_valid_update};
 use reqwest::Url;
-use std::{env, marker::PhantomData, sync::Arc};
+use std::{collections::BTreeSet, env, marker::PhantomData, sync::Arc};
 use tokio::sync::{mpsc::channel, watch};
 use tokio::time::Duration;
 use tree_hash::TreeHash;
 
+pub mod historical;
+pub mod network;
+pub mod ssz;
+use historical::{HistoricalBlockTarget, BEACON_SSZ_TIMEOUT};
+use network::NetworkDefinition;
+
 pub const MAX_REQUEST_LIGHT_CLIENT_UPDATES: u8 = 128;
 const CONSENSUS_RPCS_ENV_VAR: &str = "SOURCE_CONSENSUS_HTTP_RPCS";
 pub const CONSENSUS_PROVIDER_TIMEOUT: Duration = Duration::from_secs(20);
 
+/// Consensus preset of the source network, configured by `SOURCE_CONSENSUS_SPEC` (`mainnet` by default, `minimal` for
+/// local devnets). Selects the consensus spec the host runs with and the matching program ELF.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SourceConsensusSpec {
+    Mainnet,
+    Minimal,
+}
+
+impl SourceConsensusSpec {
+    pub fn from_env() -> Result<Self> {
+        dotenv::dotenv().ok();
+
+        match std::env::var("SOURCE_CONSENSUS_SPEC")
+            .unwrap_or_else(|_| "mainnet".to_string())
+            .to_lowercase()
+            .as_str()
+        {
+            "mainnet" => Ok(SourceConsensusSpec::Mainnet),
+            "minimal" => Ok(SourceConsensusSpec::Minimal),
+            other => Err(Error::msg(format!(
+                "Invalid SOURCE_CONSENSUS_SPEC '{}', expected 'mainnet' or 'minimal'",
+                other
+            ))),
+        }
+    }
+}
+
+/// Network identity digest expected of proofs for the configured source network (see `NetworkDefinition::from_env`).
+///
+/// Proofs commit `network_identity_digest` over the genesis root and fork schedule they were verified against, any
+/// proof with a different digest was produced for another network and must be refused.
+pub fn get_source_network_identity_digest<S: ConsensusSpecName>() -> Result<B256> {
+    Ok(NetworkDefinition::from_env()?.network_identity_digest::<S>())
+}
+
+/// Minimum number of sync committee members that must sign the finality update of a proof, from
+/// `MIN_SYNC_COMMITTEE_PARTICIPANTS` (defaults to a 2/3 supermajority of the sync committee).
+///
+/// The program enforces it and commits it with the actual participation, so destination contracts can apply their
+/// own policy on top.
+pub fn get_min_sync_committee_participants<S: ConsensusSpec>() -> Result<u64> {
+    match env::var("MIN_SYNC_COMMITTEE_PARTICIPANTS") {
+        Ok(min_participants) => {
+            let min_participants = min_participants.parse::<u64>().map_err(|e| {
+                Error::msg(format!("Invalid MIN_SYNC_COMMITTEE_PARTICIPANTS format: {}", e))
+            })?;
+            if min_participants > S::sync_committee_size() {
+                return Err(anyhow!(
+                    "MIN_SYNC_COMMITTEE_PARTICIPANTS {} exceeds the sync committee size {}",
+                    min_participants,
+                    S::sync_committee_size()
+                ));
+            }
+            Ok(min_participants)
+        }
+        Err(_) => Ok(sync_committee_supermajority::<S>()),
+    }
+}
+
+/// Whether proofs may fall back to a force update once finality has been stalled for a sync committee period, from
+/// `ALLOW_FORCE_UPDATES` (disabled by default).
+///
+/// Forced transitions advance the store to an attested, not finalized, header and are flagged in the public values.
+pub fn get_allow_force_updates() -> Result<bool> {
+    match env::var("ALLOW_FORCE_UPDATES") {
+        Ok(allow_force_updates) => allow_force_updates
+            .parse::<bool>()
+            .map_err(|e| Error::msg(format!("Invalid ALLOW_FORCE_UPDATES format: {}", e))),
+        Err(_) => Ok(false),
+    }
+}
+
+/// Number of consensus providers which must agree on the cold start checkpoint's block root before its store hash is
+/// accepted, from `CHECKPOINT_QUORUM` (defaults to a majority of the `n_providers` providers).
+pub fn get_checkpoint_quorum(n_providers: usize) -> Result<usize> {
+    match env::var("CHECKPOINT_QUORUM") {
+        Ok(checkpoint_quorum) => {
+            let checkpoint_quorum = checkpoint_quorum
+                .parse::<usize>()
+                .map_err(|e| Error::msg(format!("Invalid CHECKPOINT_QUORUM format: {}", e)))?;
+            if checkpoint_quorum == 0 || checkpoint_quorum > n_providers {
+                return Err(anyhow!(
+                    "CHECKPOINT_QUORUM {} must be between 1 and the number of providers {}",
+                    checkpoint_quorum,
+                    n_providers
+                ));
+            }
+            Ok(checkpoint_quorum)
+        }
+        Err(_) => Ok(n_providers / 2 + 1),
+    }
+}
+
 pub struct Client<S: ConsensusSpec, R: ConsensusRpc<S>> {
     inner: Inner<S, R>,
+    network: NetworkDefinition,
 }
 
 impl<S: ConsensusSpec, R: ConsensusRpc<S> + std::fmt::Debug> Client<S, R> {
     /// Constructor
     pub fn new(consensus_rpc: &Url) -> Result<Self> {
-        // Parsing chain ID and creating network
-
-        let chain_id = std::env::var("SOURCE_CHAIN_ID")
-            .map_err(|e| Error::msg(format!("SOURCE_CHAIN_ID not set or invalid: {}", e)))?;
-
-        let network = Network::from_chain_id(
-            chain_id
-                .parse()
-                .map_err(|e| Error::msg(format!("Invalid chain ID format: {}", e)))?,
-        )
-        .map_err(|e| Error::msg(format!("Failed to convert chain ID to network: {}", e)))?;
-
-        let base_config = network.to_base_config();
+        // Loading the source network definition
+        let network = NetworkDefinition::from_env()?;
 
         // Configuring client
         let config = Config {
             consensus_rpc: consensus_rpc.to_string(),
             execution_rpc: None,
-            chain: base_config.chain,
-            forks: base_config.forks,
+            chain: network.chain_config(),
+            forks: network.forks.clone(),
             strict_checkpoint_age: false,
             max_checkpoint_age: 604800, // 1 week
             ..Default::default()
@@ -71,7 +167,7 @@ impl<S: ConsensusSpec, R: ConsensusRpc<S> + std::fmt::Debug> Client<S, R> {
             Arc::new(config),
         );
 
-        Ok(Self { inner })
+        Ok(Self { inner, network })
     }
 
     /// Bootstrap the client from a checkpoint
@@ -102,6 +198,14 @@ impl<S: ConsensusSpec, R: ConsensusRpc<S> + std::fmt::Debug> Client<S, R> {
         Ok(bootstrap_client)
     }
 
+    /// Resume the client from a store, e.g. the bridge head's persisted store. Unlike bootstrapping, this works from
+    /// any slot, including the non-checkpoint head of a forced update.
+    pub fn from_store(consensus_rpc: &Url, store: LightClientStore<S>) -> Result<Self> {
+        let mut client = Client::new(consensus_rpc)?;
+        client.inner.store = store;
+        Ok(client)
+    }
+
     /// Get current finalized header
     pub fn get_current_finalized_header(&self) -> LightClientHeader {
         self.inner.store.finalized_header.clone()
@@ -125,30 +229,9 @@ impl<S: ConsensusSpec, R: ConsensusRpc<S> + std::fmt::Debug> Client<S, R> {
         Ok(B256::from_slice(block.tree_hash_root().as_ref()))
     }
 
-    /// Get latest checkpoint
+    /// Get latest checkpoint from the source network's checkpoint source
     pub async fn get_latest_checkpoint() -> Result<B256> {
-        let cf = checkpoints::CheckpointFallback::new()
-            .build()
-            .await
-            .map_err(|e| Error::msg(format!("Failed to build checkpoint fallback: {}", e)))?;
-
-        let chain_id = std::env::var("SOURCE_CHAIN_ID").map_err(|e| {
-            Error::msg(format!(
-                "SOURCE_CHAIN_ID environment variable not set: {}",
-                e
-            ))
-        })?;
-
-        let network = Network::from_chain_id(
-            chain_id
-                .parse()
-                .map_err(|e| Error::msg(format!("Invalid chain ID format: {}", e)))?,
-        )
-        .map_err(|e| Error::msg(format!("Failed to convert chain ID to network: {}", e)))?;
-
-        cf.fetch_latest_checkpoint(&network)
-            .await
-            .map_err(|e| Error::msg(format!("Failed to fetch latest checkpoint: {}", e)))
+        NetworkDefinition::from_env()?.fetch_latest_checkpoint().await
     }
 
     /// Fetch the latest finality update.
@@ -190,9 +273,9 @@ impl<S: ConsensusSpec, R: ConsensusRpc<S> + std::fmt::Debug> Client<S, R> {
         }
     }
 
-    /// Get expected slot
+    /// Get expected slot, from the source network's genesis time and slot duration
     pub fn expected_current_slot(&self) -> u64 {
-        self.inner.expected_current_slot()
+        self.network.expected_current_slot()
     }
 
     /// Fetch first update for client
@@ -286,17 +369,15 @@ impl<S: ConsensusSpec, R: ConsensusRpc<S> + std::fmt::Debug> Client<S, R> {
         Ok(store_clone)
     }
 
-    /// Get the latest slot & store hash from the latest finality checkpoint.
-    pub async fn get_latest_finality_slot_and_store_hash(
+    /// Get the slot & store hash of a finality checkpoint, along with the provider's block root at the checkpoint's
+    /// slot (which a canonical checkpoint equals).
+    pub async fn get_checkpoint_slot_and_store_hash(
         consensus_rpc: &Url,
-    ) -> Result<(u64, FixedBytes<32>)> {
-        // Get latest beacon checkpoint
-        info!("Fetching cold start client from latest checkpoint");
-        let latest_checkpoint = Client::<S, R>::get_latest_checkpoint().await?;
-
+        checkpoint: B256,
+    ) -> Result<(B256, u64, FixedBytes<32>)> {
         // Get the client from the beacon checkpoint
-        let client =
-            Client::<S, R>::bootstrap_from_checkpoint(consensus_rpc, latest_checkpoint).await?;
+        info!("Fetching cold start client from checkpoint {}", checkpoint);
+        let client = Client::<S, R>::bootstrap_from_checkpoint(consensus_rpc, checkpoint).await?;
 
         // Get slot head from checkpoint
         let slot = client.get_current_finalizer_header_beacon_slot();
@@ -309,7 +390,7 @@ impl<S: ConsensusSpec, R: ConsensusRpc<S> + std::fmt::Debug> Client<S, R> {
         // Get synced store
         info!("Syncing bootstrapped cold start store");
         let synced_store = Client::<S, R>::get_store_with_next_sync_committee(
-            client.inner.expected_current_slot(),
+            client.expected_current_slot(),
             client.inner.store,
             &client.inner.config.chain.genesis_root,
             &client.inner.config.forks,
@@ -321,7 +402,10 @@ impl<S: ConsensusSpec, R: ConsensusRpc<S> + std::fmt::Debug> Client<S, R> {
         let store_hash = sha256_hash_helios_store(&synced_store)?;
         info!("Calculated cold start store hash: {}", store_hash);
 
-        Ok((slot, store_hash))
+        // Get the provider's canonical block root at the checkpoint slot
+        let canonical_root = client.get_current_checkpoint().await?;
+
+        Ok((canonical_root, slot, store_hash))
     }
 
     /// Prepares a consensus proof input for a sp1 helios slot transition proof
@@ -329,21 +413,181 @@ impl<S: ConsensusSpec, R: ConsensusRpc<S> + std::fmt::Debug> Client<S, R> {
     /// * `consensus_rpc` - Url of the consensus RPC to use to prepare a store
     /// * `input_head` - Target slot number to prove from up until current finality head
     /// * `store_hash` - The previous hash of the helios client store state at the `input_head` slot
+    /// * `store` - The store hashing to `store_hash` when known (e.g. from the bridge head checkpoint), otherwise it is
+    ///   restored by bootstrapping from `input_slot`, which must then be a checkpoint slot
     pub async fn prepare_consensus_proof_inputs(
         consensus_rpc: &Url,
         input_slot: u64,
         store_hash: FixedBytes<32>,
+        store: Option<LightClientStore<S>>,
     ) -> Result<ConsensusProofInputs<S>> {
-        let mut client: Client<S, R> =
-            Client::bootstrap_from_slot(consensus_rpc, input_slot).await?;
+        let (client, resumed): (Client<S, R>, bool) = match store {
+            Some(store) => (Client::from_store(consensus_rpc, store)?, true),
+            None => (Client::bootstrap_from_slot(consensus_rpc, input_slot).await?, false),
+        };
 
-        let genesis_root = &client.inner.config.chain.genesis_root;
-        let forks = &client.inner.config.forks;
+        let genesis_root = client.inner.config.chain.genesis_root;
+        let forks = client.inner.config.forks.clone();
 
         // Get finality update
         debug!("Getting finality update from input slot {}", input_slot);
         let finality_update = client.get_latest_finality_update().await?;
 
+        // Get sync commitee updates and the store they apply to
+        let (updates, expected_current_slot, store) = if resumed {
+            Client::<S, R>::prepare_updates(client).await?
+        } else {
+            Client::<S, R>::prepare_store_and_updates(client, input_slot).await?
+        };
+
+        // When finality has been stalled for a sync committee period, fall back to forcing the best valid update. The
+        // timeout is measured up to its attested slot, as the program does (see `apply_force_update`)
+        let update_timeout = S::slots_per_epoch() * S::epochs_per_sync_committee_period();
+        let finality_stalled = finality_update.finalized_header().beacon().slot <= input_slot
+            && updates.last().is_some_and(|update| {
+                update.attested_header().beacon().slot
+                    > store.finalized_header.beacon().slot + update_timeout
+            });
+        let force_update = if finality_stalled && get_allow_force_updates()? {
+            // Rank the updates as the program does, which rejects a force update outranked by any of them. The best
+            // stays among the updates, ranking equal to itself
+            let mut replayed_store = store.clone();
+            let mut best_valid_update = None;
+            for update in updates.iter() {
+                let finalized_slot = replayed_store.finalized_header.beacon().slot;
+                apply_update(&mut replayed_store, update);
+                best_valid_update = track_best_valid_update(
+                    best_valid_update,
+                    update,
+                    finalized_slot,
+                    &replayed_store,
+                );
+            }
+            let force_update = best_valid_update.cloned();
+            if let Some(force_update) = &force_update {
+                warn!(
+                    "Finality stalled since slot {}, forcing update attested at slot {}",
+                    store.finalized_header.beacon().slot,
+                    force_update.attested_header().beacon().slot
+                );
+            }
+            force_update
+        } else {
+            None
+        };
+
+        // Create program inputs, the force update replaces the finality update
+        debug!("Building sp1 proof inputs.");
+        let finality_update = match force_update {
+            Some(_) => None,
+            None => Some(finality_update),
+        };
+        let proof_inputs = ConsensusProofInputs {
+            updates,
+            finality_update,
+            force_update,
+            expected_current_slot,
+            store,
+            genesis_root,
+            forks,
+            store_hash,
+            min_sync_committee_participants: get_min_sync_committee_participants::<S>()?,
+        };
+        debug!("Built sp1 proof inputs.");
+
+        Ok(proof_inputs)
+    }
+
+    /// Prepares a sync committee rotation proof input (see `rotation_program`), applying the sync committee updates
+    /// available from the input slot without a finality update. Used to keep the sync committee chain alive while
+    /// finality is stalled.
+    /// # Arguments
+    /// * `consensus_rpc` - Url of the consensus RPC to use to prepare a store
+    /// * `input_slot` - Slot of the store to rotate the sync committees of
+    /// * `store_hash` - The previous hash of the helios client store state at the `input_slot` slot
+    /// * `store` - The store hashing to `store_hash` when known (e.g. from the bridge head checkpoint), otherwise it is
+    ///   restored by bootstrapping from `input_slot`, which must then be a checkpoint slot
+    pub async fn prepare_rotation_proof_inputs(
+        consensus_rpc: &Url,
+        input_slot: u64,
+        store_hash: FixedBytes<32>,
+        store: Option<LightClientStore<S>>,
+    ) -> Result<RotationProofInputs<S>> {
+        let (client, resumed): (Client<S, R>, bool) = match store {
+            Some(store) => (Client::from_store(consensus_rpc, store)?, true),
+            None => (Client::bootstrap_from_slot(consensus_rpc, input_slot).await?, false),
+        };
+
+        let genesis_root = client.inner.config.chain.genesis_root;
+        let forks = client.inner.config.forks.clone();
+
+        // Get sync commitee updates and the store they apply to
+        let (updates, expected_current_slot, store) = if resumed {
+            Client::<S, R>::prepare_updates(client).await?
+        } else {
+            Client::<S, R>::prepare_store_and_updates(client, input_slot).await?
+        };
+        if updates.is_empty() {
+            return Err(anyhow::anyhow!(
+                "No sync committee updates beyond input slot {} to rotate with.",
+                input_slot
+            ));
+        }
+
+        Ok(RotationProofInputs {
+            updates,
+            expected_current_slot,
+            store,
+            genesis_root,
+            forks,
+            store_hash,
+        })
+    }
+
+    /// Prepares a bootstrap proof input (see `bootstrap_program`), proving the store hash a cold start derives from
+    /// `checkpoint` so the first `input_store_hash` of the proof chain is not trusted from the RPC.
+    /// # Arguments
+    /// * `consensus_rpc` - Url of the consensus RPC to fetch the bootstrap from
+    /// * `checkpoint` - Weak subjectivity checkpoint (beacon block root) to bootstrap from, e.g. the `Fixed`
+    ///   checkpoint source of the network definition
+    pub async fn prepare_bootstrap_proof_inputs(
+        consensus_rpc: &Url,
+        checkpoint: B256,
+    ) -> Result<BootstrapProofInputs<S>> {
+        let client: Client<S, R> = Client::bootstrap_from_checkpoint(consensus_rpc, checkpoint).await?;
+
+        // Get the bootstrap the client was initialised from
+        debug!("Getting bootstrap for checkpoint {}", checkpoint);
+        let bootstrap = client.inner.rpc.get_bootstrap(checkpoint).await.map_err(|e| {
+            Error::msg(format!("Failed to fetch bootstrap for checkpoint {}: {}", checkpoint, e))
+        })?;
+
+        // Get the update carrying the next sync committee
+        debug!("Getting bootstrap first update");
+        let first_update = client.get_first_update().await?;
+
+        Ok(BootstrapProofInputs {
+            checkpoint,
+            bootstrap,
+            first_update,
+            expected_current_slot: client.expected_current_slot(),
+            genesis_root: client.inner.config.chain.genesis_root,
+            forks: client.inner.config.forks.clone(),
+        })
+    }
+
+    /// Fetches the sync committee updates from the bootstrapped client's slot and restores the store to the state it
+    /// had at the end of the previous proof (with its next sync committee).
+    ///
+    /// # Returns
+    /// Tuple of (updates to apply, expected current slot, store).
+    async fn prepare_store_and_updates(
+        mut client: Client<S, R>,
+        input_slot: u64,
+    ) -> Result<(Vec<Update<S>>, u64, LightClientStore<S>)> {
+        let genesis_root = &client.inner.config.chain.genesis_root;
+        let forks = &client.inner.config.forks;
+
         // Get sync commitee updates
         debug!("Getting sync commitee updates.");
         let mut updates = client.get_updates().await?;
@@ -402,20 +646,31 @@ impl<S: ConsensusSpec, R: ConsensusRpc<S> + std::fmt::Debug> Client<S, R> {
             }
         };
 
-        // Create program inputs
-        debug!("Building sp1 proof inputs.");
-        let proof_inputs = ConsensusProofInputs {
-            updates,
-            finality_update,
-            expected_current_slot,
-            store,
-            genesis_root: *genesis_root,
-            forks: forks.clone(),
-            store_hash,
-        };
-        debug!("Built sp1 proof inputs.");
+        Ok((updates, expected_current_slot, store))
+    }
 
-        Ok(proof_inputs)
+    /// Fetches the sync committee updates for a client resumed from the exact store at the end of the previous proof
+    /// (see `Client::from_store`), which needs no restoring. Updates the store has already applied are skipped.
+    ///
+    /// # Returns
+    /// Tuple of (updates to apply, expected current slot, store).
+    async fn prepare_updates(
+        client: Client<S, R>,
+    ) -> Result<(Vec<Update<S>>, u64, LightClientStore<S>)> {
+        let finalized_slot = client.get_current_finalizer_header_beacon_slot();
+
+        // Get sync commitee updates
+        debug!("Getting sync commitee updates from resumed slot {}.", finalized_slot);
+        let updates: Vec<Update<S>> = client
+            .get_updates()
+            .await?
+            .into_iter()
+            .filter(|update| update.attested_header().beacon().slot > finalized_slot)
+            .collect();
+
+        let expected_current_slot = client.expected_current_slot();
+
+        Ok((updates, expected_current_slot, client.inner.store))
     }
 }
 
@@ -429,7 +684,7 @@ pub struct ConsensusHttpProxy<S: ConsensusSpec, R: ConsensusRpc<S>> {
     validation_timeout: Duration,
 }
 
-impl<S: ConsensusSpec, R: ConsensusRpc<S> + std::fmt::Debug> ConsensusHttpProxy<S, R> {
+impl<S: ConsensusSpecName, R: ConsensusRpc<S> + std::fmt::Debug> ConsensusHttpProxy<S, R> {
     pub fn from_env() -> Result<Self> {
         dotenv::dotenv().ok();
 
@@ -487,8 +742,12 @@ impl<S: ConsensusSpec, R: ConsensusRpc<S> + std::fmt::Debug> ConsensusHttpProxy<
     /// # Arguments
     /// * `input_slot` - The starting slot number for the state transition.
     /// * `store_hash` - The hash of the client store state at the `input_slot`.
+    /// * `store` - The client store at the `input_slot` when known (e.g. from the bridge head checkpoint), which is
+    ///   required to continue from a non-checkpoint slot such as the head of a forced update.
     /// * `validate` - Whether or not validation rules of output_slot > input_slot,
-    ///   next_sync_commitee is non zero and output_slot % 32 is non zero are applied.
+    ///   next_sync_commitee is non zero and output_slot % slots_per_epoch is zero are applied. These rules are
+    ///   enforced by the program itself (see `consensus_mpt_program`), this is a pre-check rejecting the window
+    ///   before execution inputs are fetched.
     ///
     /// # Returns
     /// Tuple of (input slot, output slot, validated proof inputs).
@@ -500,16 +759,18 @@ impl<S: ConsensusSpec, R: ConsensusRpc<S> + std::fmt::Debug> ConsensusHttpProxy<
         &self,
         input_slot: u64,
         store_hash: FixedBytes<32>,
+        store: Option<LightClientStore<S>>,
         validate: bool
     ) -> Result<ProofInputsWithWindow<S>> {
         // TODO move this function out of here its a bit strange to have the consensus and execution rpcs here
         // Deserves it own location
         let (input_slot, output_slot, validated_consensus_proof_inputs, expected_output_store_hash) = multiplex(
             |url| {
+                let store = store.clone();
                 async move {
                     // Fetch proof_inputs
                     let consensus_proof_inputs = Client::<S, R>::prepare_consensus_proof_inputs(
-                        &url, input_slot, store_hash,
+                        &url, input_slot, store_hash, store,
                     )
                     .await?;
 
@@ -519,6 +780,17 @@ impl<S: ConsensusSpec, R: ConsensusRpc<S> + std::fmt::Debug> ConsensusHttpProxy<
                             // Run program logic
                             let proof_outputs = consensus_program(consensus_proof_inputs.clone())?;
 
+                            // Refuse inputs verified against another network
+                            let expected_network_identity_digest =
+                                get_source_network_identity_digest::<S>()?;
+                            if proof_outputs.network_identity_digest != expected_network_identity_digest {
+                                return Err(anyhow::anyhow!(
+                                    "Network identity digest {:?} does not match the configured source network {:?}",
+                                    proof_outputs.network_identity_digest,
+                                    expected_network_identity_digest
+                                ));
+                            }
+
                             // Convert newHead to u64
                             let output_slot = proof_outputs.output_slot;
 
@@ -531,11 +803,9 @@ impl<S: ConsensusSpec, R: ConsensusRpc<S> + std::fmt::Debug> ConsensusHttpProxy<
                                 ));
                             }
 
-                            // Block non-checkpoint slots (they prevent bootstrapping on restart)
-                            if validate && output_slot % 32 > 0 { 
-                                // FIXME might need the validate_progress guard as its used as a flag to allow
-                                // the proof anyway. And for vk building we need to be able to arbirarily bypass this 
-                                // sort of validation.
+                            // Block non-checkpoint slots (they prevent bootstrapping on restart), forced heads are
+                            // attested headers and need not be aligned
+                            if validate && !proof_outputs.forced_update && output_slot % S::slots_per_epoch() > 0 {
                                 return Err(anyhow::anyhow!(
                                     "Output slot {} was a non-checkpoint slot. Preventing this as it prevents bootstrapping if we go offline.",
                                     output_slot,
@@ -574,23 +844,61 @@ impl<S: ConsensusSpec, R: ConsensusRpc<S> + std::fmt::Debug> ConsensusHttpProxy<
             })?
             .block_number();
 
-        let finalized_output_block_number = *validated_consensus_proof_inputs
-            .finality_update
-            .finalized_header()
+        // A forced head is the finalized header of the force update when it advanced the store, its attested header
+        // otherwise (see `apply_force_update`)
+        let output_header = match &validated_consensus_proof_inputs.force_update {
+            Some(force_update) if force_update.finalized_header().beacon().slot == output_slot => {
+                force_update.finalized_header()
+            }
+            Some(force_update) => force_update.attested_header(),
+            None => validated_consensus_proof_inputs
+                .finality_update
+                .as_ref()
+                .ok_or_else(|| anyhow!("Consensus proof inputs have no finality update"))?
+                .finalized_header(),
+        };
+        let finalized_output_block_number = *output_header
             .execution()
             .map_err(|_| {
                 anyhow::Error::msg("Failed to get output finalized execution header".to_string())
             })?
             .block_number();
+        let output_beacon_header = output_header.beacon().clone();
 
         // Get Execution Proxy (Note this is a bit messy to do this here now FIXME)
-        let validated_consensus_mpt_proof_input_with_window = ExecutionHttpProxy::<S>::try_from_env()
+        let execution_http_proxy = ExecutionHttpProxy::<S>::try_from_env();
+
+        // Logs emitted before the output block are proven within historical blocks of the window, so that receipts
+        // are proven for every block in (input, output]
+        let network = NetworkDefinition::from_env()?;
+        let mut historical_block_targets = Vec::new();
+        for window_block in execution_http_proxy
+            .get_window_receipt_proofs(finalized_input_block_number, finalized_output_block_number)
+            .await?
+        {
+            let slot = (window_block.timestamp - network.genesis_time) / network.seconds_per_slot;
+            debug!(
+                "Proving the receipts of block {} within the historical beacon block at slot {}",
+                window_block.block_number, slot
+            );
+            historical_block_targets.push(HistoricalBlockTarget {
+                slot,
+                contract_storages: Vec::new(),
+                receipt_proofs: window_block.receipt_proofs,
+            });
+        }
+        let historical_blocks = self
+            .prepare_historical_block_proofs(&output_beacon_header, historical_block_targets)
+            .await?;
+
+        let validated_consensus_mpt_proof_input_with_window = execution_http_proxy
             .prepare_consensus_mpt_proof_inputs(
                 input_slot,
                 output_slot,
                 finalized_input_block_number,
                 finalized_output_block_number,
                 validated_consensus_proof_inputs,
+                historical_blocks,
                 expected_output_store_hash
             )
             .await?;
@@ -600,23 +908,102 @@ impl<S: ConsensusSpec, R: ConsensusRpc<S> + std::fmt::Debug> ConsensusHttpProxy<
         Ok(validated_consensus_mpt_proof_input_with_window)
     }
 
-    /// Get the latest slot & store hash from the latest finality checkpoint.
-    pub async fn get_latest_finality_slot_and_store_hash(&self) -> Result<(u64, FixedBytes<32>)> {
-        // This is used in cold start procedure which is a trusted operation (hence the principle trusted endpoint).
-        // FIXME this bootstrap needs to be more strictly defined
-        // but leaving this for now.
+    /// Builds the proofs of the beacon blocks of `blocks` against the finalized state of `finalized_header`, with the
+    /// storage and receipt proofs targeting each block (see `historical::prepare_historical_block_proofs`).
+    ///
+    /// The finalized state is downloaded once for all blocks and the states closing their periods once per period.
+    /// Providers must serve SSZ beacon states, archive ones for blocks older than `SLOTS_PER_HISTORICAL_ROOT` slots.
+    pub async fn prepare_historical_block_proofs(
+        &self,
+        finalized_header: &BeaconBlockHeader,
+        blocks: Vec<HistoricalBlockTarget>,
+    ) -> Result<Vec<HistoricalBlockProof<S>>> {
+        let forks = NetworkDefinition::from_env()?.forks;
+        // One download for the finalized state, one per summary period and one for the blocks themselves
+        let summary_periods = blocks
+            .iter()
+            .filter_map(|block| historical::summary_period::<S>(block.slot, finalized_header.slot))
+            .collect::<BTreeSet<_>>()
+            .len() as u32;
         query_with_fallback(
             &self.principal_provider_url,
-            &Vec::new(),
+            &self.backup_providers_urls,
             |url| {
-                async move { Client::<S, R>::get_latest_finality_slot_and_store_hash(&url).await }
-                    .boxed()
+                let finalized_header = finalized_header.clone();
+                let forks = forks.clone();
+                let blocks = blocks.clone();
+                async move {
+                    historical::prepare_historical_block_proofs::<S>(
+                        &url,
+                        &finalized_header,
+                        &forks,
+                        blocks,
+                    )
+                    .await
+                }
+                .boxed()
             },
-            CONSENSUS_PROVIDER_TIMEOUT,
+            BEACON_SSZ_TIMEOUT * (2 + summary_periods),
         )
         .await
     }
 
+    /// Get the latest slot & store hash from the latest finality checkpoint.
+    pub async fn get_latest_finality_slot_and_store_hash(&self) -> Result<(u64, FixedBytes<32>)> {
+        // This is used in cold start procedure. The resulting store hash can be proven from the checkpoint with
+        // `bootstrap_job` (see `Client::prepare_bootstrap_proof_inputs`).
+        let network = NetworkDefinition::from_env()?;
+        let checkpoint = network.fetch_latest_checkpoint().await?;
+        info!("Cold starting from checkpoint {}", checkpoint);
+
+        let (canonical_root, slot, store_hash) = if network.checkpoint_source.is_local() {
+            // A local checkpoint is trusted as is, the bootstrap is verified against it so any provider will do
+            query_with_fallback(
+                &self.principal_provider_url,
+                &self.backup_providers_urls,
+                |url| {
+                    async move {
+                        Client::<S, R>::get_checkpoint_slot_and_store_hash(&url, checkpoint).await
+                    }
+                    .boxed()
+                },
+                CONSENSUS_PROVIDER_TIMEOUT,
+            )
+            .await?
+        } else {
+            // A fetched checkpoint must be canonical for a quorum of the providers before its store hash is accepted
+            let min_agreeing = get_checkpoint_quorum(self.all_providers_urls.len())?;
+            info!(
+                "Requiring {} of {} providers to agree on the checkpoint",
+                min_agreeing,
+                self.all_providers_urls.len()
+            );
+            quorum(
+                |url| {
+                    async move {
+                        Client::<S, R>::get_checkpoint_slot_and_store_hash(&url, checkpoint).await
+                    }
+                    .boxed()
+                },
+                &self.all_providers_urls,
+                min_agreeing,
+                CONSENSUS_PROVIDER_TIMEOUT,
+            )
+            .await?
+        };
+
+        if canonical_root != checkpoint {
+            return Err(anyhow!(
+                "Checkpoint {} is not canonical, the block root at its slot {} is {}",
+                checkpoint,
+                slot,
+                canonical_root
+            ));
+        }
+
+        Ok((slot, store_hash))
+    }
+
     // Get latest_finality_slot
     pub async fn get_latest_finality_slot(&self) -> Result<u64> {
         multiplex(
@@ -635,3 +1022,29 @@ impl<S: ConsensusSpec, R: ConsensusRpc<S> + std::fmt::Debug> ConsensusHttpProxy<
         .await
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A single test, as it sets the process wide `CHECKPOINT_QUORUM`
+    #[test]
+    fn checkpoint_quorum_defaults_to_a_majority_and_is_bounded_by_the_providers() {
+        env::remove_var("CHECKPOINT_QUORUM");
+        assert_eq!(get_checkpoint_quorum(1).unwrap(), 1);
+        assert_eq!(get_checkpoint_quorum(3).unwrap(), 2);
+        assert_eq!(get_checkpoint_quorum(4).unwrap(), 3);
+
+        env::set_var("CHECKPOINT_QUORUM", "2");
+        assert_eq!(get_checkpoint_quorum(3).unwrap(), 2);
+        assert!(get_checkpoint_quorum(1).is_err());
+
+        env::set_var("CHECKPOINT_QUORUM", "0");
+        assert!(get_checkpoint_quorum(3).is_err());
+
+        env::set_var("CHECKPOINT_QUORUM", "two");
+        assert!(get_checkpoint_quorum(3).is_err());
+
+        env::remove_var("CHECKPOINT_QUORUM");
+    }
+}
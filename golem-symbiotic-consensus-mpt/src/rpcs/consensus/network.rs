@@ -0,0 +1,211 @@
+use alloy_primitives::B256;
+use anyhow::{anyhow, Context, Error, Result};
+use golem_symbiotic_consensus_mpt_types::network::{network_identity_digest, ConsensusSpecName};
+use helios_consensus_core::types::Forks;
+use helios_ethereum::config::{checkpoints::CheckpointFallback, networks::Network, ChainConfig};
+use log::warn;
+use serde::{Deserialize, Serialize};
+use std::{
+    env, fs,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// Seconds per slot of the networks built into helios.
+const BUILT_IN_SECONDS_PER_SLOT: u64 = 12;
+
+/// Where the latest finalized checkpoint (a beacon block root) is fetched from on cold start.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum CheckpointSource {
+    /// helios' checkpoint fallback services of the built-in network with the definition's `chain_id`.
+    Helios,
+    /// Checkpointz compatible APIs, tried in order.
+    Checkpointz(Vec<String>),
+    /// A fixed, trusted checkpoint.
+    Fixed(B256),
+    /// A local file containing a trusted checkpoint (hex encoded block root), e.g. for air-gapped bootstraps.
+    File(String),
+}
+
+impl CheckpointSource {
+    /// Whether the checkpoint is provided locally by the operator (and trusted as is) rather than fetched remotely.
+    pub fn is_local(&self) -> bool {
+        matches!(self, CheckpointSource::Fixed(_) | CheckpointSource::File(_))
+    }
+
+    /// Checkpoint source override from `SOURCE_CHECKPOINT` (a hex encoded block root) or `SOURCE_CHECKPOINT_FILE`
+    /// (a file containing one), `None` when neither is set.
+    fn from_env() -> Result<Option<Self>> {
+        match (env::var("SOURCE_CHECKPOINT"), env::var("SOURCE_CHECKPOINT_FILE")) {
+            (Ok(_), Ok(_)) => Err(anyhow!(
+                "Only one of SOURCE_CHECKPOINT and SOURCE_CHECKPOINT_FILE can be set"
+            )),
+            (Ok(checkpoint), Err(_)) => Ok(Some(CheckpointSource::Fixed(
+                parse_checkpoint(&checkpoint).context("Invalid SOURCE_CHECKPOINT")?,
+            ))),
+            (Err(_), Ok(path)) => Ok(Some(CheckpointSource::File(path))),
+            (Err(_), Err(_)) => Ok(None),
+        }
+    }
+}
+
+/// Definition of the source network the consensus client follows.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct NetworkDefinition {
+    pub chain_id: u64,
+    pub genesis_root: B256,
+    pub genesis_time: u64,
+    pub seconds_per_slot: u64,
+    pub forks: Forks, // fork versions and epochs, from genesis to electra
+    pub checkpoint_source: CheckpointSource,
+}
+
+impl NetworkDefinition {
+    /// Loads the source network definition.
+    ///
+    /// When `SOURCE_NETWORK_DEFINITION` is set it must point to a JSON file containing a `NetworkDefinition`, if
+    /// `SOURCE_CHAIN_ID` is also set it must match the definition's `chain_id`. Otherwise the network is taken from
+    /// helios' built-in table for `SOURCE_CHAIN_ID`.
+    ///
+    /// The checkpoint source can be overridden with `SOURCE_CHECKPOINT` or `SOURCE_CHECKPOINT_FILE`.
+    pub fn from_env() -> Result<Self> {
+        let mut network_definition = Self::definition_from_env()?;
+        if let Some(checkpoint_source) = CheckpointSource::from_env()? {
+            network_definition.checkpoint_source = checkpoint_source;
+        }
+        Ok(network_definition)
+    }
+
+    /// Loads the source network definition, without checkpoint source overrides.
+    fn definition_from_env() -> Result<Self> {
+        let chain_id = match env::var("SOURCE_CHAIN_ID") {
+            Ok(chain_id) => Some(
+                chain_id
+                    .parse::<u64>()
+                    .map_err(|e| Error::msg(format!("Invalid chain ID format: {}", e)))?,
+            ),
+            Err(_) => None,
+        };
+
+        match env::var("SOURCE_NETWORK_DEFINITION") {
+            Ok(path) => {
+                let contents = fs::read_to_string(&path).with_context(|| {
+                    format!("Failed to read SOURCE_NETWORK_DEFINITION file '{}'", path)
+                })?;
+                let network_definition = serde_json::from_str::<NetworkDefinition>(&contents)
+                    .with_context(|| format!("Invalid SOURCE_NETWORK_DEFINITION file '{}'", path))?;
+
+                if let Some(chain_id) = chain_id {
+                    if chain_id != network_definition.chain_id {
+                        return Err(anyhow!(
+                            "SOURCE_CHAIN_ID {} does not match the chain id {} of SOURCE_NETWORK_DEFINITION '{}'",
+                            chain_id,
+                            network_definition.chain_id,
+                            path
+                        ));
+                    }
+                }
+                if network_definition.seconds_per_slot == 0 {
+                    return Err(anyhow!(
+                        "Invalid SOURCE_NETWORK_DEFINITION file '{}', seconds_per_slot must be non zero",
+                        path
+                    ));
+                }
+
+                Ok(network_definition)
+            }
+            Err(_) => {
+                let chain_id = chain_id.ok_or_else(|| {
+                    anyhow!("Neither SOURCE_NETWORK_DEFINITION nor SOURCE_CHAIN_ID is set")
+                })?;
+                Self::from_chain_id(chain_id)
+            }
+        }
+    }
+
+    /// The definition of a network built into helios.
+    pub fn from_chain_id(chain_id: u64) -> Result<Self> {
+        let network = Network::from_chain_id(chain_id)
+            .map_err(|e| Error::msg(format!("Failed to convert chain ID to network: {}", e)))?;
+        let base_config = network.to_base_config();
+
+        Ok(NetworkDefinition {
+            chain_id: base_config.chain.chain_id,
+            genesis_root: base_config.chain.genesis_root,
+            genesis_time: base_config.chain.genesis_time,
+            seconds_per_slot: BUILT_IN_SECONDS_PER_SLOT,
+            forks: base_config.forks,
+            checkpoint_source: CheckpointSource::Helios,
+        })
+    }
+
+    /// Chain config of the helios consensus client.
+    pub fn chain_config(&self) -> ChainConfig {
+        ChainConfig {
+            chain_id: self.chain_id,
+            genesis_time: self.genesis_time,
+            genesis_root: self.genesis_root,
+        }
+    }
+
+    /// The slot expected to be current, from the genesis time and slot duration.
+    pub fn expected_current_slot(&self) -> u64 {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        now.saturating_sub(self.genesis_time) / self.seconds_per_slot
+    }
+
+    /// Network identity digest committed by proofs of this network.
+    pub fn network_identity_digest<S: ConsensusSpecName>(&self) -> B256 {
+        network_identity_digest::<S>(&self.genesis_root, &self.forks)
+    }
+
+    /// Fetches the latest finalized checkpoint from the definition's checkpoint source.
+    pub async fn fetch_latest_checkpoint(&self) -> Result<B256> {
+        match &self.checkpoint_source {
+            CheckpointSource::Helios => {
+                let network = Network::from_chain_id(self.chain_id).map_err(|e| {
+                    Error::msg(format!(
+                        "Helios checkpoint source requires a built-in network: {}",
+                        e
+                    ))
+                })?;
+                let cf = CheckpointFallback::new()
+                    .build()
+                    .await
+                    .map_err(|e| Error::msg(format!("Failed to build checkpoint fallback: {}", e)))?;
+                cf.fetch_latest_checkpoint(&network)
+                    .await
+                    .map_err(|e| Error::msg(format!("Failed to fetch latest checkpoint: {}", e)))
+            }
+            CheckpointSource::Checkpointz(urls) => {
+                for url in urls.iter() {
+                    match CheckpointFallback::fetch_checkpoint_from_api(url).await {
+                        Ok(checkpoint) => return Ok(checkpoint),
+                        Err(e) => warn!("Failed to fetch checkpoint from '{}': {}", url, e),
+                    }
+                }
+                Err(anyhow!(
+                    "Failed to fetch latest checkpoint from any of the {} checkpointz sources.",
+                    urls.len()
+                ))
+            }
+            CheckpointSource::Fixed(checkpoint) => Ok(*checkpoint),
+            CheckpointSource::File(path) => {
+                let contents = fs::read_to_string(path)
+                    .with_context(|| format!("Failed to read checkpoint file '{}'", path))?;
+                parse_checkpoint(&contents)
+                    .with_context(|| format!("Invalid checkpoint file '{}'", path))
+            }
+        }
+    }
+}
+
+/// Parses a hex encoded checkpoint (block root), ignoring surrounding whitespace.
+fn parse_checkpoint(checkpoint: &str) -> Result<B256> {
+    checkpoint
+        .trim()
+        .parse::<B256>()
+        .map_err(|e| anyhow!("Invalid checkpoint '{}': {}", checkpoint.trim(), e))
+}
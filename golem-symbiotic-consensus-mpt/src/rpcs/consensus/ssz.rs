@@ -0,0 +1,1166 @@
+use alloy_primitives::B256;
+use anyhow::{anyhow, bail, Result};
+use sha2_v0_10_8::{Digest, Sha256};
+
+/// Schema of an SSZ value, enough to split SSZ bytes into their parts and merkleize them (`hash_tree_root`).
+///
+/// Lengths and limits are in elements (bytes for `ByteVector` / `ByteList`, bits for `Bitvector` / `Bitlist`).
+#[derive(Debug, Clone)]
+pub enum SszType {
+    Uint(usize), // little endian unsigned integer of the given byte size (1, 8 or 32)
+    ByteVector(usize),
+    ByteList(usize),
+    Bitvector(usize),
+    Bitlist(usize),
+    Vector(Box<SszType>, usize),
+    List(Box<SszType>, usize),
+    Container(Vec<SszType>),
+}
+
+const BYTES_PER_CHUNK: usize = 32;
+const BYTES_PER_LENGTH_OFFSET: usize = 4;
+
+pub fn hash_pair(left: &B256, right: &B256) -> B256 {
+    let mut hasher = Sha256::new();
+    hasher.update(left.as_slice());
+    hasher.update(right.as_slice());
+    B256::from_slice(&hasher.finalize())
+}
+
+/// Roots of the all zero subtrees, `zero_hashes(depth)[i]` being the root of a subtree of depth `i`.
+pub fn zero_hashes(depth: usize) -> Vec<B256> {
+    let mut zero_hashes = vec![B256::ZERO];
+    for i in 0..depth {
+        zero_hashes.push(hash_pair(&zero_hashes[i], &zero_hashes[i]));
+    }
+    zero_hashes
+}
+
+/// Depth of the tree merkleizing `limit` chunks, `ceil(log2(limit))`.
+pub fn chunk_depth(limit: usize) -> usize {
+    limit.max(1).next_power_of_two().trailing_zeros() as usize
+}
+
+/// Every level of the zero padded tree of `depth` over `chunks`, from the chunks (level 0) up to the root. Levels only
+/// hold their non padding nodes (at least one), padding is `zero_hashes(depth)[level]`.
+fn merkle_levels(chunks: &[B256], depth: usize) -> Result<Vec<Vec<B256>>> {
+    if depth < usize::BITS as usize && chunks.len() > 1 << depth {
+        bail!("{} chunks exceed a tree of depth {}", chunks.len(), depth);
+    }
+
+    let zero_hashes = zero_hashes(depth);
+    let mut level = if chunks.is_empty() { vec![B256::ZERO] } else { chunks.to_vec() };
+    let mut levels = Vec::with_capacity(depth + 1);
+    for zero_hash in zero_hashes.iter().take(depth) {
+        let next_level = level
+            .chunks(2)
+            .map(|pair| hash_pair(&pair[0], pair.get(1).unwrap_or(zero_hash)))
+            .collect();
+        levels.push(level);
+        level = next_level;
+    }
+    levels.push(level);
+    Ok(levels)
+}
+
+/// Root of the zero padded tree of `depth` over `chunks`.
+pub fn merkleize(chunks: &[B256], depth: usize) -> Result<B256> {
+    Ok(merkle_levels(chunks, depth)?[depth][0])
+}
+
+/// Sibling of the chunk at `index` on each level of the zero padded tree of `depth` over `chunks`, from the chunk up to
+/// (excluding) the root.
+pub fn merkle_branch(chunks: &[B256], depth: usize, index: usize) -> Result<Vec<B256>> {
+    if depth < usize::BITS as usize && index >= 1 << depth {
+        bail!("Index {} is out of range for a tree of depth {}", index, depth);
+    }
+
+    let zero_hashes = zero_hashes(depth);
+    let levels = merkle_levels(chunks, depth)?;
+    Ok((0..depth)
+        .map(|level| {
+            let sibling = (index >> level) ^ 1;
+            levels[level].get(sibling).copied().unwrap_or(zero_hashes[level])
+        })
+        .collect())
+}
+
+/// Mixes the length of a list into the root of its data.
+pub fn mix_in_length(root: &B256, length: usize) -> B256 {
+    hash_pair(root, &length_chunk(length))
+}
+
+/// The length of a list as it is mixed in, a little endian uint256.
+pub fn length_chunk(length: usize) -> B256 {
+    let mut chunk = B256::ZERO;
+    chunk[..8].copy_from_slice(&(length as u64).to_le_bytes());
+    chunk
+}
+
+/// Packs bytes into zero padded chunks.
+fn pack(bytes: &[u8]) -> Vec<B256> {
+    bytes
+        .chunks(BYTES_PER_CHUNK)
+        .map(|chunk| {
+            let mut packed = B256::ZERO;
+            packed[..chunk.len()].copy_from_slice(chunk);
+            packed
+        })
+        .collect()
+}
+
+fn read_offset(bytes: &[u8], position: usize) -> Result<usize> {
+    let offset = bytes
+        .get(position..position + BYTES_PER_LENGTH_OFFSET)
+        .ok_or_else(|| anyhow!("SSZ offset at {} is out of range of {} bytes", position, bytes.len()))?;
+    Ok(u32::from_le_bytes(offset.try_into()?) as usize)
+}
+
+/// Slices the variable size parts of a container or list between consecutive offsets.
+fn slice_variable_parts<'a>(bytes: &'a [u8], offsets: &[usize]) -> Result<Vec<&'a [u8]>> {
+    let mut parts = Vec::with_capacity(offsets.len());
+    for (i, start) in offsets.iter().enumerate() {
+        let end = offsets.get(i + 1).copied().unwrap_or(bytes.len());
+        if *start > end || end > bytes.len() {
+            bail!("SSZ offsets {}..{} are out of order or range of {} bytes", start, end, bytes.len());
+        }
+        parts.push(&bytes[*start..end]);
+    }
+    Ok(parts)
+}
+
+impl SszType {
+    /// Size of the SSZ encoding, `None` when it is variable.
+    pub fn fixed_size(&self) -> Option<usize> {
+        match self {
+            SszType::Uint(size) => Some(*size),
+            SszType::ByteVector(length) => Some(*length),
+            SszType::Bitvector(length) => Some(length.div_ceil(8)),
+            SszType::Vector(element, length) => element.fixed_size().map(|size| size * length),
+            SszType::Container(fields) => fields.iter().map(SszType::fixed_size).sum(),
+            SszType::ByteList(_) | SszType::Bitlist(_) | SszType::List(_, _) => None,
+        }
+    }
+
+    fn is_basic(&self) -> bool {
+        matches!(self, SszType::Uint(_))
+    }
+
+    /// Splits the SSZ encoding of a container into the encodings of its fields.
+    pub fn split_fields<'a>(&self, bytes: &'a [u8]) -> Result<Vec<&'a [u8]>> {
+        let SszType::Container(fields) = self else {
+            bail!("Only containers have fields");
+        };
+
+        let mut parts: Vec<Option<&[u8]>> = Vec::with_capacity(fields.len());
+        let mut offsets = Vec::new();
+        let mut position = 0;
+        for field in fields.iter() {
+            match field.fixed_size() {
+                Some(size) => {
+                    let part = bytes.get(position..position + size).ok_or_else(|| {
+                        anyhow!("SSZ field at {} is out of range of {} bytes", position, bytes.len())
+                    })?;
+                    parts.push(Some(part));
+                    position += size;
+                }
+                None => {
+                    offsets.push(read_offset(bytes, position)?);
+                    parts.push(None);
+                    position += BYTES_PER_LENGTH_OFFSET;
+                }
+            }
+        }
+        if let Some(first_offset) = offsets.first() {
+            if *first_offset != position {
+                bail!("First SSZ offset {} does not follow the fixed part of {} bytes", first_offset, position);
+            }
+        } else if position != bytes.len() {
+            bail!("SSZ container of {} bytes has {} trailing bytes", position, bytes.len() - position);
+        }
+
+        let mut variable_parts = slice_variable_parts(bytes, &offsets)?.into_iter();
+        Ok(parts
+            .into_iter()
+            .map(|part| part.unwrap_or_else(|| variable_parts.next().unwrap()))
+            .collect())
+    }
+
+    /// Splits the SSZ encoding of a vector or list of composite elements into the encodings of its elements.
+    pub fn split_elements<'a>(&self, bytes: &'a [u8]) -> Result<Vec<&'a [u8]>> {
+        let element = match self {
+            SszType::Vector(element, _) | SszType::List(element, _) => element,
+            _ => bail!("Only vectors and lists have elements"),
+        };
+
+        let elements = match element.fixed_size() {
+            Some(0) => bail!("SSZ elements cannot be empty"),
+            Some(size) => {
+                if bytes.len() % size != 0 {
+                    bail!("{} bytes are not a whole number of {} byte elements", bytes.len(), size);
+                }
+                bytes.chunks(size).collect()
+            }
+            None if bytes.is_empty() => Vec::new(),
+            None => {
+                let first_offset = read_offset(bytes, 0)?;
+                if first_offset % BYTES_PER_LENGTH_OFFSET != 0 || first_offset == 0 {
+                    bail!("Invalid first SSZ offset {}", first_offset);
+                }
+                let offsets = (0..first_offset / BYTES_PER_LENGTH_OFFSET)
+                    .map(|i| read_offset(bytes, i * BYTES_PER_LENGTH_OFFSET))
+                    .collect::<Result<Vec<usize>>>()?;
+                slice_variable_parts(bytes, &offsets)?
+            }
+        };
+
+        match self {
+            SszType::Vector(_, length) if elements.len() != *length => {
+                bail!("SSZ vector has {} elements, expected {}", elements.len(), length)
+            }
+            SszType::List(_, limit) if elements.len() > *limit => {
+                bail!("SSZ list has {} elements, exceeding its limit of {}", elements.len(), limit)
+            }
+            _ => Ok(elements),
+        }
+    }
+
+    /// Roots of the fields of a container, the leaves of its tree.
+    pub fn field_roots(&self, bytes: &[u8]) -> Result<Vec<B256>> {
+        let SszType::Container(fields) = self else {
+            bail!("Only containers have fields");
+        };
+        fields
+            .iter()
+            .zip(self.split_fields(bytes)?)
+            .map(|(field, part)| field.hash_tree_root(part))
+            .collect()
+    }
+
+    /// Roots of the elements of a vector or list of composite elements, the leaves of its data tree.
+    pub fn element_roots(&self, bytes: &[u8]) -> Result<Vec<B256>> {
+        let element = match self {
+            SszType::Vector(element, _) | SszType::List(element, _) => element,
+            _ => bail!("Only vectors and lists have elements"),
+        };
+        self.split_elements(bytes)?
+            .into_iter()
+            .map(|part| element.hash_tree_root(part))
+            .collect()
+    }
+
+    pub fn hash_tree_root(&self, bytes: &[u8]) -> Result<B256> {
+        match self {
+            SszType::Uint(size) => {
+                if bytes.len() != *size {
+                    bail!("SSZ uint of {} bytes has {} bytes", size, bytes.len());
+                }
+                Ok(pack(bytes)[0])
+            }
+            SszType::ByteVector(length) => {
+                if bytes.len() != *length {
+                    bail!("SSZ byte vector of {} bytes has {} bytes", length, bytes.len());
+                }
+                merkleize(&pack(bytes), chunk_depth(length.div_ceil(BYTES_PER_CHUNK)))
+            }
+            SszType::ByteList(limit) => {
+                if bytes.len() > *limit {
+                    bail!("SSZ byte list has {} bytes, exceeding its limit of {}", bytes.len(), limit);
+                }
+                let root = merkleize(&pack(bytes), chunk_depth(limit.div_ceil(BYTES_PER_CHUNK)))?;
+                Ok(mix_in_length(&root, bytes.len()))
+            }
+            SszType::Bitvector(length) => {
+                if bytes.len() != length.div_ceil(8) {
+                    bail!("SSZ bitvector of {} bits has {} bytes", length, bytes.len());
+                }
+                merkleize(&pack(bytes), chunk_depth(length.div_ceil(256)))
+            }
+            SszType::Bitlist(limit) => {
+                // The highest set bit of the last byte delimits the bits
+                let last = *bytes
+                    .last()
+                    .filter(|last| **last != 0)
+                    .ok_or_else(|| anyhow!("SSZ bitlist is missing its delimiting bit"))?;
+                let delimiter = 7 - last.leading_zeros() as usize;
+                let length = (bytes.len() - 1) * 8 + delimiter;
+                if length > *limit {
+                    bail!("SSZ bitlist has {} bits, exceeding its limit of {}", length, limit);
+                }
+                let mut bits = bytes.to_vec();
+                *bits.last_mut().unwrap() ^= 1 << delimiter;
+                if delimiter == 0 {
+                    bits.pop();
+                }
+                let root = merkleize(&pack(&bits), chunk_depth(limit.div_ceil(256)))?;
+                Ok(mix_in_length(&root, length))
+            }
+            SszType::Vector(element, length) if element.is_basic() => {
+                let size = element.fixed_size().unwrap();
+                if bytes.len() != size * length {
+                    bail!("SSZ vector of {} uints has {} bytes", length, bytes.len());
+                }
+                merkleize(&pack(bytes), chunk_depth((size * length).div_ceil(BYTES_PER_CHUNK)))
+            }
+            SszType::List(element, limit) if element.is_basic() => {
+                let size = element.fixed_size().unwrap();
+                if bytes.len() % size != 0 || bytes.len() / size > *limit {
+                    bail!("SSZ list of at most {} uints has {} bytes", limit, bytes.len());
+                }
+                let root = merkleize(&pack(bytes), chunk_depth((size * limit).div_ceil(BYTES_PER_CHUNK)))?;
+                Ok(mix_in_length(&root, bytes.len() / size))
+            }
+            SszType::Vector(_, length) => merkleize(&self.element_roots(bytes)?, chunk_depth(*length)),
+            SszType::List(_, limit) => {
+                let element_roots = self.element_roots(bytes)?;
+                let root = merkleize(&element_roots, chunk_depth(*limit))?;
+                Ok(mix_in_length(&root, element_roots.len()))
+            }
+            SszType::Container(fields) => merkleize(&self.field_roots(bytes)?, chunk_depth(fields.len())),
+        }
+    }
+}
+
+/// Little endian uint of an SSZ encoding, e.g. a field split from a container.
+pub fn read_uint(bytes: &[u8]) -> Result<u64> {
+    Ok(u64::from_le_bytes(
+        bytes.try_into().map_err(|_| anyhow!("SSZ uint64 has {} bytes", bytes.len()))?,
+    ))
+}
+
+// Beacon chain containers, as of the consensus specs v1.5 (Capella to Electra)
+
+/// Index of `block_roots` within the beacon state.
+pub const BEACON_STATE_BLOCK_ROOTS_INDEX: usize = 5;
+/// Index of `slot` within the beacon state.
+const BEACON_STATE_SLOT_INDEX: usize = 2;
+/// Index of `historical_summaries` within the beacon state.
+pub const BEACON_STATE_HISTORICAL_SUMMARIES_INDEX: usize = 27;
+/// Index of `execution_payload` within the beacon block body.
+pub const BEACON_BLOCK_BODY_EXECUTION_PAYLOAD_INDEX: usize = 9;
+/// `HISTORICAL_ROOTS_LIMIT`, the limit of `historical_summaries`.
+const HISTORICAL_ROOTS_LIMIT: usize = 1 << 24;
+const VALIDATOR_REGISTRY_LIMIT: usize = 1 << 40;
+const MAX_BYTES_PER_TRANSACTION: usize = 1 << 30;
+const MAX_TRANSACTIONS_PER_PAYLOAD: usize = 1 << 20;
+const BYTES_PER_LOGS_BLOOM: usize = 256;
+const MAX_EXTRA_DATA_BYTES: usize = 32;
+const DEPOSIT_CONTRACT_TREE_DEPTH: usize = 32;
+
+/// Preset constants sizing the beacon chain containers, which helios' `ConsensusSpec` does not expose.
+#[derive(Debug, Clone)]
+pub struct BeaconPreset {
+    pub slots_per_historical_root: usize,
+    pub epochs_per_historical_vector: usize,
+    pub epochs_per_slashings_vector: usize,
+    pub slots_per_eth1_voting_period: usize,
+    pub sync_committee_size: usize,
+    pub max_validators_per_committee: usize,
+    pub max_committees_per_slot: usize,
+    pub max_proposer_slashings: usize,
+    pub max_attester_slashings: usize,
+    pub max_attester_slashings_electra: usize,
+    pub max_attestations: usize,
+    pub max_attestations_electra: usize,
+    pub max_deposits: usize,
+    pub max_voluntary_exits: usize,
+    pub max_bls_to_execution_changes: usize,
+    pub max_blob_commitments_per_block: usize,
+    pub max_withdrawals_per_payload: usize,
+    pub max_deposit_requests_per_payload: usize,
+    pub max_withdrawal_requests_per_payload: usize,
+    pub max_consolidation_requests_per_payload: usize,
+    pub pending_deposits_limit: usize,
+    pub pending_partial_withdrawals_limit: usize,
+    pub pending_consolidations_limit: usize,
+}
+
+impl BeaconPreset {
+    pub fn mainnet() -> Self {
+        BeaconPreset {
+            slots_per_historical_root: 8192,
+            epochs_per_historical_vector: 65536,
+            epochs_per_slashings_vector: 8192,
+            slots_per_eth1_voting_period: 64 * 32,
+            sync_committee_size: 512,
+            max_validators_per_committee: 2048,
+            max_committees_per_slot: 64,
+            max_proposer_slashings: 16,
+            max_attester_slashings: 2,
+            max_attester_slashings_electra: 1,
+            max_attestations: 128,
+            max_attestations_electra: 8,
+            max_deposits: 16,
+            max_voluntary_exits: 16,
+            max_bls_to_execution_changes: 16,
+            max_blob_commitments_per_block: 4096,
+            max_withdrawals_per_payload: 16,
+            max_deposit_requests_per_payload: 8192,
+            max_withdrawal_requests_per_payload: 16,
+            max_consolidation_requests_per_payload: 2,
+            pending_deposits_limit: 1 << 27,
+            pending_partial_withdrawals_limit: 1 << 27,
+            pending_consolidations_limit: 1 << 18,
+        }
+    }
+
+    pub fn minimal() -> Self {
+        BeaconPreset {
+            slots_per_historical_root: 64,
+            epochs_per_historical_vector: 64,
+            epochs_per_slashings_vector: 64,
+            slots_per_eth1_voting_period: 4 * 8,
+            sync_committee_size: 32,
+            max_committees_per_slot: 4,
+            max_blob_commitments_per_block: 32,
+            max_withdrawals_per_payload: 4,
+            max_deposit_requests_per_payload: 4,
+            max_withdrawal_requests_per_payload: 2,
+            pending_partial_withdrawals_limit: 64,
+            pending_consolidations_limit: 64,
+            ..BeaconPreset::mainnet()
+        }
+    }
+
+    /// The preset of a consensus spec, by its `ConsensusSpecName::spec_name`.
+    pub fn from_spec_name(spec_name: &str) -> Result<Self> {
+        match spec_name {
+            "mainnet" => Ok(BeaconPreset::mainnet()),
+            "minimal" => Ok(BeaconPreset::minimal()),
+            other => Err(anyhow!("No beacon preset for consensus spec '{}'", other)),
+        }
+    }
+}
+
+/// Forks whose beacon state has `historical_summaries`, the ones historical blocks can be proven against.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum BeaconFork {
+    Capella,
+    Deneb,
+    Electra,
+}
+
+fn uint64() -> SszType {
+    SszType::Uint(8)
+}
+
+fn root() -> SszType {
+    SszType::ByteVector(32)
+}
+
+fn list(element: SszType, limit: usize) -> SszType {
+    SszType::List(Box::new(element), limit)
+}
+
+fn vector(element: SszType, length: usize) -> SszType {
+    SszType::Vector(Box::new(element), length)
+}
+
+fn checkpoint() -> SszType {
+    SszType::Container(vec![uint64(), root()])
+}
+
+fn beacon_block_header() -> SszType {
+    SszType::Container(vec![uint64(), uint64(), root(), root(), root()])
+}
+
+fn eth1_data() -> SszType {
+    SszType::Container(vec![root(), uint64(), root()])
+}
+
+fn sync_committee(preset: &BeaconPreset) -> SszType {
+    SszType::Container(vec![
+        vector(SszType::ByteVector(48), preset.sync_committee_size),
+        SszType::ByteVector(48),
+    ])
+}
+
+fn withdrawal() -> SszType {
+    SszType::Container(vec![uint64(), uint64(), SszType::ByteVector(20), uint64()])
+}
+
+/// `ExecutionPayload` or, when `header` is set, `ExecutionPayloadHeader`.
+fn execution_payload(preset: &BeaconPreset, fork: BeaconFork, header: bool) -> SszType {
+    let mut fields = vec![
+        root(),                                 // parent_hash
+        SszType::ByteVector(20),                // fee_recipient
+        root(),                                 // state_root
+        root(),                                 // receipts_root
+        SszType::ByteVector(BYTES_PER_LOGS_BLOOM), // logs_bloom
+        root(),                                 // prev_randao
+        uint64(),                               // block_number
+        uint64(),                               // gas_limit
+        uint64(),                               // gas_used
+        uint64(),                               // timestamp
+        SszType::ByteList(MAX_EXTRA_DATA_BYTES),   // extra_data
+        SszType::Uint(32),                      // base_fee_per_gas
+        root(),                                 // block_hash
+    ];
+    if header {
+        fields.extend([root(), root()]); // transactions_root, withdrawals_root
+    } else {
+        fields.push(execution_payload_transactions());
+        fields.push(execution_payload_withdrawals(preset));
+    }
+    if fork >= BeaconFork::Deneb {
+        fields.extend([uint64(), uint64()]); // blob_gas_used, excess_blob_gas
+    }
+    SszType::Container(fields)
+}
+
+/// `ExecutionPayload.transactions`.
+pub fn execution_payload_transactions() -> SszType {
+    list(SszType::ByteList(MAX_BYTES_PER_TRANSACTION), MAX_TRANSACTIONS_PER_PAYLOAD)
+}
+
+/// `ExecutionPayload.withdrawals`.
+pub fn execution_payload_withdrawals(preset: &BeaconPreset) -> SszType {
+    list(withdrawal(), preset.max_withdrawals_per_payload)
+}
+
+/// `ExecutionPayload`.
+pub fn execution_payload_schema(preset: &BeaconPreset, fork: BeaconFork) -> SszType {
+    execution_payload(preset, fork, false)
+}
+
+/// `BeaconState`.
+pub fn beacon_state_schema(preset: &BeaconPreset, fork: BeaconFork) -> SszType {
+    let participation = list(SszType::Uint(1), VALIDATOR_REGISTRY_LIMIT);
+    let validator = SszType::Container(vec![
+        SszType::ByteVector(48), // pubkey
+        root(),                  // withdrawal_credentials
+        uint64(),                // effective_balance
+        SszType::Uint(1),        // slashed
+        uint64(),                // activation_eligibility_epoch
+        uint64(),                // activation_epoch
+        uint64(),                // exit_epoch
+        uint64(),                // withdrawable_epoch
+    ]);
+
+    let mut fields = vec![
+        uint64(), // genesis_time
+        root(),   // genesis_validators_root
+        uint64(), // slot
+        SszType::Container(vec![SszType::ByteVector(4), SszType::ByteVector(4), uint64()]), // fork
+        beacon_block_header(),                                    // latest_block_header
+        vector(root(), preset.slots_per_historical_root),         // block_roots
+        vector(root(), preset.slots_per_historical_root),         // state_roots
+        list(root(), HISTORICAL_ROOTS_LIMIT),                     // historical_roots
+        eth1_data(),                                              // eth1_data
+        list(eth1_data(), preset.slots_per_eth1_voting_period),   // eth1_data_votes
+        uint64(),                                                 // eth1_deposit_index
+        list(validator, VALIDATOR_REGISTRY_LIMIT),                // validators
+        list(uint64(), VALIDATOR_REGISTRY_LIMIT),                 // balances
+        vector(root(), preset.epochs_per_historical_vector),      // randao_mixes
+        vector(uint64(), preset.epochs_per_slashings_vector),     // slashings
+        participation.clone(),                                    // previous_epoch_participation
+        participation,                                            // current_epoch_participation
+        SszType::Bitvector(4),                                    // justification_bits
+        checkpoint(),                                             // previous_justified_checkpoint
+        checkpoint(),                                             // current_justified_checkpoint
+        checkpoint(),                                             // finalized_checkpoint
+        list(uint64(), VALIDATOR_REGISTRY_LIMIT),                 // inactivity_scores
+        sync_committee(preset),                                   // current_sync_committee
+        sync_committee(preset),                                   // next_sync_committee
+        execution_payload(preset, fork, true),                    // latest_execution_payload_header
+        uint64(),                                                 // next_withdrawal_index
+        uint64(),                                                 // next_withdrawal_validator_index
+        list(SszType::Container(vec![root(), root()]), HISTORICAL_ROOTS_LIMIT), // historical_summaries
+    ];
+    if fork >= BeaconFork::Electra {
+        fields.extend([
+            uint64(), // deposit_requests_start_index
+            uint64(), // deposit_balance_to_consume
+            uint64(), // exit_balance_to_consume
+            uint64(), // earliest_exit_epoch
+            uint64(), // consolidation_balance_to_consume
+            uint64(), // earliest_consolidation_epoch
+            list(
+                SszType::Container(vec![
+                    SszType::ByteVector(48),
+                    root(),
+                    uint64(),
+                    SszType::ByteVector(96),
+                    uint64(),
+                ]),
+                preset.pending_deposits_limit,
+            ), // pending_deposits
+            list(
+                SszType::Container(vec![uint64(), uint64(), uint64()]),
+                preset.pending_partial_withdrawals_limit,
+            ), // pending_partial_withdrawals
+            list(
+                SszType::Container(vec![uint64(), uint64()]),
+                preset.pending_consolidations_limit,
+            ), // pending_consolidations
+        ]);
+    }
+    SszType::Container(fields)
+}
+
+/// `BeaconBlockBody`.
+pub fn beacon_block_body_schema(preset: &BeaconPreset, fork: BeaconFork) -> SszType {
+    let electra = fork >= BeaconFork::Electra;
+    let committee_bits = if electra {
+        preset.max_validators_per_committee * preset.max_committees_per_slot
+    } else {
+        preset.max_validators_per_committee
+    };
+    let signature = || SszType::ByteVector(96);
+    let signed_header = || SszType::Container(vec![beacon_block_header(), signature()]);
+    let attestation_data = || {
+        SszType::Container(vec![uint64(), uint64(), root(), checkpoint(), checkpoint()])
+    };
+    let indexed_attestation = || {
+        SszType::Container(vec![list(uint64(), committee_bits), attestation_data(), signature()])
+    };
+    let mut attestation = vec![SszType::Bitlist(committee_bits), attestation_data(), signature()];
+    if electra {
+        attestation.push(SszType::Bitvector(preset.max_committees_per_slot));
+    }
+
+    let mut fields = vec![
+        signature(), // randao_reveal
+        eth1_data(), // eth1_data
+        root(),      // graffiti
+        list(
+            SszType::Container(vec![signed_header(), signed_header()]),
+            preset.max_proposer_slashings,
+        ), // proposer_slashings
+        list(
+            SszType::Container(vec![indexed_attestation(), indexed_attestation()]),
+            if electra { preset.max_attester_slashings_electra } else { preset.max_attester_slashings },
+        ), // attester_slashings
+        list(
+            SszType::Container(attestation),
+            if electra { preset.max_attestations_electra } else { preset.max_attestations },
+        ), // attestations
+        list(
+            SszType::Container(vec![
+                vector(root(), DEPOSIT_CONTRACT_TREE_DEPTH + 1),
+                SszType::Container(vec![SszType::ByteVector(48), root(), uint64(), signature()]),
+            ]),
+            preset.max_deposits,
+        ), // deposits
+        list(
+            SszType::Container(vec![SszType::Container(vec![uint64(), uint64()]), signature()]),
+            preset.max_voluntary_exits,
+        ), // voluntary_exits
+        SszType::Container(vec![SszType::Bitvector(preset.sync_committee_size), signature()]), // sync_aggregate
+        execution_payload(preset, fork, false), // execution_payload
+        list(
+            SszType::Container(vec![
+                SszType::Container(vec![uint64(), SszType::ByteVector(48), SszType::ByteVector(20)]),
+                signature(),
+            ]),
+            preset.max_bls_to_execution_changes,
+        ), // bls_to_execution_changes
+    ];
+    if fork >= BeaconFork::Deneb {
+        fields.push(list(SszType::ByteVector(48), preset.max_blob_commitments_per_block)); // blob_kzg_commitments
+    }
+    if electra {
+        fields.push(SszType::Container(vec![
+            list(
+                SszType::Container(vec![
+                    SszType::ByteVector(48),
+                    root(),
+                    uint64(),
+                    signature(),
+                    uint64(),
+                ]),
+                preset.max_deposit_requests_per_payload,
+            ),
+            list(
+                SszType::Container(vec![SszType::ByteVector(20), SszType::ByteVector(48), uint64()]),
+                preset.max_withdrawal_requests_per_payload,
+            ),
+            list(
+                SszType::Container(vec![
+                    SszType::ByteVector(20),
+                    SszType::ByteVector(48),
+                    SszType::ByteVector(48),
+                ]),
+                preset.max_consolidation_requests_per_payload,
+            ),
+        ])); // execution_requests
+    }
+    SszType::Container(fields)
+}
+
+/// `SignedBeaconBlock`.
+fn signed_beacon_block_schema(preset: &BeaconPreset, fork: BeaconFork) -> SszType {
+    SszType::Container(vec![
+        SszType::Container(vec![
+            uint64(),
+            uint64(),
+            root(),
+            root(),
+            beacon_block_body_schema(preset, fork),
+        ]),
+        SszType::ByteVector(96),
+    ])
+}
+
+/// The roots of a beacon state needed to prove blocks within its `block_roots` and `historical_summaries`.
+#[derive(Debug, Clone)]
+pub struct BeaconStateRoots {
+    pub slot: u64,
+    field_roots: Vec<B256>,
+    block_roots: Vec<B256>,
+    historical_summaries: Vec<(B256, B256)>, // (block_summary_root, state_summary_root)
+}
+
+impl BeaconStateRoots {
+    /// Merkleizes an SSZ encoded beacon state.
+    pub fn from_ssz(bytes: &[u8], preset: &BeaconPreset, fork: BeaconFork) -> Result<Self> {
+        let schema = beacon_state_schema(preset, fork);
+        let parts = schema.split_fields(bytes)?;
+        let SszType::Container(fields) = &schema else {
+            unreachable!()
+        };
+
+        let field_roots = fields
+            .iter()
+            .zip(parts.iter())
+            .map(|(field, part)| field.hash_tree_root(part))
+            .collect::<Result<Vec<B256>>>()?;
+        let block_roots = fields[BEACON_STATE_BLOCK_ROOTS_INDEX]
+            .element_roots(parts[BEACON_STATE_BLOCK_ROOTS_INDEX])?;
+        let historical_summaries_field = &fields[BEACON_STATE_HISTORICAL_SUMMARIES_INDEX];
+        let historical_summaries = historical_summaries_field
+            .split_elements(parts[BEACON_STATE_HISTORICAL_SUMMARIES_INDEX])?
+            .into_iter()
+            .map(|summary| (B256::from_slice(&summary[..32]), B256::from_slice(&summary[32..])))
+            .collect();
+
+        Ok(BeaconStateRoots {
+            slot: read_uint(parts[BEACON_STATE_SLOT_INDEX])?,
+            field_roots,
+            block_roots,
+            historical_summaries,
+        })
+    }
+
+    fn state_depth(&self) -> usize {
+        chunk_depth(self.field_roots.len())
+    }
+
+    pub fn state_root(&self) -> Result<B256> {
+        merkleize(&self.field_roots, self.state_depth())
+    }
+
+    /// `state.block_roots`.
+    pub fn block_roots(&self) -> &[B256] {
+        &self.block_roots
+    }
+
+    /// Root of `state.block_roots`, the `block_summary_root` of the state's period once it is complete.
+    pub fn block_roots_root(&self) -> B256 {
+        self.field_roots[BEACON_STATE_BLOCK_ROOTS_INDEX]
+    }
+
+    /// Branch from `state.block_roots[slot % SLOTS_PER_HISTORICAL_ROOT]` to the root of `state.block_roots`.
+    pub fn block_roots_vector_branch(&self, slot: u64) -> Result<Vec<B256>> {
+        let slots_per_historical_root = self.block_roots.len();
+        merkle_branch(
+            &self.block_roots,
+            chunk_depth(slots_per_historical_root),
+            slot as usize % slots_per_historical_root,
+        )
+    }
+
+    /// Branch from `state.block_roots[slot % SLOTS_PER_HISTORICAL_ROOT]` to the state root.
+    pub fn block_roots_branch(&self, slot: u64) -> Result<Vec<B256>> {
+        let mut branch = self.block_roots_vector_branch(slot)?;
+        branch.extend(merkle_branch(
+            &self.field_roots,
+            self.state_depth(),
+            BEACON_STATE_BLOCK_ROOTS_INDEX,
+        )?);
+        Ok(branch)
+    }
+
+    /// `state.historical_summaries[summary_index].block_summary_root`.
+    pub fn block_summary_root(&self, summary_index: usize) -> Result<B256> {
+        self.historical_summaries
+            .get(summary_index)
+            .map(|(block_summary_root, _)| *block_summary_root)
+            .ok_or_else(|| {
+                anyhow!(
+                    "Historical summary {} is out of range of the state's {} summaries",
+                    summary_index,
+                    self.historical_summaries.len()
+                )
+            })
+    }
+
+    /// Branch from `state.historical_summaries[summary_index].block_summary_root` to the state root: the summary's
+    /// `state_summary_root`, the list's data tree, its length mix-in and the state fields.
+    pub fn historical_summary_branch(&self, summary_index: usize) -> Result<Vec<B256>> {
+        let (_, state_summary_root) = self.historical_summaries.get(summary_index).ok_or_else(|| {
+            anyhow!(
+                "Historical summary {} is out of range of the state's {} summaries",
+                summary_index,
+                self.historical_summaries.len()
+            )
+        })?;
+        let summary_roots: Vec<B256> = self
+            .historical_summaries
+            .iter()
+            .map(|(block_summary_root, state_summary_root)| hash_pair(block_summary_root, state_summary_root))
+            .collect();
+
+        let mut branch = vec![*state_summary_root];
+        branch.extend(merkle_branch(&summary_roots, chunk_depth(HISTORICAL_ROOTS_LIMIT), summary_index)?);
+        branch.push(length_chunk(summary_roots.len()));
+        branch.extend(merkle_branch(
+            &self.field_roots,
+            self.state_depth(),
+            BEACON_STATE_HISTORICAL_SUMMARIES_INDEX,
+        )?);
+        Ok(branch)
+    }
+}
+
+/// The parts of an SSZ encoded signed beacon block needed to prove its execution payload.
+#[derive(Debug, Clone)]
+pub struct BeaconBlockParts {
+    pub slot: u64,
+    pub proposer_index: u64,
+    pub parent_root: B256,
+    pub state_root: B256,
+    pub body_root: B256,
+    pub execution_payload_root: B256,
+    pub execution_branch: Vec<B256>, // from the execution payload to the body root
+    pub execution_payload_fields: Vec<Vec<u8>>, // SSZ encoded fields of the execution payload
+}
+
+impl BeaconBlockParts {
+    /// Splits and merkleizes an SSZ encoded signed beacon block.
+    pub fn from_signed_block_ssz(bytes: &[u8], preset: &BeaconPreset, fork: BeaconFork) -> Result<Self> {
+        let signed_block = signed_beacon_block_schema(preset, fork);
+        let SszType::Container(signed_block_fields) = &signed_block else {
+            unreachable!()
+        };
+        let block = &signed_block_fields[0];
+        let SszType::Container(block_fields) = block else {
+            unreachable!()
+        };
+        let body = &block_fields[4];
+
+        let block_parts = block.split_fields(signed_block.split_fields(bytes)?[0])?;
+        let body_parts = body.split_fields(block_parts[4])?;
+        let body_roots = body.field_roots(block_parts[4])?;
+        let body_depth = chunk_depth(body_roots.len());
+
+        let execution_payload = execution_payload_schema(preset, fork);
+        let execution_payload_fields = execution_payload
+            .split_fields(body_parts[BEACON_BLOCK_BODY_EXECUTION_PAYLOAD_INDEX])?
+            .into_iter()
+            .map(<[u8]>::to_vec)
+            .collect();
+
+        Ok(BeaconBlockParts {
+            slot: read_uint(block_parts[0])?,
+            proposer_index: read_uint(block_parts[1])?,
+            parent_root: B256::from_slice(block_parts[2]),
+            state_root: B256::from_slice(block_parts[3]),
+            body_root: merkleize(&body_roots, body_depth)?,
+            execution_payload_root: body_roots[BEACON_BLOCK_BODY_EXECUTION_PAYLOAD_INDEX],
+            execution_branch: merkle_branch(&body_roots, body_depth, BEACON_BLOCK_BODY_EXECUTION_PAYLOAD_INDEX)?,
+            execution_payload_fields,
+        })
+    }
+
+    /// Root of the block's header, the block root.
+    pub fn block_root(&self) -> Result<B256> {
+        let mut header = Vec::with_capacity(8 + 8 + 32 * 3);
+        header.extend_from_slice(&self.slot.to_le_bytes());
+        header.extend_from_slice(&self.proposer_index.to_le_bytes());
+        header.extend_from_slice(self.parent_root.as_slice());
+        header.extend_from_slice(self.state_root.as_slice());
+        header.extend_from_slice(self.body_root.as_slice());
+        beacon_block_header().hash_tree_root(&header)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::b256;
+
+    fn chunk(byte: u8) -> B256 {
+        B256::repeat_byte(byte)
+    }
+
+    #[test]
+    fn zero_hashes_match_the_deposit_contract() {
+        let zero_hashes = zero_hashes(3);
+        assert_eq!(zero_hashes[1], b256!("f5a5fd42d16a20302798ef6ed309979b43003d2320d9f0e8ea9831a92759fb4b"));
+        assert_eq!(zero_hashes[2], b256!("db56114e00fdd4c1f85c892bf35ac9a89289aaecb1ebd0a96cde606a748b5d71"));
+        assert_eq!(zero_hashes[3], b256!("c78009fdf07fc56a11f122370658a353aaa542ed63e44c4bc15ff4cd105ab33c"));
+    }
+
+    #[test]
+    fn merkleizes_with_zero_padding() {
+        let chunks = [chunk(1), chunk(2), chunk(3)];
+        let left = hash_pair(&chunks[0], &chunks[1]);
+        let right = hash_pair(&chunks[2], &B256::ZERO);
+        assert_eq!(merkleize(&chunks, 2).unwrap(), hash_pair(&left, &right));
+
+        // A deeper limit pads with zero subtrees
+        let zero_hashes = zero_hashes(2);
+        assert_eq!(
+            merkleize(&chunks, 3).unwrap(),
+            hash_pair(&hash_pair(&left, &right), &zero_hashes[2])
+        );
+        assert_eq!(merkleize(&[], 2).unwrap(), zero_hashes[2]);
+        assert_eq!(merkleize(&[chunk(1)], 0).unwrap(), chunk(1));
+        assert!(merkleize(&chunks, 1).is_err());
+    }
+
+    #[test]
+    fn branches_fold_back_to_the_root() {
+        let chunks: Vec<B256> = (1..=5).map(chunk).collect();
+        let root = merkleize(&chunks, 4).unwrap();
+        for index in 0..16 {
+            let branch = merkle_branch(&chunks, 4, index).unwrap();
+            let mut node = chunks.get(index).copied().unwrap_or(B256::ZERO);
+            for (level, sibling) in branch.iter().enumerate() {
+                node = if (index >> level) & 1 == 1 {
+                    hash_pair(sibling, &node)
+                } else {
+                    hash_pair(&node, sibling)
+                };
+            }
+            assert_eq!(node, root, "branch of chunk {}", index);
+        }
+        assert!(merkle_branch(&chunks, 4, 16).is_err());
+    }
+
+    #[test]
+    fn empty_bitlist_is_the_mixed_in_zero_chunk() {
+        assert_eq!(
+            SszType::Bitlist(256).hash_tree_root(&[0x01]).unwrap(),
+            b256!("f5a5fd42d16a20302798ef6ed309979b43003d2320d9f0e8ea9831a92759fb4b")
+        );
+        // The delimiter is dropped, 9 bits set
+        let bits = SszType::Bitlist(2048).hash_tree_root(&[0xff, 0x03]).unwrap();
+        let mut packed = B256::ZERO;
+        packed[0] = 0xff;
+        packed[1] = 0x01;
+        assert_eq!(bits, mix_in_length(&merkleize(&[packed], 3).unwrap(), 9));
+        assert!(SszType::Bitlist(2048).hash_tree_root(&[0xff, 0x00]).is_err());
+    }
+
+    #[test]
+    fn splits_containers_with_variable_fields() {
+        // Container { a: uint64, b: List[uint64, 4], c: bytes32, d: ByteList[64] }
+        let container = SszType::Container(vec![
+            uint64(),
+            SszType::List(Box::new(uint64()), 4),
+            root(),
+            SszType::ByteList(64),
+        ]);
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(&7u64.to_le_bytes());
+        bytes.extend_from_slice(&48u32.to_le_bytes()); // 8 + 4 + 32 + 4
+        bytes.extend_from_slice(chunk(9).as_slice());
+        bytes.extend_from_slice(&64u32.to_le_bytes());
+        bytes.extend_from_slice(&1u64.to_le_bytes());
+        bytes.extend_from_slice(&2u64.to_le_bytes());
+        bytes.extend_from_slice(b"extra");
+
+        let parts = container.split_fields(&bytes).unwrap();
+        assert_eq!(read_uint(parts[0]).unwrap(), 7);
+        assert_eq!(parts[1].len(), 16);
+        assert_eq!(parts[2], chunk(9).as_slice());
+        assert_eq!(parts[3], b"extra");
+
+        let mut list_chunk = B256::ZERO;
+        list_chunk[..8].copy_from_slice(&1u64.to_le_bytes());
+        list_chunk[8..16].copy_from_slice(&2u64.to_le_bytes());
+        let mut extra_chunk = B256::ZERO;
+        extra_chunk[..5].copy_from_slice(b"extra");
+        let field_roots = container.field_roots(&bytes).unwrap();
+        assert_eq!(field_roots[0], length_chunk(7));
+        assert_eq!(field_roots[1], mix_in_length(&list_chunk, 2));
+        assert_eq!(field_roots[2], chunk(9));
+        assert_eq!(field_roots[3], mix_in_length(&merkleize(&[extra_chunk], 1).unwrap(), 5));
+        assert_eq!(container.hash_tree_root(&bytes).unwrap(), merkleize(&field_roots, 2).unwrap());
+
+        // Offsets must follow the fixed part and each other
+        let mut misplaced = bytes.clone();
+        misplaced[8..12].copy_from_slice(&44u32.to_le_bytes());
+        assert!(container.split_fields(&misplaced).is_err());
+        let mut reversed = bytes.clone();
+        reversed[44..48].copy_from_slice(&40u32.to_le_bytes());
+        assert!(container.split_fields(&reversed).is_err());
+    }
+
+    #[test]
+    fn splits_lists_of_variable_elements() {
+        let list = SszType::List(Box::new(SszType::ByteList(32)), 4);
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(&8u32.to_le_bytes());
+        bytes.extend_from_slice(&10u32.to_le_bytes());
+        bytes.extend_from_slice(b"ab");
+        bytes.extend_from_slice(b"cde");
+        let elements = list.split_elements(&bytes).unwrap();
+        assert_eq!(elements, vec![b"ab".as_slice(), b"cde".as_slice()]);
+        assert_eq!(list.split_elements(&[]).unwrap().len(), 0);
+
+        let element_roots = list.element_roots(&bytes).unwrap();
+        assert_eq!(
+            list.hash_tree_root(&bytes).unwrap(),
+            mix_in_length(&merkleize(&element_roots, 2).unwrap(), 2)
+        );
+        assert!(SszType::List(Box::new(SszType::ByteList(32)), 1).hash_tree_root(&bytes).is_err());
+    }
+
+    // SSZ encoding of the default (zeroed, empty) value of a type
+    fn default_ssz(ty: &SszType) -> Vec<u8> {
+        match ty {
+            SszType::Container(fields) => {
+                let fixed_size: usize = fields
+                    .iter()
+                    .map(|field| field.fixed_size().unwrap_or(BYTES_PER_LENGTH_OFFSET))
+                    .sum();
+                let mut bytes = Vec::new();
+                let mut variable = Vec::new();
+                for field in fields {
+                    if field.fixed_size().is_some() {
+                        bytes.extend(default_ssz(field));
+                    } else {
+                        bytes.extend(((fixed_size + variable.len()) as u32).to_le_bytes());
+                        variable.extend(default_ssz(field));
+                    }
+                }
+                bytes.extend(variable);
+                bytes
+            }
+            SszType::Bitlist(_) => vec![0x01],
+            SszType::ByteList(_) | SszType::List(_, _) => Vec::new(),
+            _ => vec![0; ty.fixed_size().unwrap()],
+        }
+    }
+
+    // Folds a branch from `leaf` at the generalized `index` (relative to the root), as the program does
+    fn fold_branch(leaf: B256, branch: &[B256], index: u64) -> B256 {
+        branch.iter().enumerate().fold(leaf, |node, (level, sibling)| {
+            if (index >> level) & 1 == 1 {
+                hash_pair(sibling, &node)
+            } else {
+                hash_pair(&node, sibling)
+            }
+        })
+    }
+
+    // Offset of `block_roots` in a beacon state: genesis_time, genesis_validators_root, slot, fork, latest_block_header
+    const BLOCK_ROOTS_OFFSET: usize = 8 + 32 + 8 + 16 + 112;
+
+    // A minimal preset beacon state at `slot` with distinct block roots and, appended after the last (Deneb) field,
+    // `summaries` historical summaries
+    fn minimal_state(fork: BeaconFork, slot: u64, summaries: u8) -> Vec<u8> {
+        let preset = BeaconPreset::minimal();
+        let mut bytes = default_ssz(&beacon_state_schema(&preset, fork));
+        bytes[40..48].copy_from_slice(&slot.to_le_bytes());
+        for i in 0..preset.slots_per_historical_root {
+            let position = BLOCK_ROOTS_OFFSET + i * 32;
+            bytes[position..position + 32].copy_from_slice(B256::with_last_byte(i as u8 + 1).as_slice());
+        }
+        for summary in 0..summaries {
+            bytes.extend_from_slice(chunk(0xb0 + summary).as_slice()); // block_summary_root
+            bytes.extend_from_slice(chunk(0xc0 + summary).as_slice()); // state_summary_root
+        }
+        bytes
+    }
+
+    #[test]
+    fn proves_block_roots_within_the_state() {
+        let preset = BeaconPreset::minimal();
+        let bytes = minimal_state(BeaconFork::Deneb, 200, 0);
+        let state = BeaconStateRoots::from_ssz(&bytes, &preset, BeaconFork::Deneb).unwrap();
+        assert_eq!(state.slot, 200);
+        assert_eq!(state.state_root().unwrap(), beacon_state_schema(&preset, BeaconFork::Deneb).hash_tree_root(&bytes).unwrap());
+
+        // 28 fields (depth 5) over 64 block roots (depth 6)
+        let slot = 190;
+        let branch = state.block_roots_branch(slot).unwrap();
+        assert_eq!(branch.len(), 5 + 6);
+        let leaf = state.block_roots()[(slot % 64) as usize];
+        assert_eq!(leaf, B256::with_last_byte(63));
+        let index = (BEACON_STATE_BLOCK_ROOTS_INDEX as u64) * 64 + slot % 64;
+        assert_eq!(fold_branch(leaf, &branch, index), state.state_root().unwrap());
+        assert_eq!(
+            fold_branch(leaf, &state.block_roots_vector_branch(slot).unwrap(), slot % 64),
+            state.block_roots_root()
+        );
+        assert_ne!(fold_branch(leaf, &branch, index + 1), state.state_root().unwrap());
+    }
+
+    #[test]
+    fn electra_states_are_one_level_deeper() {
+        let preset = BeaconPreset::minimal();
+        let bytes = minimal_state(BeaconFork::Electra, 200, 0);
+        let state = BeaconStateRoots::from_ssz(&bytes, &preset, BeaconFork::Electra).unwrap();
+
+        // 37 fields (depth 6), the Deneb depth is one short of the state root
+        let branch = state.block_roots_branch(7).unwrap();
+        assert_eq!(branch.len(), 6 + 6);
+        let leaf = state.block_roots()[7];
+        let index = (BEACON_STATE_BLOCK_ROOTS_INDEX as u64) * 64 + 7;
+        assert_eq!(fold_branch(leaf, &branch, index), state.state_root().unwrap());
+        assert_ne!(fold_branch(leaf, &branch[..11], index), state.state_root().unwrap());
+
+        // A Deneb schema does not fit an Electra state
+        assert!(BeaconStateRoots::from_ssz(&bytes, &preset, BeaconFork::Deneb).is_err());
+    }
+
+    #[test]
+    fn proves_historical_summaries_within_the_state() {
+        let preset = BeaconPreset::minimal();
+        let bytes = minimal_state(BeaconFork::Deneb, 1000, 3);
+        let state = BeaconStateRoots::from_ssz(&bytes, &preset, BeaconFork::Deneb).unwrap();
+        assert_eq!(state.block_summary_root(1).unwrap(), chunk(0xb1));
+        assert!(state.block_summary_root(3).is_err());
+
+        // state_summary_root, 24 list levels, the length mix-in and 5 state levels
+        let branch = state.historical_summary_branch(1).unwrap();
+        assert_eq!(branch.len(), 1 + 24 + 1 + 5);
+        assert_eq!(branch[0], chunk(0xc1));
+        assert_eq!(branch[25], length_chunk(3));
+        let index = ((((BEACON_STATE_HISTORICAL_SUMMARIES_INDEX as u64) << 1) << 24) | 1) << 1;
+        assert_eq!(fold_branch(chunk(0xb1), &branch, index), state.state_root().unwrap());
+        assert_ne!(fold_branch(chunk(0xb0), &branch, index), state.state_root().unwrap());
+        assert!(state.historical_summary_branch(3).is_err());
+    }
+
+    #[test]
+    fn proves_the_execution_payload_within_the_block() {
+        let preset = BeaconPreset::minimal();
+        for fork in [BeaconFork::Capella, BeaconFork::Deneb, BeaconFork::Electra] {
+            let mut bytes = default_ssz(&signed_beacon_block_schema(&preset, fork));
+            // The block follows its offset and the signature, starting with slot and proposer_index
+            bytes[100..108].copy_from_slice(&42u64.to_le_bytes());
+            bytes[108..116].copy_from_slice(&3u64.to_le_bytes());
+            let block = BeaconBlockParts::from_signed_block_ssz(&bytes, &preset, fork).unwrap();
+            assert_eq!((block.slot, block.proposer_index), (42, 3));
+
+            let payload = execution_payload_schema(&preset, fork);
+            assert_eq!(
+                block.execution_payload_fields.len(),
+                if fork == BeaconFork::Capella { 15 } else { 17 }
+            );
+            assert_eq!(block.execution_payload_root, payload.hash_tree_root(&default_ssz(&payload)).unwrap());
+
+            // Body depth 4, execution_payload is field 9
+            assert_eq!(block.execution_branch.len(), 4);
+            assert_eq!(
+                fold_branch(block.execution_payload_root, &block.execution_branch, 9),
+                block.body_root
+            );
+        }
+    }
+}
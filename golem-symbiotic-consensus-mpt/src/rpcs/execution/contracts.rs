@@ -0,0 +1,107 @@
+use alloy_primitives::{Address, Log, B256};
+use anyhow::{anyhow, Context, Result};
+use golem_symbiotic_app_source_contract_bindings::app_src::AppSource;
+use golem_symbiotic_consensus_mpt_types::storage_layout::{StorageKey, StorageLayout};
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, env, fs};
+
+/// How the storage slots to prove for a source contract are discovered for a window.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum SourceContractSlotSource {
+    /// Depositors of `AppSource.TokensLocked` events emitted by the contract within the window,
+    /// each slot keyed by `[Address(user)]`.
+    TokensLockedEvents,
+    /// A fixed set of slots, each given by the keys consumed by the layout path
+    /// (e.g. `[[]]` for a plain slot).
+    Static(Vec<Vec<StorageKey>>),
+}
+
+/// A source contract whose account and storage slots are proven in every transition proof.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SourceContractConfig {
+    pub name: String,
+    pub address: Address,
+    pub layout: StorageLayout,
+    pub slot_source: SourceContractSlotSource,
+}
+
+pub fn get_source_contract_address() -> Result<Address> {
+    let source_state_bridge_contract_address = env::var("TOKEN_BRIDGE_ADDRESS")
+        .context("Missing TOKEN_BRIDGE_ADDRESS in environment")?
+        .parse::<Address>()
+        .context("Invalid Ethereum address format")?;
+    Ok(source_state_bridge_contract_address)
+}
+
+/// Storage layout of the source contract's proven variable, `SOURCE_CONTRACT_STORAGE_LAYOUT` (JSON encoded
+/// `StorageLayout`) or `AppSource.lockedTokens` when unset.
+pub fn get_source_contract_storage_layout() -> Result<StorageLayout> {
+    match env::var("SOURCE_CONTRACT_STORAGE_LAYOUT") {
+        Ok(layout) => serde_json::from_str::<StorageLayout>(&layout)
+            .context("Invalid SOURCE_CONTRACT_STORAGE_LAYOUT, expected a JSON encoded StorageLayout"),
+        Err(_) => Ok(StorageLayout::locked_tokens()),
+    }
+}
+
+/// Loads the set of source contracts to prove.
+///
+/// When `SOURCE_CONTRACTS_CONFIG` is set it must point to a JSON file containing a list of `SourceContractConfig`.
+/// Otherwise a single `AppSource` contract is configured from `TOKEN_BRIDGE_ADDRESS` and
+/// `SOURCE_CONTRACT_STORAGE_LAYOUT`, with its slots discovered from `TokensLocked` events.
+///
+/// Contracts are returned sorted by address, the program rejects contracts which are not strictly increasing by address.
+pub fn get_source_contracts() -> Result<Vec<SourceContractConfig>> {
+    let mut source_contracts = match env::var("SOURCE_CONTRACTS_CONFIG") {
+        Ok(path) => {
+            let contents = fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read SOURCE_CONTRACTS_CONFIG file '{}'", path))?;
+            serde_json::from_str::<Vec<SourceContractConfig>>(&contents)
+                .with_context(|| format!("Invalid SOURCE_CONTRACTS_CONFIG file '{}'", path))?
+        }
+        Err(_) => vec![SourceContractConfig {
+            name: "AppSource".to_string(),
+            address: get_source_contract_address()?,
+            layout: get_source_contract_storage_layout()?,
+            slot_source: SourceContractSlotSource::TokensLockedEvents,
+        }],
+    };
+
+    if source_contracts.is_empty() {
+        return Err(anyhow!("No source contracts configured."));
+    }
+
+    source_contracts.sort_by_key(|source_contract| source_contract.address);
+    for pair in source_contracts.windows(2) {
+        if pair[0].address == pair[1].address {
+            return Err(anyhow!(
+                "Source contract '{:?}' is configured more than once.",
+                pair[0].address
+            ));
+        }
+    }
+
+    Ok(source_contracts)
+}
+
+/// Layout keys of the depositors of `TokensLocked` events, one `[Address(user)]` per event.
+pub fn locked_token_events_to_layout_keys(
+    locked_token_event: Vec<Log<AppSource::TokensLocked>>,
+) -> Vec<Vec<StorageKey>> {
+    locked_token_event
+        .iter()
+        .map(|locked_token_event| vec![StorageKey::Address(locked_token_event.user)])
+        .collect()
+}
+
+/// Derives the storage slot key of each set of layout keys, deduplicating repeated slots.
+pub fn layout_keys_to_storage_slots(
+    layout_keys: Vec<Vec<StorageKey>>,
+    layout: &StorageLayout,
+) -> Result<HashMap<B256, Vec<StorageKey>>> {
+    let mut slot_to_layout_keys = HashMap::<B256, Vec<StorageKey>>::new();
+    for keys in layout_keys.into_iter() {
+        let slot = layout.storage_location(&keys)?;
+        slot_to_layout_keys.insert(slot, keys);
+    }
+    Ok(slot_to_layout_keys)
+}
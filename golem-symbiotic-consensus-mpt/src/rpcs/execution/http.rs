@@ -1,70 +1,111 @@
 use crate::{
     rpcs::query_with_fallback,
 };
+use super::contracts::{
+    get_source_contracts, layout_keys_to_storage_slots, locked_token_events_to_layout_keys,
+    SourceContractConfig, SourceContractSlotSource,
+};
 use alloy::{
-    eips::BlockId,
+    eips::{eip2718::Encodable2718, BlockId},
     providers::{Provider, ProviderBuilder, RootProvider},
-    rpc::types::{EIP1186AccountProofResponse, Filter},
+    rpc::types::{BlockTransactionsKind, EIP1186AccountProofResponse, Filter},
     sol_types::{SolEvent},
     transports::http::Http,
 };
-use alloy_primitives::{Address, FixedBytes, Log, B256};
+use alloy_primitives::{Address, Bytes, FixedBytes, Log, B256, KECCAK_EMPTY};
+use alloy_trie::{proof::ProofRetainer, HashBuilder, Nibbles, EMPTY_ROOT_HASH};
 use anyhow::{anyhow, Context, Result, Error};
 use futures::FutureExt;
 use helios_consensus_core::consensus_spec::ConsensusSpec;
 use log::{debug, error, warn};
+use golem_symbiotic_consensus_mpt_types::network::ConsensusSpecName;
 use golem_symbiotic_consensus_mpt_types::types::{
-    ConsensusProofInputs, ContractStorage, ProofInputs, ProofInputsWithWindow, StorageSlot,
+    ConsensusProofInputs, ContractStorage, HistoricalBlockProof, ProofInputs, ProofInputsWithWindow,
+    ReceiptLog, ReceiptProof, StorageSlot,
 };
 use golem_symbiotic_consensus_mpt_program::consensus::consensus_mpt_program;
 use reqwest::{Client, Url};
-use std::{env, marker::PhantomData};
-use tokio::time::{sleep, Duration};
-use golem_symbiotic_app_source_contract_bindings::app_src::AppSource;
 use std::{
-    collections::HashMap,
-};
-use golem_symbiotic_consensus_mpt_types::types::{    
-    get_storage_location_for_key,
-    SOURCE_CONTRACT_LOCKED_TOKENS_STORAGE_INDEX
+    collections::{BTreeSet, HashMap},
+    env,
+    marker::PhantomData,
 };
-
-pub fn get_source_contract_address() -> Result<Address> {
-    let source_state_bridge_contract_address = env::var("TOKEN_BRIDGE_ADDRESS")
-        .context("Missing TOKEN_BRIDGE_ADDRESS in environment")?
-        .parse::<Address>()
-        .context("Invalid Ethereum address format")?;
-    Ok(source_state_bridge_contract_address)
+use tokio::time::{sleep, Duration};
+use golem_symbiotic_app_source_contract_bindings::app_src::{AppSource, IEndpointV2};
+use golem_symbiotic_consensus_mpt_types::storage_layout::{StorageKey, StorageLayout};
+
+/// Optional address of the contract whose `PacketSent` logs should be proven (the LayerZero endpoint).
+pub fn get_receipt_log_emitter_address() -> Result<Option<Address>> {
+    match env::var("LAYERZERO_ENDPOINT_V2") {
+        Ok(address) => Ok(Some(
+            address
+                .parse::<Address>()
+                .context("Invalid LAYERZERO_ENDPOINT_V2 address format")?,
+        )),
+        Err(_) => Ok(None),
+    }
 }
 
-pub fn addresses_attestation_pair_to_storage_slots(
-    locked_token_event: Vec<Log<AppSource::TokensLocked>>,
-) -> HashMap<B256, Address> {
-    let mut slot_to_address_attestation = HashMap::<B256, Address>::new();
-    for locked_token_event in locked_token_event.iter() {
-        let slot = get_storage_location_for_key(
-            locked_token_event.user,
-            SOURCE_CONTRACT_LOCKED_TOKENS_STORAGE_INDEX,
-        );
-        slot_to_address_attestation.insert(slot, locked_token_event.user);
+/// Whether each slot should also be proven at the input block of the window, so that the program commits
+/// per slot deltas (`PROVE_STORAGE_DELTAS`, defaults to false).
+pub fn get_prove_storage_deltas() -> Result<bool> {
+    match env::var("PROVE_STORAGE_DELTAS") {
+        Ok(value) => value
+            .parse::<bool>()
+            .context("Failed to parse PROVE_STORAGE_DELTAS as bool"),
+        Err(_) => Ok(false),
     }
-    slot_to_address_attestation
+}
+
+/// Builds the storage multiproof of an `eth_getProof` response: the deduplicated pool of storage trie nodes and, for
+/// each entry of `storage_proof` (in order), its path from the storage root as indices into the pool.
+pub fn storage_multiproof(account_proof: &EIP1186AccountProofResponse) -> (Vec<Bytes>, Vec<Vec<u32>>) {
+    let mut nodes: Vec<Bytes> = Vec::new();
+    let mut node_indices: HashMap<Bytes, u32> = HashMap::new();
+    let paths = account_proof
+        .storage_proof
+        .iter()
+        .map(|storage_proof| {
+            storage_proof
+                .proof
+                .iter()
+                .map(|node| {
+                    *node_indices.entry(node.clone()).or_insert_with(|| {
+                        nodes.push(node.clone());
+                        (nodes.len() - 1) as u32
+                    })
+                })
+                .collect()
+        })
+        .collect();
+    (nodes, paths)
 }
 
 const CHUNK_SIZE: u64 = 100;
 const MAX_RETRIES: usize = 3;
 const RETRY_BASE_DELAY: Duration = Duration::from_secs(1);
+
+/// Receipt proofs of a block before the output block of a window, proven by the program through a historical block
+/// proof of that block.
+#[derive(Debug, Clone)]
+pub struct WindowBlockReceiptProofs {
+    pub block_number: u64,
+    pub timestamp: u64,
+    pub receipt_proofs: Vec<ReceiptProof>,
+}
 const EXECUTION_PROVIDER_TIMEOUT: Duration = Duration::from_secs(20);
 
 pub struct ExecutionHttpProxy<S: ConsensusSpec> {
     principal_provider: RootProvider<Http<Client>>,
     backup_providers: Vec<RootProvider<Http<Client>>>,
-    source_state_bridge_contract_address: Address,
+    source_contracts: Vec<SourceContractConfig>,
+    receipt_log_emitter_address: Option<Address>,
+    prove_storage_deltas: bool,
     _marker: PhantomData<S>,
     validation_timeout: Duration,
 }
 
-impl<S: ConsensusSpec> ExecutionHttpProxy<S> {
+impl<S: ConsensusSpecName> ExecutionHttpProxy<S> {
     pub fn from_env() -> Result<Self> {
         dotenv::dotenv().ok();
 
@@ -102,10 +143,14 @@ impl<S: ConsensusSpec> ExecutionHttpProxy<S> {
 
         let principal_provider = providers.remove(0);
 
-        let source_state_bridge_contract_address = get_source_contract_address()?;
+        let source_contracts = get_source_contracts()?;
+        let receipt_log_emitter_address = get_receipt_log_emitter_address()?;
+        let prove_storage_deltas = get_prove_storage_deltas()?;
 
         Ok(ExecutionHttpProxy {
-            source_state_bridge_contract_address,
+            source_contracts,
+            receipt_log_emitter_address,
+            prove_storage_deltas,
             principal_provider,
             backup_providers: providers,
             _marker: PhantomData,
@@ -117,6 +162,11 @@ impl<S: ConsensusSpec> ExecutionHttpProxy<S> {
         ExecutionHttpProxy::from_env().unwrap()
     }
 
+    /// The configured source contracts, sorted by address.
+    pub fn source_contracts(&self) -> &[SourceContractConfig] {
+        &self.source_contracts
+    }
+
     async fn _get_source_contract_event_chunk<T>(
         provider: &RootProvider<Http<Client>>,
         source_state_bridge_contract_address: &Address,
@@ -216,89 +266,393 @@ impl<S: ConsensusSpec> ExecutionHttpProxy<S> {
         }
     }
 
-    // TODO Doc string
-    async fn _prepare_consensus_mpt_proof_inputs(
+    /// Builds receipt trie proofs for every receipt in `block_number` containing a log of event `T`
+    /// emitted by `emitter_address`.
+    ///
+    /// The block receipts are fetched in full and the receipts trie is rebuilt locally (keyed by `rlp(tx_index)`)
+    /// so that proofs can be retained for the receipts of interest, as there is no `eth_getProof` equivalent
+    /// for receipts. The rebuilt root must match the block header's `receipts_root`.
+    async fn _get_receipt_proofs<T>(
         provider: &RootProvider<Http<Client>>,
-        source_state_bridge_contract_address: &Address,
+        emitter_address: &Address,
+        block_number: u64,
+    ) -> Result<Vec<ReceiptProof>>
+    where
+        T: SolEvent + 'static,
+    {
+        let receipts = provider
+            .get_block_receipts(BlockId::number(block_number))
+            .await?
+            .ok_or_else(|| anyhow!("Missing receipts for block '{}'.", block_number))?;
+
+        // Encode receipts exactly as they are stored in the receipts trie and collect the matching logs
+        let mut encoded_receipts: Vec<(u64, Vec<u8>)> = Vec::with_capacity(receipts.len());
+        let mut receipt_logs: Vec<(u64, Vec<ReceiptLog>)> = Vec::new();
+        for (tx_index, receipt) in receipts.into_iter().enumerate() {
+            let tx_index = tx_index as u64;
+            let receipt = receipt.into_primitives_receipt().inner;
+
+            let logs: Vec<ReceiptLog> = receipt
+                .logs()
+                .iter()
+                .enumerate()
+                .filter(|(_, log)| {
+                    log.address == *emitter_address
+                        && log.topics().first() == Some(&T::SIGNATURE_HASH)
+                })
+                .map(|(log_index, log)| ReceiptLog {
+                    log_index: log_index as u32,
+                    emitter: log.address,
+                    topics: log.topics().to_vec(),
+                    data: log.data.data.clone(),
+                })
+                .collect();
+
+            if !logs.is_empty() {
+                receipt_logs.push((tx_index, logs));
+            }
+            encoded_receipts.push((tx_index, receipt.encoded_2718()));
+        }
+
+        if receipt_logs.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        // Rebuild the receipts trie retaining proofs for the receipts containing matching logs
+        let targets: Vec<Nibbles> = receipt_logs
+            .iter()
+            .map(|(tx_index, _)| Nibbles::unpack(alloy::rlp::encode(*tx_index)))
+            .collect();
+        let mut leaves: Vec<(Nibbles, &Vec<u8>)> = encoded_receipts
+            .iter()
+            .map(|(tx_index, receipt)| (Nibbles::unpack(alloy::rlp::encode(*tx_index)), receipt))
+            .collect();
+        leaves.sort_by(|a, b| a.0.cmp(&b.0));
+
+        let mut hash_builder =
+            HashBuilder::default().with_proof_retainer(ProofRetainer::new(targets));
+        for (key, receipt) in leaves.iter() {
+            hash_builder.add_leaf(key.clone(), receipt);
+        }
+        let receipts_root = hash_builder.root();
+        let proof_nodes = hash_builder.take_proof_nodes();
+
+        // Check the rebuilt root against the block header, so inconsistent receipts fail here and not in the program
+        let block = provider
+            .get_block(BlockId::number(block_number), BlockTransactionsKind::Hashes)
+            .await?
+            .ok_or_else(|| anyhow!("Missing block '{}'.", block_number))?;
+        if receipts_root != block.header.receipts_root {
+            return Err(anyhow!(
+                "Rebuilt receipts root '{:?}' does not match the receipts root '{:?}' of block '{}'.",
+                receipts_root,
+                block.header.receipts_root,
+                block_number
+            ));
+        }
+
+        debug!(
+            "Rebuilt receipts root '{:?}' for block '{}' with {} receipt(s) of interest.",
+            receipts_root,
+            block_number,
+            receipt_logs.len()
+        );
+
+        let receipt_proofs = receipt_logs
+            .into_iter()
+            .map(|(tx_index, logs)| {
+                let key = Nibbles::unpack(alloy::rlp::encode(tx_index));
+                let mpt_proof: Vec<Bytes> = proof_nodes
+                    .matching_nodes_sorted(&key)
+                    .into_iter()
+                    .map(|(_, node)| node)
+                    .collect();
+                let receipt = Bytes::from(encoded_receipts[tx_index as usize].1.clone());
+                ReceiptProof {
+                    tx_index,
+                    receipt,
+                    mpt_proof,
+                    logs,
+                }
+            })
+            .collect();
+
+        Ok(receipt_proofs)
+    }
+
+    /// Numbers of the blocks within `start_block..=end_block` containing a log of event `T` emitted by
+    /// `emitter_address`, in increasing order.
+    async fn _get_event_block_numbers<T>(
+        provider: &RootProvider<Http<Client>>,
+        emitter_address: &Address,
+        start_block: u64,
+        end_block: u64,
+    ) -> Result<Vec<u64>>
+    where
+        T: SolEvent + 'static,
+    {
+        let mut block_numbers = BTreeSet::new();
+        let mut current_block = start_block;
+
+        while current_block <= end_block {
+            let chunk_end = (current_block + CHUNK_SIZE).min(end_block);
+            let filter = Filter::new()
+                .address(*emitter_address)
+                .event(T::SIGNATURE)
+                .from_block(current_block)
+                .to_block(chunk_end);
+            for log in provider.get_logs(&filter).await? {
+                block_numbers.insert(log.block_number.ok_or_else(|| {
+                    anyhow!("Log of event '{}' is missing its block number.", T::SIGNATURE)
+                })?);
+            }
+            current_block = chunk_end + 1;
+        }
+
+        Ok(block_numbers.into_iter().collect())
+    }
+
+    /// Builds the receipt proofs of the logs of event `T` emitted by `emitter_address` in each block strictly between
+    /// `input_block_number` and `output_block_number`, the output block's own receipts being proven directly.
+    async fn _get_window_receipt_proofs<T>(
+        provider: &RootProvider<Http<Client>>,
+        emitter_address: &Address,
         input_block_number: u64,
         output_block_number: u64,
-        validated_consensus_proof_inputs: ConsensusProofInputs<S>,
-    ) -> Result<ProofInputs<S>> {
-        let contract_events = Self::_get_source_contract_events::<AppSource::TokensLocked>(
+    ) -> Result<Vec<WindowBlockReceiptProofs>>
+    where
+        T: SolEvent + 'static,
+    {
+        if output_block_number <= input_block_number + 1 {
+            return Ok(Vec::new());
+        }
+
+        let block_numbers = Self::_get_event_block_numbers::<T>(
             provider,
-            source_state_bridge_contract_address,
-            input_block_number,
-            output_block_number,
+            emitter_address,
+            input_block_number + 1,
+            output_block_number - 1,
         )
         .await?;
 
-        let storage_slot_address_map = addresses_attestation_pair_to_storage_slots(contract_events);
+        let mut window_receipt_proofs = Vec::with_capacity(block_numbers.len());
+        for block_number in block_numbers {
+            let receipt_proofs =
+                Self::_get_receipt_proofs::<T>(provider, emitter_address, block_number).await?;
+            let block = provider
+                .get_block(BlockId::number(block_number), BlockTransactionsKind::Hashes)
+                .await?
+                .ok_or_else(|| anyhow!("Missing block '{}'.", block_number))?;
+            window_receipt_proofs.push(WindowBlockReceiptProofs {
+                block_number,
+                timestamp: block.header.timestamp,
+                receipt_proofs,
+            });
+        }
 
-        for (storage_slot, address) in storage_slot_address_map.iter() {
+        Ok(window_receipt_proofs)
+    }
+
+    /// Builds the account and storage slot proofs of a single source contract at `output_block_number` and,
+    /// when `prove_input_storage` is set, the proofs of the same slots at `input_block_number`.
+    ///
+    /// The slots to prove are discovered according to the contract's `slot_source`, their keys derived from
+    /// the contract's storage layout and the resulting slots sorted by slot key.
+    async fn _get_contract_storage(
+        provider: &RootProvider<Http<Client>>,
+        source_contract: &SourceContractConfig,
+        input_block_number: u64,
+        output_block_number: u64,
+        prove_input_storage: bool,
+    ) -> Result<(ContractStorage, Option<ContractStorage>)> {
+        let layout_keys = match &source_contract.slot_source {
+            SourceContractSlotSource::TokensLockedEvents => {
+                let contract_events = Self::_get_source_contract_events::<AppSource::TokensLocked>(
+                    provider,
+                    &source_contract.address,
+                    input_block_number,
+                    output_block_number,
+                )
+                .await?;
+                locked_token_events_to_layout_keys(contract_events)
+            }
+            SourceContractSlotSource::Static(layout_keys) => layout_keys.clone(),
+        };
+
+        let storage_slot_layout_keys_map =
+            layout_keys_to_storage_slots(layout_keys, &source_contract.layout)?;
+
+        for (storage_slot, layout_keys) in storage_slot_layout_keys_map.iter() {
             debug!(
-                "Storage slots obtained address '{:?}' storage_slot '{:?}'",
-                address, storage_slot
+                "Storage slots obtained for contract '{}' layout keys '{:?}' storage_slot '{:?}'",
+                source_contract.name, layout_keys, storage_slot
             );
         }
 
+        let output_contract_storage = Self::_get_contract_storage_at(
+            provider,
+            source_contract,
+            &storage_slot_layout_keys_map,
+            output_block_number,
+        )
+        .await?;
+
+        let input_contract_storage = if prove_input_storage {
+            Some(
+                Self::_get_contract_storage_at(
+                    provider,
+                    source_contract,
+                    &storage_slot_layout_keys_map,
+                    input_block_number,
+                )
+                .await?,
+            )
+        } else {
+            None
+        };
+
+        Ok((output_contract_storage, input_contract_storage))
+    }
+
+    /// Builds the account and storage slot proofs of the given slots of a source contract at `block_number`.
+    async fn _get_contract_storage_at(
+        provider: &RootProvider<Http<Client>>,
+        source_contract: &SourceContractConfig,
+        storage_slot_layout_keys_map: &HashMap<B256, Vec<StorageKey>>,
+        block_number: u64,
+    ) -> Result<ContractStorage> {
         // Get mpt proof
         let mpt_account_proof = Self::_get_proof(
             provider,
-            source_state_bridge_contract_address, //get_source_contract_address()?,
-            storage_slot_address_map.keys().cloned().collect(),
-            BlockId::number(output_block_number),
+            &source_contract.address,
+            storage_slot_layout_keys_map.keys().cloned().collect(),
+            BlockId::number(block_number),
         )
         .await?;
 
+        // Deduplicate the storage trie nodes shared by the slot proofs
+        let (storage_proof_nodes, storage_proof_paths) = storage_multiproof(&mpt_account_proof);
         debug!(
-            "mpt_account_proof {:?}",
-            serde_json::to_string(&mpt_account_proof)
+            "Storage multiproof of contract '{}' has {} nodes for {} slots",
+            source_contract.name,
+            storage_proof_nodes.len(),
+            storage_proof_paths.len()
         );
 
         let mut storage_slots: Vec<StorageSlot> = mpt_account_proof
             .storage_proof
             .iter()
-            .map(|slot| {
-                let address = storage_slot_address_map
-                    .get(&slot.key.as_b256())
-                    .copied()
-                    .expect("Missing address for storage slot");
-                StorageSlot {
-                    slot_key_address: address,
-                    key: slot.key.as_b256(),
+            .zip(storage_proof_paths)
+            .map(|(slot, mpt_proof_path)| {
+                let key = slot.key.as_b256();
+                let layout_keys = storage_slot_layout_keys_map
+                    .get(&key)
+                    .cloned()
+                    .ok_or_else(|| {
+                        anyhow!(
+                            "eth_getProof of contract '{}' returned slot '{:?}' which was not requested",
+                            source_contract.name,
+                            key
+                        )
+                    })?;
+                Ok(StorageSlot {
+                    slot_key_address: StorageLayout::leaf_address(&layout_keys),
+                    layout_keys,
+                    key,
                     expected_value: slot.value,
-                    mpt_proof: slot.proof.clone(),
-                }
+                    mpt_proof_path,
+                })
             })
-            .collect();
-
-        // Sort by address order for stability (in case rpc returns strange order)
-        //storage_slots.sort_by_key(|s| s.slot_key_address);
-        storage_slots.sort_by(|a, b| {
-            a.slot_key_address.cmp(&b.slot_key_address)
-        });
+            .collect::<Result<Vec<StorageSlot>>>()?;
+
+        // Sort by slot key, the canonical order the program requires (slot keys are unique)
+        storage_slots.sort_by_key(|slot| slot.key);
+
+        // eth_getProof reports an absent account as an empty account, which must be proven by exclusion
+        let account_absent = mpt_account_proof.nonce == 0
+            && mpt_account_proof.balance.is_zero()
+            && (mpt_account_proof.code_hash == KECCAK_EMPTY
+                || mpt_account_proof.code_hash == B256::ZERO)
+            && (mpt_account_proof.storage_hash == EMPTY_ROOT_HASH
+                || mpt_account_proof.storage_hash == B256::ZERO);
+        if account_absent {
+            warn!(
+                "Source contract '{}' ({:?}) is absent from the state trie at block {}, proving its exclusion",
+                source_contract.name, source_contract.address, block_number
+            );
+        }
 
-        let contract_storage = ContractStorage {
+        Ok(ContractStorage {
             address: mpt_account_proof.address,
-            expected_value: alloy_trie::TrieAccount {
+            expected_value: (!account_absent).then(|| alloy_trie::TrieAccount {
                 nonce: mpt_account_proof.nonce,
                 balance: mpt_account_proof.balance,
                 storage_root: mpt_account_proof.storage_hash,
                 code_hash: mpt_account_proof.code_hash,
-            },
+            }),
             mpt_proof: mpt_account_proof.account_proof,
+            layout: source_contract.layout.clone(),
+            storage_proof_nodes,
             storage_slots,
+        })
+    }
+
+    // TODO Doc string
+    async fn _prepare_consensus_mpt_proof_inputs(
+        provider: &RootProvider<Http<Client>>,
+        source_contracts: &[SourceContractConfig],
+        receipt_log_emitter_address: &Option<Address>,
+        input_block_number: u64,
+        output_block_number: u64,
+        prove_storage_deltas: bool,
+        validated_consensus_proof_inputs: ConsensusProofInputs<S>,
+        historical_blocks: Vec<HistoricalBlockProof<S>>,
+    ) -> Result<ProofInputs<S>> {
+        let mut contract_storages = Vec::with_capacity(source_contracts.len());
+        let mut input_contract_storages = Vec::with_capacity(source_contracts.len());
+        for source_contract in source_contracts.iter() {
+            let (contract_storage, input_contract_storage) = Self::_get_contract_storage(
+                provider,
+                source_contract,
+                input_block_number,
+                output_block_number,
+                prove_storage_deltas,
+            )
+            .await?;
+            contract_storages.push(contract_storage);
+            input_contract_storages.extend(input_contract_storage);
+        }
+        let input_contract_storages = prove_storage_deltas.then_some(input_contract_storages);
+
+        // Get receipt proofs for the logs emitted within the finalized output block, those of the earlier blocks of the
+        // window come with their historical block proofs
+        let receipt_proofs = match receipt_log_emitter_address {
+            Some(emitter_address) => {
+                Self::_get_receipt_proofs::<IEndpointV2::PacketSent>(
+                    provider,
+                    emitter_address,
+                    output_block_number,
+                )
+                .await?
+            }
+            None => Vec::new(),
         };
 
         let consensus_mpt_proof_input: ProofInputs<S> = ProofInputs::<S> {
             updates: validated_consensus_proof_inputs.updates,
             finality_update: validated_consensus_proof_inputs.finality_update,
+            force_update: validated_consensus_proof_inputs.force_update,
             expected_current_slot: validated_consensus_proof_inputs.expected_current_slot,
             store: validated_consensus_proof_inputs.store,
             genesis_root: validated_consensus_proof_inputs.genesis_root,
             forks: validated_consensus_proof_inputs.forks,
             store_hash: validated_consensus_proof_inputs.store_hash,
-            contract_storage,
+            min_sync_committee_participants: validated_consensus_proof_inputs
+                .min_sync_committee_participants,
+            contract_storages,
+            input_contract_storages,
+            receipt_proofs,
+            historical_blocks,
         };
 
         let consensus_mpt_proof_input_clone = consensus_mpt_proof_input.clone();
@@ -326,22 +680,29 @@ impl<S: ConsensusSpec> ExecutionHttpProxy<S> {
         input_block_number: u64,
         output_block_number: u64,
         validated_consensus_proof_inputs: ConsensusProofInputs<S>,
+        historical_blocks: Vec<HistoricalBlockProof<S>>,
         expected_output_store_hash: FixedBytes<32>
     ) -> Result<ProofInputsWithWindow<S>> {
-        let source_state_bridge_contract_address = self.source_state_bridge_contract_address;
+        let receipt_log_emitter_address = self.receipt_log_emitter_address;
+        let prove_storage_deltas = self.prove_storage_deltas;
         let output = query_with_fallback(
             &self.principal_provider,
             &self.backup_providers,
             |provider| {
                 let validated_consensus_proof_inputs = validated_consensus_proof_inputs.clone();
+                let historical_blocks = historical_blocks.clone();
+                let source_contracts = self.source_contracts.clone();
                 // use provider as the client here
                 async move {
                     Self::_prepare_consensus_mpt_proof_inputs(
                         &provider,
-                        &source_state_bridge_contract_address,
+                        &source_contracts,
+                        &receipt_log_emitter_address,
                         input_block_number,
                         output_block_number,
+                        prove_storage_deltas,
                         validated_consensus_proof_inputs,
+                        historical_blocks,
                     )
                     .await
                 }
@@ -363,15 +724,45 @@ impl<S: ConsensusSpec> ExecutionHttpProxy<S> {
         Ok(output_with_blocks)
     }
 
+    /// Receipt proofs of the `PacketSent` logs of the receipt log emitter in each block strictly between
+    /// `input_block_number` and `output_block_number`, empty when no emitter is configured.
+    pub async fn get_window_receipt_proofs(
+        &self,
+        input_block_number: u64,
+        output_block_number: u64,
+    ) -> Result<Vec<WindowBlockReceiptProofs>> {
+        let Some(emitter_address) = self.receipt_log_emitter_address else {
+            return Ok(Vec::new());
+        };
+        query_with_fallback(
+            &self.principal_provider,
+            &self.backup_providers,
+            |provider| {
+                async move {
+                    Self::_get_window_receipt_proofs::<IEndpointV2::PacketSent>(
+                        &provider,
+                        &emitter_address,
+                        input_block_number,
+                        output_block_number,
+                    )
+                    .await
+                }
+                .boxed()
+            },
+            self.validation_timeout,
+        )
+        .await
+    }
+
     pub async fn get_source_contract_events<T>(
         &self,
+        source_state_bridge_contract_address: Address,
         start_block: u64,
         end_block: u64,
     ) -> Result<Vec<Log<T>>>
     where
         T: SolEvent + Send + 'static,
     {
-        let source_state_bridge_contract_address = self.source_state_bridge_contract_address;
         query_with_fallback(
             &self.principal_provider,
             &self.backup_providers,
@@ -392,3 +783,66 @@ impl<S: ConsensusSpec> ExecutionHttpProxy<S> {
         .await
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // An `eth_getProof` response with the given storage proof nodes per slot
+    fn account_proof(storage_proofs: &[&[&str]]) -> EIP1186AccountProofResponse {
+        let storage_proof: Vec<serde_json::Value> = storage_proofs
+            .iter()
+            .enumerate()
+            .map(|(index, proof)| {
+                serde_json::json!({
+                    "key": format!("0x{:x}", index),
+                    "value": "0x1",
+                    "proof": proof,
+                })
+            })
+            .collect();
+        serde_json::from_value(serde_json::json!({
+            "address": Address::ZERO,
+            "balance": "0x0",
+            "codeHash": KECCAK_EMPTY,
+            "nonce": "0x0",
+            "storageHash": B256::ZERO,
+            "accountProof": [],
+            "storageProof": storage_proof,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn storage_multiproof_shares_common_nodes() {
+        let account_proof = account_proof(&[
+            &["0xaa", "0xbb", "0xcc"],
+            &["0xaa", "0xbb", "0xdd"],
+            &["0xaa", "0xee"],
+        ]);
+        let (nodes, paths) = storage_multiproof(&account_proof);
+
+        let node = |hex: &str| hex.parse::<Bytes>().unwrap();
+        assert_eq!(
+            nodes,
+            vec![node("0xaa"), node("0xbb"), node("0xcc"), node("0xdd"), node("0xee")]
+        );
+        assert_eq!(paths, vec![vec![0, 1, 2], vec![0, 1, 3], vec![0, 4]]);
+
+        // Each path resolves to the original proof
+        for (storage_proof, path) in account_proof.storage_proof.iter().zip(paths.iter()) {
+            let resolved: Vec<Bytes> = path
+                .iter()
+                .map(|index| nodes[*index as usize].clone())
+                .collect();
+            assert_eq!(resolved, storage_proof.proof);
+        }
+    }
+
+    #[test]
+    fn storage_multiproof_of_no_slots_is_empty() {
+        let (nodes, paths) = storage_multiproof(&account_proof(&[]));
+        assert!(nodes.is_empty());
+        assert!(paths.is_empty());
+    }
+}
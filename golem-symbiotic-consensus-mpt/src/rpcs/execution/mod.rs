@@ -1,2 +1,3 @@
 //pub mod ws;
-pub mod http;
\ No newline at end of file
+pub mod http;
+pub mod contracts;
\ No newline at end of file
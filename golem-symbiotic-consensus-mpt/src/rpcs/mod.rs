@@ -1,9 +1,10 @@
 use tokio::time::{timeout, Duration};
 use anyhow::{anyhow, Result};
 use futures::{
-    future::{select_ok, BoxFuture},
+    future::{join_all, select_ok, BoxFuture},
 };
 use log::warn;
+use std::fmt::Debug;
 
 pub mod execution;
 pub mod consensus;
@@ -88,4 +89,107 @@ where
         Ok((result, _)) => Ok(result),
         Err(e) => Err(anyhow!("All multiplexed requests failed: {}", e)),
     }
-}
\ No newline at end of file
+}
+
+/// Takes a closure that produces a future for each provided client, runs them all concurrently (each with a timeout)
+/// and returns the result at least `min_agreeing` of the providers agree on.
+/// Returns an error if no result reaches the quorum.
+pub async fn quorum<F, P, R>(
+    f: F,
+    providers: &[P],
+    min_agreeing: usize,
+    timeout_duration: Duration,
+) -> Result<R>
+where
+    F: Fn(P) -> BoxFuture<'static, Result<R>>,
+    P: Clone,
+    R: PartialEq + Debug + 'static,
+{
+    if min_agreeing == 0 || min_agreeing > providers.len() {
+        return Err(anyhow!(
+            "A quorum of {} cannot be reached with {} providers.",
+            min_agreeing,
+            providers.len()
+        ));
+    }
+
+    let futs = providers.iter().map(|client| {
+        let fut = f(client.clone());
+        Box::pin(async move {
+            timeout(timeout_duration, fut)
+                .await
+                .map_err(|_| anyhow!("Provider timed out after {:?}", timeout_duration))?
+        }) as BoxFuture<'static, Result<R>>
+    });
+
+    // Tally the distinct results
+    let mut tallies: Vec<(R, usize)> = Vec::new();
+    for (index, result) in join_all(futs).await.into_iter().enumerate() {
+        match result {
+            Ok(result) => match tallies.iter_mut().find(|(value, _)| *value == result) {
+                Some((_, count)) => *count += 1,
+                None => tallies.push((result, 1)),
+            },
+            Err(err) => warn!("Provider {} failed: {}", index, err),
+        }
+    }
+
+    match tallies.into_iter().max_by_key(|(_, count)| *count) {
+        Some((result, count)) if count >= min_agreeing => Ok(result),
+        Some((result, count)) => Err(anyhow!(
+            "No quorum, at most {} of {} providers agree (on {:?}), {} required.",
+            count,
+            providers.len(),
+            result,
+            min_agreeing
+        )),
+        None => Err(anyhow!("All {} quorum requests failed.", providers.len())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::FutureExt;
+
+    // Providers are the values they answer with, 0 fails and u64::MAX never answers
+    fn answer(provider: u64) -> BoxFuture<'static, Result<u64>> {
+        async move {
+            match provider {
+                0 => Err(anyhow!("provider down")),
+                u64::MAX => {
+                    tokio::time::sleep(Duration::from_secs(60)).await;
+                    Ok(provider)
+                }
+                _ => Ok(provider),
+            }
+        }
+        .boxed()
+    }
+
+    #[tokio::test]
+    async fn quorum_returns_the_value_enough_providers_agree_on() {
+        let result = quorum(answer, &[7, 7, 8, 0], 2, Duration::from_secs(1)).await;
+        assert_eq!(result.unwrap(), 7);
+    }
+
+    #[tokio::test]
+    async fn quorum_fails_when_too_few_providers_agree() {
+        assert!(quorum(answer, &[7, 8, 9], 2, Duration::from_secs(1)).await.is_err());
+        assert!(quorum(answer, &[7, 0, 0], 2, Duration::from_secs(1)).await.is_err());
+        assert!(quorum(answer, &[0, 0], 1, Duration::from_secs(1)).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn quorum_counts_timed_out_providers_as_failed() {
+        let providers = [7, u64::MAX, u64::MAX];
+        assert!(quorum(answer, &providers, 2, Duration::from_millis(50)).await.is_err());
+        assert_eq!(quorum(answer, &providers, 1, Duration::from_millis(50)).await.unwrap(), 7);
+    }
+
+    #[tokio::test]
+    async fn quorum_rejects_an_unreachable_threshold() {
+        assert!(quorum(answer, &[7, 7], 0, Duration::from_secs(1)).await.is_err());
+        assert!(quorum(answer, &[7, 7], 3, Duration::from_secs(1)).await.is_err());
+    }
+}
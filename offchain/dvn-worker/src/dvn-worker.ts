@@ -24,7 +24,7 @@ export class DvnWorker {
     
     // DVN contract ABI (simplified)
     const dvnAbi = [
-      'function submitVerification(bytes32 packetId, bytes32 H, uint48 epoch, bytes calldata relayProof, bytes calldata zkProof, tuple(uint64 slot, bytes32 blockHash, bytes32 receiptsRoot, address emitter, bytes32 topicsHash, uint32 logIndex, uint64 minFinality) zkInputs)',
+      'function submitVerification(bytes32 packetId, bytes32 H, uint48 epoch, bytes calldata relayProof, bytes calldata zkProof, tuple(uint64 slot, bytes32 beaconBlockRoot, bytes32 blockHash, uint64 blockNumber, bytes32 receiptsRoot, address emitter, bytes32 topicsHash, uint32 logIndex, uint64 minFinality) zkInputs)',
       'function verified(bytes32) view returns (bool)',
       'event Verified(bytes32 indexed packetId, bytes32 H)',
       'event Submitted(bytes32 indexed packetId)'
@@ -13,7 +13,9 @@ export interface PacketSentEvent {
 
 export interface ZkInputs {
   slot: bigint;
+  beaconBlockRoot: string;
   blockHash: string;
+  blockNumber: bigint;
   receiptsRoot: string;
   emitter: string;
   topicsHash: string;
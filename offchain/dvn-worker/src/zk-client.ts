@@ -37,7 +37,9 @@ export class ZkClient {
 			proof: "0x" + "00".repeat(128), // Stub proof
 			inputs: {
 				slot: BigInt(1000), // Stub slot
+				beaconBlockRoot: "0x" + "00".repeat(32),
 				blockHash: "0x" + "00".repeat(32),
+				blockNumber: BigInt(0),
 				receiptsRoot: "0x" + "00".repeat(32),
 				emitter: "0x" + "00".repeat(20),
 				topicsHash: "0x" + "00".repeat(32),
@@ -1,17 +1,38 @@
 // SPDX-License-Identifier: MIT
 pragma solidity ^0.8.25;
 
+// Public values committed by the proofs, decoded by implementations of this interface.
+import {ZkEthStatePublicValues} from "./ZkEthStatePublicValues.sol";
+
 interface IZkEthStateVerifier {
     struct Inputs {
-        uint64 slot;          // finalized beacon slot
-        bytes32 blockHash;    // ETH block containing the log
-        bytes32 receiptsRoot; // receipts root of that block
-        address emitter;      // LZ Endpoint/MessageLib address on ETH
-        bytes32 topicsHash;   // keccak256(abi.encodePacked(topics))
-        uint32 logIndex;      // index in the receipt
-        uint64 minFinality;   // slots/confirmations threshold
+        uint64 slot;             // finalized beacon slot
+        bytes32 beaconBlockRoot; // finalized beacon block root
+        bytes32 blockHash;       // ETH block containing the log
+        uint64 blockNumber;      // number of that block
+        bytes32 receiptsRoot;    // receipts root of that block
+        address emitter;         // LZ Endpoint/MessageLib address on ETH
+        bytes32 topicsHash;      // keccak256(abi.encodePacked(topics))
+        uint32 logIndex;         // index in the receipt
+        uint64 minFinality;      // slots/confirmations threshold
     }
-    
+
+    /// @dev Implementations must check the inputs against the proof's committed public values
+    /// (see `ZkEthStateInputs.bindsTo`).
     function verifySourceEvent(bytes calldata proof, Inputs calldata publicInputs)
         external view returns (bool);
-}
\ No newline at end of file
+}
+
+library ZkEthStateInputs {
+    /// @notice Whether `inputs` refer to the finalized beacon block and execution block committed by a transition proof.
+    function bindsTo(
+        IZkEthStateVerifier.Inputs memory inputs,
+        ZkEthStatePublicValues.TransitionPublicValues memory publicValues
+    ) internal pure returns (bool) {
+        return inputs.slot == publicValues.outputSlot
+            && inputs.beaconBlockRoot == publicValues.beaconBlockRoot
+            && inputs.blockHash == publicValues.executionBlockHash
+            && inputs.blockNumber == publicValues.executionBlockNumber
+            && inputs.receiptsRoot == publicValues.receiptsRoot;
+    }
+}
@@ -0,0 +1,124 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.25;
+
+/// @notice Public values committed by the zk Ethereum state programs.
+/// @dev This file is the single source of truth for the committed public values: the Rust types are generated
+/// from it with `alloy_sol_types::sol!` (see golem-symbiotic-consensus-mpt-types/src/public_values.rs).
+/// The committed bytes are `abi.encode(PublicValuesEnvelope)` where `payload` is the `abi.encode` of one of the
+/// structs below, at the `version` expected by the verifier. Any change to a released struct requires a version bump.
+/// `networkIdentityDigest` binds a proof to the source network (genesis root, fork schedule and consensus spec),
+/// verifiers must compare it with the digest of the network they expect.
+/// `syncCommitteeParticipants` is the number of sync committee members that signed the finality update (the lowest
+/// of any aggregated transition) and `minSyncCommitteeParticipants` the minimum the program enforced, verifiers may
+/// apply a stricter policy.
+/// `forcedUpdate` marks a store advanced by a force update without finality (the head is an attested, not a
+/// finalized header) once finality stalled for a sync committee period, verifiers requiring finality must reject it.
+/// `programVersion` is the version of the program logic (bumped with its vkey on a new fork or a fix). An aggregation
+/// with a non zero `previousAggregationVkey` migrates the store hash chain from the previous version: it recursively
+/// verified an aggregation proof of that vkey ending at its `inputSlot` and `inputStoreHash`.
+/// The program only commits `previousAggregationVkey`, any aggregation program could be named there. Verifiers must
+/// pin both the current aggregation vkey (the one the proof is verified with) and the previous one, and reject a non
+/// zero `previousAggregationVkey` other than the pinned previous vkey, or a migration could start the chain from the
+/// proof of an arbitrary program.
+library ZkEthStatePublicValues {
+    struct PublicValuesEnvelope {
+        uint16 version;
+        bytes payload;
+    }
+
+    /// @notice Leading fields of the transition, rotation and aggregation public values, frozen across versions so
+    /// that a store hash chain can be read from public values of any version (used to migrate between versions).
+    struct ChainHead {
+        bytes32 networkIdentityDigest;
+        uint64 inputSlot;
+        bytes32 inputStoreHash;
+        uint64 outputSlot;
+        bytes32 outputStoreHash;
+    }
+
+    /// @notice Public values of the consensus MPT transition program (version 1).
+    struct TransitionPublicValues {
+        bytes32 networkIdentityDigest;
+        uint64 inputSlot;
+        bytes32 inputStoreHash;
+        uint64 outputSlot;
+        bytes32 outputStoreHash;
+        bytes32 beaconBlockRoot;
+        bytes32 executionBlockHash;
+        uint64 executionBlockNumber;
+        uint64 executionTimestamp;
+        bytes32 executionStateRoot;
+        bytes32 receiptsRoot;
+        bytes32 verifiedContractStorageSlotsRoot; // contracts by address, each contract's slots by derived slot key
+        bytes32 contractStorageLayoutsHash;
+        bytes32 verifiedReceiptLogsRoot;
+        bytes32 verifiedContractStorageDeltasRoot;
+        bytes32 verifiedHistoricalBlocksRoot;
+        bytes32 nextSyncCommitteeHash;
+        uint64 syncCommitteeParticipants;
+        uint64 minSyncCommitteeParticipants;
+        bool forcedUpdate;
+        uint32 programVersion;
+    }
+
+    /// @notice Public values of the consensus only program (version 1).
+    struct ConsensusPublicValues {
+        bytes32 networkIdentityDigest;
+        uint64 inputSlot;
+        bytes32 inputStoreHash;
+        uint64 outputSlot;
+        bytes32 outputStoreHash;
+        bytes32 executionStateRoot;
+        bytes32 nextSyncCommitteeHash;
+        bool forcedUpdate;
+        uint32 programVersion;
+    }
+
+    /// @notice Public values of the bootstrap program (version 1).
+    /// @dev Proves the store hash the whole proof chain starts from: `checkpoint` is the weak subjectivity
+    /// checkpoint (beacon block root) the store was bootstrapped from, verifiers must compare it with a pinned root.
+    struct BootstrapPublicValues {
+        bytes32 networkIdentityDigest;
+        bytes32 checkpoint;
+        uint64 outputSlot;
+        bytes32 outputStoreHash;
+        bytes32 currentSyncCommitteeHash;
+        bytes32 nextSyncCommitteeHash;
+        uint32 programVersion;
+    }
+
+    /// @notice Public values of the sync committee rotation program (version 1).
+    /// @dev Carries no execution state: it only keeps the sync committee chain (and store hash chain) alive while
+    /// finality is stalled.
+    struct RotationPublicValues {
+        bytes32 networkIdentityDigest;
+        uint64 inputSlot;
+        bytes32 inputStoreHash;
+        uint64 outputSlot;
+        bytes32 outputStoreHash;
+        bytes32 currentSyncCommitteeHash;
+        bytes32 nextSyncCommitteeHash;
+        uint64 nUpdates;
+        uint32 programVersion;
+    }
+
+    /// @notice Public values of the aggregation program (version 1).
+    /// @dev `previousAggregationVkey` must be checked against the pinned previous aggregation vkey when non zero.
+    struct AggregationPublicValues {
+        bytes32 networkIdentityDigest;
+        uint64 inputSlot;
+        bytes32 inputStoreHash;
+        uint64 outputSlot;
+        bytes32 outputStoreHash;
+        bytes32 transitionVkey;
+        bytes32 rotationVkey;
+        bytes32 executionStateRootsHash;
+        bytes32 transitionOutputsHash;
+        uint64 nTransitions;
+        uint64 syncCommitteeParticipants;
+        uint64 minSyncCommitteeParticipants;
+        bool forcedUpdate;
+        uint32 programVersion;
+        bytes32 previousAggregationVkey;
+    }
+}
@@ -91,7 +91,9 @@ contract DvnE2ETest is Test {
         bytes memory zkProof = new bytes(128); // Mock proof
         IZkEthStateVerifier.Inputs memory zkInputs = IZkEthStateVerifier.Inputs({
             slot: 1000,
+            beaconBlockRoot: bytes32(uint256(0x123)),
             blockHash: srcBlockHash,
+            blockNumber: 100,
             receiptsRoot: bytes32(uint256(0x456)),
             emitter: emitter,
             topicsHash: keccak256(abi.encodePacked("PacketSent")),